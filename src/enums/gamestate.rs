@@ -5,6 +5,7 @@ pub enum GameState {
     Lair,
     Playing,
     Paused,
+    LevelUp,
     Dead,
     Quitting,
 }
@@ -15,6 +16,7 @@ impl GameState {
             GameState::Lair => { false }
             GameState::Playing => {true }
             GameState::Paused => { false }
+            GameState::LevelUp => { false }
             GameState::Dead => { false }
             GameState::Quitting => { false }
         }
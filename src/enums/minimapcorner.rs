@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MinimapCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl MinimapCorner {
+    pub const ALL: [MinimapCorner; 4] = [MinimapCorner::TopLeft, MinimapCorner::TopRight, MinimapCorner::BottomLeft, MinimapCorner::BottomRight];
+
+    pub fn default() -> Self {
+        MinimapCorner::TopRight
+    }
+}
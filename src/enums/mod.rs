@@ -1,3 +1,5 @@
 pub mod gametab;
 pub mod numberformatmode;
-pub mod gamestate;
\ No newline at end of file
+pub mod gamestate;
+pub mod loglevel;
+pub mod minimapcorner;
\ No newline at end of file
@@ -1,25 +1,44 @@
+use crate::game::collision::spatial_hash_grid::SpatialHashGrid;
 use crate::game::data::game_data::GameData;
 use crate::game::maths::pos_2::{normalize_i64_upscaled, project_onto_i64, Pos2FixedPoint, FIXED_POINT_SCALE, INVALID_POS2};
-use crate::game::objects::game_object::{add_units, remove_units, GameObject};
+use crate::game::objects::game_object::remove_units;
 use crate::game::objects::object_shape::ObjectShape;
 use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_store::UnitStore;
 use rayon::iter::*;
 use rayon::slice::ParallelSliceMut;
 
-use crate::game::data::damage_numbers::DamageNumber;
+use crate::game::events::game_event::GameEvent;
 use crate::game::map::game_map::GameMap;
 use crate::game::maths::integers::int_sqrt_64;
 use crate::game::objects::attacks::attack_defaults::get_modified_attack;
 use crate::game::objects::attacks::attack_landed::AttackLanded;
 use crate::game::objects::attacks::attack_stats::{AttackName, AttackStats};
-use crate::game::objects::loot::Loot;
-use crate::game::resources::loot::collect_loot;
+use crate::game::objects::attacks::targeting::{nearest_target, TargetingContext};
+use crate::game::objects::damage::resolve_damage;
+use crate::game::objects::elite_affix::leech;
+use crate::game::objects::status_effect::can_attack;
 use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
-use egui::Color32;
+use crate::helper::logging::COLLISION;
+use rand::Rng;
 use rustc_hash::FxHashSet;
 use smallvec::SmallVec;
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use tracing::trace;
+
+// A player attack overlapping a breakable tile, applied once the parallel pass is done
+struct TileHit {
+    attack_id: u32,
+    tile: (usize, usize),
+    damage: f64,
+}
+
+// An attack that hit a wall and continues in a new direction
+struct AttackBounce {
+    attack_id: u32,
+    direction: (f32, f32),
+}
 
 pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2FixedPoint)], game_data: Arc<GameData>, delta_time: f64) -> Vec<(AttackName, Pos2FixedPoint, u32)> {
     let collectables_collected = Arc::new(Mutex::new(Vec::new()));
@@ -27,10 +46,10 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
     let mut attacks_to_spawn: Vec<(AttackName, Pos2FixedPoint, u32)> = Vec::new();
 
     {
-        let mut units = acquire_lock_mut(&game_data.units, "objects");
+        let mut units = acquire_lock_mut(&game_data.units, "game_units");
         let unit_positions = acquire_lock_mut(&game_data.unit_positions, "unit_positions");
-        let spatial_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "spatial_grid");
-        let game_map = acquire_lock_mut(&game_data.game_map, "game_map");
+        let spatial_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "spatial_hash_grid");
+        let mut game_map = acquire_lock_mut(&game_data.game_map, "game_map");
 
         let player_id = acquire_lock_mut(&game_data.player_id, "player_id");
         let player_position = player_id
@@ -42,6 +61,8 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
         let chunk_size = ((unit_positions_updates.len() / rayon::current_num_threads()).max(1)).max(1);
 
         let mut attack_hits_to_process = Arc::new(Mutex::new(Vec::default()));
+        let tile_hits_to_process: Mutex<Vec<TileHit>> = Mutex::new(Vec::new());
+        let bounced_attacks: Mutex<Vec<AttackBounce>> = Mutex::new(Vec::new());
 
         attacks_to_spawn = unit_positions_updates
             .par_chunks_mut(chunk_size)
@@ -50,12 +71,13 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                 let mut local_attacks_to_spawn = Vec::new();
 
                 for (unit_id, old_position, new_position) in chunk {
-                    let Some(unit) = units.get(*unit_id as usize).and_then(|u| u.as_ref()) else { continue; };
+                    let Some(object_type) = units.object_type(*unit_id) else { continue; };
+                    let unit_index = *unit_id as usize;
 
-                    match unit.object_type {
+                    match object_type {
                         ObjectType::Player => {
                             if let Some(game_map) = game_map.as_ref() {
-                                handle_terrain(new_position, old_position, &unit.object_shape, game_map, tile_size);
+                                handle_terrain(new_position, old_position, &units.shapes[unit_index], game_map, tile_size);
                             }
                             continue
                         },
@@ -63,43 +85,61 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                             if player_position == INVALID_POS2 {
                                 continue;
                             }
-                            let collectable_shape = &unit.object_shape;
-                            if rectangles_collide(*new_position, collectable_shape, player_position, &unit.object_shape) {
+                            let collectable_shape = &units.shapes[unit_index];
+                            if rectangles_collide(*new_position, collectable_shape, player_position, collectable_shape) {
                                 collectables_collected.lock().unwrap().push(*unit_id);
                             }
                         },
                         ObjectType::Attack => {
-                            if let Some(attack_stats) = &unit.attack_stats {
+                            if let Some(attack_stats) = &units.attack_stats[unit_index] {
+                                if let Some(game_map) = game_map.as_ref().filter(|_| attack_stats.bounces > 0) {
+                                    if let Some(direction) = bounce_off_terrain(new_position, old_position, &units.shapes[unit_index], game_map, tile_size, attack_stats.direction) {
+                                        bounced_attacks.lock().unwrap().push(AttackBounce { attack_id: *unit_id, direction });
+                                    }
+                                }
+
                                 if is_in_damage_window(&attack_stats, delta_time) {
-                                    let attack = unit;
-                                    let attack_id = unit.id;
+                                    let attack_id = *unit_id;
                                     let attack_pos = unit_positions[attack_id as usize];
+                                    let parent_unit_id = units.parent_ids[unit_index];
 
                                     let attack_shape =  if attack_stats.use_parent_shape {
-                                        if let Some(parent_id) = unit.parent_unit_id {
-                                            if let Some(Some(parent)) = units.get(parent_id as usize) {
-                                                parent.object_shape.clone()
-                                            }  else {
-                                                ObjectShape::new(0,0)
-                                            }
+                                        if let Some(parent_id) = parent_unit_id.filter(|&id| units.is_alive(id)) {
+                                            units.shapes[parent_id as usize].scaled(attack_stats.area_multiplier)
                                         } else {
                                             ObjectShape::new(0, 0)
                                         }
                                     } else {
-                                        unit.object_shape.clone()
+                                        units.shapes[unit_index].clone()
                                     };
 
+                                    // Only the player's attacks break tiles
+                                    if let Some(game_map) = game_map.as_ref() {
+                                        if parent_unit_id.and_then(|id| units.object_type(id)) == Some(ObjectType::Player) {
+                                            let breakable_tiles = breakable_tiles_hit(attack_pos, &attack_shape, game_map, &attack_stats.tiles_hit);
+                                            if !breakable_tiles.is_empty() {
+                                                let mut tile_hits = tile_hits_to_process.lock().unwrap();
+                                                tile_hits.extend(breakable_tiles.into_iter().map(|tile| TileHit { attack_id, tile, damage: attack_stats.damage }));
+                                            }
+                                        }
+                                    }
+
                                     spatial_grid.get_nearby_units_into(*new_position, &mut nearby_unit_ids);
                                     for &nearby_unit_id in &nearby_unit_ids {
-                                        let Some(nearby_unit) = units.get(nearby_unit_id as usize).and_then(|u| u.as_ref()) else { continue; };
+                                        let Some(nearby_type) = units.object_type(nearby_unit_id) else { continue; };
 
                                         // Attacks don't hit collectables or other attacks
-                                        if nearby_unit.object_type == ObjectType::Collectable || nearby_unit.object_type == ObjectType::Attack {
+                                        if nearby_type == ObjectType::Collectable || nearby_type == ObjectType::Attack {
                                             continue
                                         }
 
                                         // Attacks don't hit their parents
-                                        if Some(nearby_unit_id) == attack.parent_unit_id {
+                                        if Some(nearby_unit_id) == parent_unit_id {
+                                            continue;
+                                        }
+
+                                        // Or units on their parent's side
+                                        if parent_unit_id.and_then(|id| units.object_type(id)) == Some(nearby_type) {
                                             continue;
                                         }
 
@@ -109,11 +149,12 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                                         }
 
                                         let nearby_unit_pos = unit_positions[nearby_unit_id as usize];
-                                        if rectangles_collide(attack_pos, &attack_shape, nearby_unit_pos, &nearby_unit.object_shape) {
+                                        if rectangles_collide(attack_pos, &attack_shape, nearby_unit_pos, &units.shapes[nearby_unit_id as usize]) {
                                             let attack_to_process = AttackLanded {
-                                                attack_id: attack.id,
+                                                attack_id,
                                                 target_id: nearby_unit_id,
                                                 damage: attack_stats.damage,
+                                                chain_depth: 0,
                                             };
                                             attack_hits_to_process.lock().unwrap().push(attack_to_process);
                                         }
@@ -122,7 +163,7 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                             }
                         },
                         ObjectType::Enemy => {
-                            let unit_shape = &unit.object_shape;
+                            let unit_shape = &units.shapes[unit_index];
                             spatial_grid.get_nearby_units_into(*new_position, &mut nearby_unit_ids);
 
                             let mut collision_normals = Vec::new();
@@ -134,12 +175,12 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                                     continue;
                                 }
 
-                                let Some(other_unit) = units.get(other_unit_id as usize).and_then(|u| u.as_ref()) else { continue; };
+                                let Some(other_type) = units.object_type(other_unit_id) else { continue; };
 
-                                if other_unit.object_type == ObjectType::Collectable { continue; }
-                                if other_unit.object_type == ObjectType::Attack { continue; }
+                                if other_type == ObjectType::Collectable { continue; }
+                                if other_type == ObjectType::Attack { continue; }
 
-                                let other_unit_shape = &other_unit.object_shape;
+                                let other_unit_shape = &units.shapes[other_unit_id as usize];
                                 let other_unit_pos = unit_positions[other_unit_id as usize];
 
                                 if rectangles_collide(*new_position, unit_shape, other_unit_pos, other_unit_shape) {
@@ -147,20 +188,20 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                                     collision_normals.push(collision_normal);
                                     nearby_positions.push(other_unit_pos);
 
-                                    if other_unit.object_type == ObjectType::Player {
+                                    if other_type == ObjectType::Player {
                                         collided_with_player = true;
                                     }
                                 }
                             }
 
                             // Collided with player, handle proximity attack:
-                            if collided_with_player {
-                                for (attack_name, cooldown) in unit.attack_cooldowns.iter() {
+                            if collided_with_player && can_attack(&units.status_effects[unit_index]) {
+                                for (attack_name, cooldown) in units.attack_cooldowns[unit_index].iter() {
                                     if *cooldown > 0.0 {
                                         continue;
                                     }
 
-                                    let attack = get_modified_attack(&unit.upgrades, attack_name.clone());
+                                    let attack = get_modified_attack(&units.upgrades[unit_index], attack_name.clone());
                                     if let Some(attack_stats) = attack.attack_stats {
                                         if attack_stats.proximity_attack {
                                             local_attacks_to_spawn.push((attack_name.clone(), *new_position, *unit_id));
@@ -193,7 +234,7 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                             }
 
                             if let Some(game_map) = game_map.as_ref() {
-                                handle_terrain(new_position, old_position, &unit.object_shape, game_map, tile_size);
+                                handle_terrain(new_position, old_position, unit_shape, game_map, tile_size);
                             }
                         }
                     }
@@ -208,8 +249,48 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                 },
             );
 
-        let mut damage_numbers = acquire_lock_mut(&game_data.damage_numbers, "damage_numbers");
-        for attack_to_process in attack_hits_to_process.lock().unwrap().iter() {
+        let mut events = acquire_lock_mut(&game_data.events, "events");
+
+        for AttackBounce { attack_id, direction } in bounced_attacks.into_inner().unwrap() {
+            if let Some(attack_stats) = units.attack_stats[attack_id as usize].as_mut() {
+                attack_stats.direction = direction;
+                attack_stats.bounces -= 1;
+            }
+        }
+
+        // Hits only add to the damage table, the shared map is copied once on a tick where something breaks
+        let tile_hits = tile_hits_to_process.into_inner().unwrap();
+        if let Some(game_map) = game_map.as_mut().filter(|_| !tile_hits.is_empty()) {
+            let mut tile_damage = acquire_lock_mut(&game_data.tile_damage, "tile_damage");
+            let mut broken_tiles = Vec::new();
+
+            for TileHit { attack_id, tile, damage } in tile_hits {
+                let Some(attack_stats) = units.attack_stats[attack_id as usize].as_mut() else { continue; };
+                if attack_stats.tiles_hit.contains(&tile) {
+                    continue;
+                }
+                attack_stats.tiles_hit.push(tile);
+
+                if let Some(broken_tile) = tile_damage.hit(game_map, tile, damage as f32) {
+                    broken_tiles.push((tile, broken_tile));
+                }
+            }
+
+            if !broken_tiles.is_empty() {
+                let game_map = Arc::make_mut(game_map);
+                for ((tile_x, tile_y), broken_tile) in broken_tiles {
+                    game_map.break_tile(tile_x, tile_y);
+                    events.publish(GameEvent::TileChanged {
+                        position: game_map.tile_center((tile_x, tile_y)),
+                        loot: broken_tile.broken_loot(),
+                    });
+                }
+            }
+        }
+
+        // Chained hits are queued behind the direct ones
+        let mut pending_hits: VecDeque<AttackLanded> = attack_hits_to_process.lock().unwrap().drain(..).collect();
+        while let Some(attack_to_process) = pending_hits.pop_front() {
             let attack_id = attack_to_process.attack_id as usize;
             let target_id = attack_to_process.target_id as usize;
 
@@ -217,58 +298,76 @@ pub fn handle_collision(unit_positions_updates: &mut [(u32, Pos2FixedPoint, Pos2
                 continue;
             }
 
-            let (attack, target) = if attack_id < target_id {
-                let (low, high) = units.split_at_mut(target_id);
-                (low.get_mut(attack_id), high.get_mut(0))
-            } else {
-                let (low, high) = units.split_at_mut(attack_id);
-                (high.get_mut(0), low.get_mut(target_id))
-            };
-
-            if let (Some(Some(attack)), Some(Some(target))) = (attack, target) {
-                if let Some(attack_stats) = attack.attack_stats.as_mut() {
-                    if attack_stats.hit_count < attack_stats.max_targets {
-                        let is_dead = target.apply_damage(attack_to_process.damage);
-                        attack_stats.units_hit.push(attack_to_process.target_id);
-                        attack_stats.hit_count += 1;
-
-                        if is_dead {
-                            units_to_remove.insert(attack_to_process.target_id);
-                        } else {
-                            if let Some(target_pos) = unit_positions.get(target_id) {
-                                let damage_number = DamageNumber {
-                                    position: target_pos.clone(),
-                                    value: attack_stats.damage,
-                                    spawn_time: Instant::now(),
-                                    colour: Color32::RED,
-                                };
-                                damage_numbers.push(damage_number);
-                            }
-                        }
-                    }
+            if !units.is_alive(attack_to_process.attack_id) || !units.is_alive(attack_to_process.target_id) {
+                continue;
+            }
+
+            let Some(attack_stats) = units.attack_stats[attack_id].as_ref() else { continue; };
+            if attack_to_process.chain_depth == 0 && attack_stats.hit_count >= attack_stats.target_limit() {
+                continue;
+            }
+            if attack_stats.units_hit.contains(&attack_to_process.target_id) {
+                continue;
+            }
+            let mut rng = rand::rng();
+            let status_effect = attack_stats.status_effect.filter(|_| rng.random_bool(attack_stats.status_chance.clamp(0.0, 1.0) as f64));
+            let damage = resolve_damage(&units, attack_to_process.target_id, attack_stats.hit(attack_to_process.damage), &mut rng);
+
+            let is_dead = units.apply_damage(attack_to_process.target_id, damage.amount);
+            if let Some(source_id) = units.parent_ids[attack_id] {
+                leech(&mut units, source_id, damage.amount);
+            }
+            if let Some(attack_stats) = units.attack_stats[attack_id].as_mut() {
+                attack_stats.units_hit.push(attack_to_process.target_id);
+                if attack_to_process.chain_depth == 0 {
+                    attack_stats.hit_count += 1;
                 }
             }
+
+            if is_dead {
+                units_to_remove.insert(attack_to_process.target_id);
+            } else if let Some(status_effect) = status_effect {
+                units.apply_status_effect(attack_to_process.target_id, status_effect);
+            }
+
+            if let Some(chained_hit) = next_chain_hit(&attack_to_process, &units, &unit_positions, &spatial_grid, &units_to_remove) {
+                events.publish(GameEvent::ChainJumped {
+                    from: unit_positions[target_id],
+                    to: unit_positions[chained_hit.target_id as usize],
+                });
+                pending_hits.push_back(chained_hit);
+            }
+
+            if let (Some(target_type), Some(target_pos)) = (units.object_type(attack_to_process.target_id), unit_positions.get(target_id)) {
+                events.publish(GameEvent::DamageDealt {
+                    target_type,
+                    position: *target_pos,
+                    amount: damage.amount,
+                    damage_type: damage.damage_type,
+                    critical: damage.critical,
+                    killed: is_dead,
+                });
+            }
         }
     }
 
     let collected_items = collectables_collected.lock().unwrap();
-    let units = acquire_lock(&game_data.units, "");
-    let collected_loot: Vec<Loot> = collected_items.iter()
-        .filter_map(|&i| units.get(i as usize)?.as_ref()?.loot.clone()) // Unwrap Option<Unit> before accessing loot
+    let units = acquire_lock(&game_data.units, "game_units");
+    let collected_loot: Vec<GameEvent> = collected_items.iter()
+        .filter_map(|&i| Some(GameEvent::LootCollected { loot: units.loot.get(i as usize)?.clone()? }))
         .collect();
     drop(units);
 
     if !collected_items.is_empty() {
+        acquire_lock_mut(&game_data.events, "events").publish_all(collected_loot);
         remove_units(collected_items.clone(), Arc::clone(&game_data));
-        collect_loot(collected_loot, Arc::clone(&game_data));
     }
 
-    let mut units_to_remove_vec: Vec<u32> = units_to_remove.into_iter().collect();
+    let units_to_remove_vec: Vec<u32> = units_to_remove.into_iter().collect();
+    trace!(target: COLLISION, "{} collected, {} killed, {} proximity attacks", collected_items.len(), units_to_remove_vec.len(), attacks_to_spawn.len());
 
     if !units_to_remove_vec.is_empty() {
-        let collectables = remove_units(units_to_remove_vec, Arc::clone(&game_data));
-        let (collectable_units, collectable_positions): (Vec<GameObject>, Vec<Pos2FixedPoint>) = collectables.into_iter().unzip();
-        add_units(collectable_units, collectable_positions, &game_data);
+        remove_units(units_to_remove_vec, Arc::clone(&game_data));
     }
 
     attacks_to_spawn
@@ -305,7 +404,51 @@ pub fn handle_terrain(new_position: &mut Pos2FixedPoint, old_position: &Pos2Fixe
     *new_position = adjusted_position;
 }
 
-fn check_tile_collision(pos: Pos2FixedPoint, unit_shape: &ObjectShape, game_map: &GameMap, tile_size: i32) -> bool {
+// Moves a projectile that ran into a wall back out of it and returns its reflected direction
+pub fn bounce_off_terrain(new_position: &mut Pos2FixedPoint, old_position: &Pos2FixedPoint, shape: &ObjectShape, game_map: &GameMap, tile_size: i32, direction: (f32, f32)) -> Option<(f32, f32)> {
+    let intended = *new_position;
+    if !check_tile_collision(intended, shape, game_map, tile_size) {
+        return None;
+    }
+
+    handle_terrain(new_position, old_position, shape, game_map, tile_size);
+    let blocked_x = new_position.x != intended.x;
+    let blocked_y = new_position.y != intended.y;
+
+    Some((
+        if blocked_x { -direction.0 } else { direction.0 },
+        if blocked_y { -direction.1 } else { direction.1 },
+    ))
+}
+
+// The next hit for attacks that chain, from the target just hit to the nearest enemy the attack hasn't hit yet
+fn next_chain_hit(hit: &AttackLanded, units: &UnitStore, unit_positions: &[Pos2FixedPoint], spatial_grid: &SpatialHashGrid, units_to_remove: &FxHashSet<u32>) -> Option<AttackLanded> {
+    let attack_stats = units.attack_stats[hit.attack_id as usize].as_ref()?;
+    if hit.chain_depth >= attack_stats.chain_count {
+        return None;
+    }
+
+    let context = TargetingContext {
+        units,
+        unit_positions,
+        spatial_hash_grid: spatial_grid,
+        caster_id: units.parent_ids[hit.attack_id as usize],
+        origin: *unit_positions.get(hit.target_id as usize)?,
+        cursor_position: None,
+    };
+    let target_id = nearest_target(attack_stats.chain_range, &context, |id| {
+        id == hit.target_id || attack_stats.units_hit.contains(&id) || units_to_remove.contains(&id)
+    })?;
+
+    Some(AttackLanded {
+        attack_id: hit.attack_id,
+        target_id,
+        damage: hit.damage * attack_stats.chain_falloff,
+        chain_depth: hit.chain_depth + 1,
+    })
+}
+
+pub fn check_tile_collision(pos: Pos2FixedPoint, unit_shape: &ObjectShape, game_map: &GameMap, tile_size: i32) -> bool {
     let (unit_min, unit_max) = unit_shape.bounding_box(pos);
 
     let min_tile_x = unit_min.x / tile_size;
@@ -330,6 +473,19 @@ fn check_tile_collision(pos: Pos2FixedPoint, unit_shape: &ObjectShape, game_map:
     false
 }
 
+fn breakable_tiles_hit(pos: Pos2FixedPoint, shape: &ObjectShape, game_map: &GameMap, already_hit: &[(usize, usize)]) -> SmallVec<[(usize, usize); 4]> {
+    let mut tiles = SmallVec::new();
+    let (min, max) = shape.bounding_box(pos);
+    let Some((min_tile, max_tile)) = game_map.tile_range(min, max) else { return tiles; };
+
+    game_map.for_each_tile_in(min_tile, max_tile, |x, y, tile| {
+        if tile.durability().is_some() && !already_hit.contains(&(x, y)) {
+            tiles.push((x, y));
+        }
+    });
+    tiles
+}
+
 fn compute_separation_vector(pos: Pos2FixedPoint, nearby_positions: &[Pos2FixedPoint]) -> Pos2FixedPoint {
     let avg_x = nearby_positions.iter().map(|p| p.x as i64).sum::<i64>() / nearby_positions.len() as i64;
     let avg_y = nearby_positions.iter().map(|p| p.y as i64).sum::<i64>() / nearby_positions.len() as i64;
@@ -389,4 +545,135 @@ fn is_in_damage_window(attack: &AttackStats, delta_time: f64) -> bool {
     let will_miss_next_window = current_time < damage_start && next_time > damage_end;
 
     in_window || will_miss_next_window
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::game_tile::{GRASS_DEFAULT, WALL_DEFAULT};
+    use crate::game::objects::game_object::GameObject;
+
+    const PLAYER_ID: u32 = 0;
+    const ATTACK_ID: u32 = 1;
+
+    fn at(x: i32, y: i32) -> Pos2FixedPoint {
+        Pos2FixedPoint::new(x * FIXED_POINT_SCALE, y * FIXED_POINT_SCALE)
+    }
+
+    // A player, one of their attacks and an enemy at each of the given positions, ids counting up from 2
+    fn scene(attack_stats: AttackStats, enemies: &[Pos2FixedPoint]) -> (UnitStore, Vec<Pos2FixedPoint>, SpatialHashGrid) {
+        let shape = ObjectShape::new(20 * FIXED_POINT_SCALE, 20 * FIXED_POINT_SCALE);
+        let mut attack = GameObject::new(ObjectType::Attack, shape.clone(), 0, 1.0, 1.0, None);
+        attack.parent_unit_id = Some(PLAYER_ID);
+        attack.attack_stats = Some(attack_stats);
+
+        let mut units = UnitStore::new();
+        let mut positions = vec![at(0, 0), at(0, 0)];
+        units.insert(PLAYER_ID, GameObject::new(ObjectType::Player, shape.clone(), 0, 100.0, 100.0, None));
+        units.insert(ATTACK_ID, attack);
+        for (index, &position) in enemies.iter().enumerate() {
+            units.insert(index as u32 + 2, GameObject::new(ObjectType::Enemy, shape.clone(), 0, 100.0, 100.0, None));
+            positions.push(position);
+        }
+
+        let mut spatial_grid = SpatialHashGrid::new();
+        for (id, &position) in positions.iter().enumerate() {
+            spatial_grid.insert_unit(id as u32, position);
+        }
+        (units, positions, spatial_grid)
+    }
+
+    fn chaining(chain_count: u32) -> AttackStats {
+        AttackStats { chain_count, chain_falloff: 0.5, ..AttackStats::default() }
+    }
+
+    fn direct_hit(target_id: u32) -> AttackLanded {
+        AttackLanded { attack_id: ATTACK_ID, target_id, damage: 10.0, chain_depth: 0 }
+    }
+
+    #[test]
+    fn chains_jump_to_the_nearest_enemy_with_falloff_until_the_chain_count() {
+        let (mut units, positions, spatial_grid) = scene(chaining(2), &[at(100, 0), at(300, 0), at(180, 0)]);
+        let no_kills = FxHashSet::default();
+        let mut hit = direct_hit(2);
+
+        let mut chain = Vec::new();
+        loop {
+            units.attack_stats[ATTACK_ID as usize].as_mut().unwrap().units_hit.push(hit.target_id);
+            let Some(next) = next_chain_hit(&hit, &units, &positions, &spatial_grid, &no_kills) else { break; };
+            chain.push((next.target_id, next.damage, next.chain_depth));
+            hit = next;
+        }
+
+        assert_eq!(chain, vec![(4, 5.0, 1), (3, 2.5, 2)]);
+    }
+
+    #[test]
+    fn chains_skip_enemies_already_hit_or_killed() {
+        let (mut units, positions, spatial_grid) = scene(chaining(3), &[at(100, 0), at(120, 0), at(140, 0), at(160, 0)]);
+        units.attack_stats[ATTACK_ID as usize].as_mut().unwrap().units_hit.extend([2, 3]);
+        let killed = FxHashSet::from_iter([4]);
+
+        let next = next_chain_hit(&direct_hit(2), &units, &positions, &spatial_grid, &killed);
+        assert_eq!(next.map(|hit| hit.target_id), Some(5));
+    }
+
+    #[test]
+    fn chains_stay_within_chain_range() {
+        let attack_stats = AttackStats { chain_range: 50 * FIXED_POINT_SCALE, ..chaining(1) };
+        let (units, positions, spatial_grid) = scene(attack_stats, &[at(100, 0), at(200, 0)]);
+
+        assert!(next_chain_hit(&direct_hit(2), &units, &positions, &spatial_grid, &FxHashSet::default()).is_none());
+    }
+
+    #[test]
+    fn piercing_attacks_hit_max_targets_plus_pierce() {
+        let attack_stats = AttackStats { max_targets: 1, pierce: 2, crit_chance: 0.0, ..AttackStats::default() };
+        let (units, positions, spatial_grid) = scene(attack_stats, &[at(0, 0), at(1, 0), at(2, 0), at(3, 0), at(4, 0)]);
+
+        let game_data = Arc::new(GameData::new());
+        *game_data.units.write().unwrap() = units;
+        *game_data.unit_positions.write().unwrap() = positions;
+        *game_data.spatial_hash_grid.write().unwrap() = spatial_grid;
+        *game_data.player_id.write().unwrap() = Some(PLAYER_ID);
+
+        handle_collision(&mut [(ATTACK_ID, at(0, 0), at(0, 0))], Arc::clone(&game_data), 0.016);
+
+        let units = game_data.units.read().unwrap();
+        let attack_stats = units.attack_stats[ATTACK_ID as usize].as_ref().unwrap();
+        assert_eq!(attack_stats.hit_count, 3);
+        assert_eq!(attack_stats.units_hit.len(), 3);
+    }
+
+    // A 10x10 map of 100 unit tiles with a wall down column 5
+    fn walled_map() -> GameMap {
+        let mut game_map = GameMap::from_tiles(10, 10, 100, vec![GRASS_DEFAULT; 100]);
+        for y in 0..10 {
+            game_map.set_tile(5, y, WALL_DEFAULT);
+        }
+        game_map
+    }
+
+    #[test]
+    fn projectiles_bounce_back_off_the_wall_they_hit() {
+        let game_map = walled_map();
+        let shape = ObjectShape::new(10, 10);
+        let old_position = Pos2FixedPoint::new(480, 250);
+        let mut new_position = Pos2FixedPoint::new(510, 260);
+
+        let direction = bounce_off_terrain(&mut new_position, &old_position, &shape, &game_map, 100, (0.95, 0.3));
+
+        assert_eq!(direction, Some((-0.95, 0.3)));
+        assert!(!check_tile_collision(new_position, &shape, &game_map, 100));
+    }
+
+    #[test]
+    fn projectiles_in_open_ground_do_not_bounce() {
+        let game_map = walled_map();
+        let old_position = Pos2FixedPoint::new(200, 250);
+        let mut new_position = Pos2FixedPoint::new(230, 260);
+
+        assert_eq!(bounce_off_terrain(&mut new_position, &old_position, &ObjectShape::new(10, 10), &game_map, 100, (0.95, 0.3)), None);
+        assert_eq!(new_position, Pos2FixedPoint::new(230, 260));
+    }
 }
\ No newline at end of file
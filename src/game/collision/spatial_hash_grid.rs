@@ -49,6 +49,26 @@ impl SpatialHashGrid {
         }
     }
 
+    // Every unit in the cells overlapping the square around the position, callers check the actual distance
+    pub fn get_units_in_range_into(
+        &self,
+        position: Pos2FixedPoint,
+        range: i32,
+        out: &mut SmallVec<[u32; 64]>
+    ) {
+        out.clear();
+
+        let (min_x, min_y) = hash_position(Pos2FixedPoint::new(position.x.saturating_sub(range), position.y.saturating_sub(range)));
+        let (max_x, max_y) = hash_position(Pos2FixedPoint::new(position.x.saturating_add(range), position.y.saturating_add(range)));
+
+        for cx in min_x..=max_x {
+            for cy in min_y..=max_y {
+                if let Some(units) = self.grid.get(&(cx, cy)) {
+                    out.extend(units.iter().copied());
+                }
+            }
+        }
+    }
 
     pub fn update_units_position_in_grid(&mut self, updates: &[(u32, Pos2FixedPoint, Pos2FixedPoint)]) {
         let chunk_size = (updates.len() / rayon::current_num_threads().max(1)).max(1);
@@ -94,6 +114,11 @@ impl SpatialHashGrid {
     }
 }
 
+pub fn cell_center((cell_x, cell_y): (i32, i32)) -> Pos2FixedPoint {
+    let half_cell = 1 << (CELL_SIZE_BITS - 1);
+    Pos2FixedPoint::new((cell_x << CELL_SIZE_BITS) + half_cell, (cell_y << CELL_SIZE_BITS) + half_cell)
+}
+
 pub fn hash_position(pos: Pos2FixedPoint) -> (i32, i32) {
     ((pos.x >> CELL_SIZE_BITS), (pos.y >> CELL_SIZE_BITS))
 }
@@ -0,0 +1,210 @@
+use crate::game::data::boss_definition::{BossDefinition, BossEncounter, BossPhase, BossSummons, BossTrigger};
+use crate::game::data::spawn_table::{DifficultyRamp, EliteChance, SpawnGroup, SpawnPattern, SpawnTable, Wave};
+use crate::game::map::map_generator::{MapGenerator, TerrainPatch};
+use crate::game::map::tile_type::TileType;
+use crate::game::map::visibility::FogOfWar;
+use crate::game::maths::pos_2::FIXED_POINT_SCALE;
+use crate::game::objects::attacks::attack_stats::AttackName;
+use crate::game::objects::loot::Loot;
+use crate::game::objects::unit_defaults::EnemyName::{AdultWhiteDragon, AquaDrake, BabyDragon, ElderDragon};
+use crate::game::objects::upgrades::{Upgrade, UpgradeType};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum AdventureName {
+    DragonValley,
+    EmberArena,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AdventureDefinition {
+    pub name: AdventureName,
+    pub map_width: usize,
+    pub map_height: usize,
+    pub tile_size: i32,
+    #[serde(default)]
+    pub map_generator: MapGenerator,
+    #[serde(default)]
+    pub terrain: Vec<TerrainPatch>,
+    // Fixed seeds give the same layout every run, None rolls a new one
+    #[serde(default)]
+    pub map_seed: Option<u64>,
+    // Hand authored .tmx, .tmj or ASCII map, either one shipped with the game or a path on disk. Replaces the generator and map size when it loads
+    #[serde(default)]
+    pub map_file: Option<String>,
+    // None reveals the whole map
+    #[serde(default)]
+    pub fog_of_war: Option<FogOfWar>,
+    pub spawn_table: SpawnTable,
+}
+
+pub fn get_adventure_definition(adventure_name: AdventureName) -> AdventureDefinition {
+    match adventure_name {
+        AdventureName::DragonValley => dragon_valley(),
+        AdventureName::EmberArena => ember_arena(),
+    }
+}
+
+fn dragon_valley() -> AdventureDefinition {
+    AdventureDefinition {
+        name: AdventureName::DragonValley,
+        map_width: 60,
+        map_height: 60,
+        tile_size: 40 * FIXED_POINT_SCALE,
+        map_generator: MapGenerator::Field { obstacle_chance: 0.008, max_obstacle_size: 3 },
+        terrain: vec![
+            TerrainPatch { tile_type: TileType::Water, count: 4, radius: 3 },
+            TerrainPatch { tile_type: TileType::Ice, count: 2, radius: 3 },
+            TerrainPatch { tile_type: TileType::Thorns, count: 3, radius: 2 },
+            TerrainPatch { tile_type: TileType::Lava, count: 2, radius: 1 },
+            TerrainPatch { tile_type: TileType::HealingSpring, count: 2, radius: 1 },
+            TerrainPatch { tile_type: TileType::Crate, count: 12, radius: 0 },
+        ],
+        map_seed: None,
+        map_file: None,
+        fog_of_war: Some(FogOfWar { sight_radius: 12, hide_enemies: true }),
+        spawn_table: SpawnTable {
+            enemy_cap: 1500,
+            min_player_distance: 6.0,
+            difficulty: DifficultyRamp {
+                count_per_minute: 0.25,
+                max_count_multiplier: 4.0,
+                health_per_minute: 0.1,
+                max_health_multiplier: 3.0,
+            },
+            elites: EliteChance {
+                base_chance: 0.0,
+                chance_per_minute: 0.01,
+                max_chance: 0.1,
+                max_affixes: 3,
+            },
+            waves: vec![
+                Wave {
+                    start_at: 0.0,
+                    end_at: None,
+                    interval: None,
+                    groups: vec![SpawnGroup { composition: vec![(BabyDragon, 1)], count: 200, pattern: SpawnPattern::Anywhere }],
+                },
+                Wave {
+                    start_at: 0.0,
+                    end_at: None,
+                    interval: Some(1.0),
+                    groups: vec![SpawnGroup { composition: vec![(BabyDragon, 1)], count: 5, pattern: SpawnPattern::OffScreen }],
+                },
+                Wave {
+                    start_at: 30.0,
+                    end_at: None,
+                    interval: Some(5.0),
+                    groups: vec![SpawnGroup { composition: vec![(BabyDragon, 4), (AquaDrake, 1)], count: 10, pattern: SpawnPattern::OffScreen }],
+                },
+                Wave {
+                    start_at: 60.0,
+                    end_at: Some(240.0),
+                    interval: Some(20.0),
+                    groups: vec![SpawnGroup { composition: vec![(AquaDrake, 1)], count: 16, pattern: SpawnPattern::Ring { radius: 8.0 } }],
+                },
+                Wave {
+                    start_at: 120.0,
+                    end_at: None,
+                    interval: Some(15.0),
+                    groups: vec![
+                        SpawnGroup { composition: vec![(AdultWhiteDragon, 1)], count: 2, pattern: SpawnPattern::SpawnPoints },
+                        SpawnGroup { composition: vec![(BabyDragon, 3), (AquaDrake, 2)], count: 20, pattern: SpawnPattern::OffScreen },
+                    ],
+                },
+                Wave {
+                    start_at: 300.0,
+                    end_at: None,
+                    interval: Some(30.0),
+                    groups: vec![SpawnGroup { composition: vec![(AdultWhiteDragon, 1), (AquaDrake, 3)], count: 24, pattern: SpawnPattern::Ring { radius: 10.0 } }],
+                },
+            ],
+            bosses: vec![
+                BossEncounter { boss: elder_dragon("Elder Dragon", 2500.0, 5.0), trigger: BossTrigger::Kills(750) },
+                BossEncounter { boss: elder_dragon("Ancient Elder Dragon", 6000.0, 15.0), trigger: BossTrigger::Time(600.0) },
+            ],
+        },
+    }
+}
+
+// Hand authored map, the boss waits in the walled arena until the player walks in
+fn ember_arena() -> AdventureDefinition {
+    AdventureDefinition {
+        name: AdventureName::EmberArena,
+        map_width: 40,
+        map_height: 30,
+        tile_size: 40 * FIXED_POINT_SCALE,
+        map_generator: MapGenerator::Open,
+        terrain: vec![],
+        map_seed: None,
+        map_file: Some("map/ember_arena.tmj".to_string()),
+        fog_of_war: None,
+        spawn_table: SpawnTable {
+            enemy_cap: 600,
+            min_player_distance: 6.0,
+            difficulty: DifficultyRamp {
+                count_per_minute: 0.25,
+                max_count_multiplier: 3.0,
+                health_per_minute: 0.1,
+                max_health_multiplier: 2.0,
+            },
+            elites: EliteChance {
+                base_chance: 0.02,
+                chance_per_minute: 0.01,
+                max_chance: 0.1,
+                max_affixes: 2,
+            },
+            waves: vec![
+                Wave {
+                    start_at: 0.0,
+                    end_at: None,
+                    interval: Some(2.0),
+                    groups: vec![SpawnGroup { composition: vec![(BabyDragon, 1)], count: 6, pattern: SpawnPattern::SpawnPoints }],
+                },
+                Wave {
+                    start_at: 45.0,
+                    end_at: None,
+                    interval: Some(10.0),
+                    groups: vec![SpawnGroup { composition: vec![(BabyDragon, 3), (AquaDrake, 1)], count: 12, pattern: SpawnPattern::SpawnPoints }],
+                },
+            ],
+            bosses: vec![
+                BossEncounter { boss: elder_dragon("Arena Elder Dragon", 3000.0, 8.0), trigger: BossTrigger::Area("boss_arena".to_string()) },
+            ],
+        },
+    }
+}
+
+fn elder_dragon(title: &str, health: f32, ruby: f64) -> BossDefinition {
+    BossDefinition {
+        title: title.to_string(),
+        enemy: ElderDragon,
+        health,
+        spawn_radius: 9.0,
+        loot: Loot { gold: health as f64 / 5.0, exp: health as f64 / 2.0, ruby },
+        phases: vec![
+            BossPhase {
+                health_threshold: 1.0,
+                attacks: vec![AttackName::Proximity, AttackName::Swipe],
+                move_speed_multiplier: 1.0,
+                upgrades: vec![],
+                summons: None,
+            },
+            BossPhase {
+                health_threshold: 0.66,
+                attacks: vec![AttackName::Proximity, AttackName::LightningBolt],
+                move_speed_multiplier: 1.0,
+                upgrades: vec![],
+                summons: Some(BossSummons { composition: vec![(BabyDragon, 1)], count: 8, interval: 10.0, radius: 3.0 }),
+            },
+            // Enraged
+            BossPhase {
+                health_threshold: 0.33,
+                attacks: vec![AttackName::Proximity, AttackName::Swipe, AttackName::LightningBolt],
+                move_speed_multiplier: 1.6,
+                upgrades: vec![Upgrade { upgrade_type: UpgradeType::DecreaseCooldown, level: 6, attack: None }],
+                summons: Some(BossSummons { composition: vec![(BabyDragon, 3), (AquaDrake, 1)], count: 12, interval: 8.0, radius: 3.0 }),
+            },
+        ],
+    }
+}
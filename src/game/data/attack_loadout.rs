@@ -0,0 +1,136 @@
+use crate::game::objects::attacks::attack_defaults::{get_basic_attack, get_modified_attack};
+use crate::game::objects::attacks::attack_stats::AttackName;
+use crate::game::objects::unit_store::AttackCooldowns;
+use crate::game::objects::upgrades::Upgrade;
+use device_query_revamped::Keycode;
+use serde::{Deserialize, Serialize};
+
+pub const AUTO_CAST_SLOTS: usize = 3;
+pub const ABILITY_SLOTS: usize = 4;
+pub const ABILITY_KEYS: [Keycode; ABILITY_SLOTS] = [Keycode::Space, Keycode::Q, Keycode::E, Keycode::R];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LoadoutSlot {
+    AutoCast(usize),
+    Ability(usize),
+}
+
+// Auto cast attacks fire whenever their cooldown is ready, abilities wait for their key
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct AttackLoadout {
+    pub auto_cast: [Option<AttackName>; AUTO_CAST_SLOTS],
+    pub abilities: [Option<AttackName>; ABILITY_SLOTS],
+}
+
+impl Default for AttackLoadout {
+    fn default() -> Self {
+        Self {
+            auto_cast: [Some(AttackName::Swipe), Some(AttackName::FireBolt), None],
+            abilities: [Some(AttackName::LightningBolt), None, None, None],
+        }
+    }
+}
+
+impl AttackLoadout {
+    // Proximity attacks are triggered by touching a unit so they can't be slotted
+    pub fn selectable_attacks() -> impl Iterator<Item = AttackName> {
+        AttackName::ALL.into_iter().filter(|&attack_name| {
+            get_basic_attack(attack_name).attack_stats.is_some_and(|attack_stats| !attack_stats.proximity_attack)
+        })
+    }
+
+    pub fn slot(&self, slot: LoadoutSlot) -> Option<AttackName> {
+        match slot {
+            LoadoutSlot::AutoCast(index) => self.auto_cast.get(index).copied().flatten(),
+            LoadoutSlot::Ability(index) => self.abilities.get(index).copied().flatten(),
+        }
+    }
+
+    // An attack can only sit in one slot, so it's taken out of any other slot first
+    pub fn assign(&mut self, slot: LoadoutSlot, attack: Option<AttackName>) {
+        if let Some(attack_name) = attack {
+            for existing in self.auto_cast.iter_mut().chain(self.abilities.iter_mut()) {
+                if *existing == Some(attack_name) {
+                    *existing = None;
+                }
+            }
+        }
+
+        match slot {
+            LoadoutSlot::AutoCast(index) => self.auto_cast[index] = attack,
+            LoadoutSlot::Ability(index) => self.abilities[index] = attack,
+        }
+    }
+
+    pub fn auto_cast_attacks(&self) -> impl Iterator<Item = AttackName> + '_ {
+        self.auto_cast.iter().flatten().copied()
+    }
+}
+
+pub fn ability_slot_for_key(key: Keycode) -> Option<usize> {
+    ABILITY_KEYS.iter().position(|&ability_key| ability_key == key)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SlotCooldown {
+    pub attack_name: AttackName,
+    pub remaining: f32,
+    // The full cooldown after upgrades, for drawing how far along it is
+    pub total: f32,
+}
+
+impl SlotCooldown {
+    pub fn progress(&self) -> f32 {
+        if self.total <= 0.0 { 1.0 } else { (1.0 - self.remaining / self.total).clamp(0.0, 1.0) }
+    }
+}
+
+// Run state behind the HUD hotbar. Ability cooldowns live here, auto cast cooldowns are mirrored from the player unit.
+#[derive(Clone, Default, Debug)]
+pub struct Hotbar {
+    pub abilities: [Option<SlotCooldown>; ABILITY_SLOTS],
+    pub auto_cast: Vec<SlotCooldown>,
+}
+
+impl Hotbar {
+    pub fn new(loadout: &AttackLoadout) -> Self {
+        Self {
+            abilities: loadout.abilities.map(|attack| attack.map(|attack_name| SlotCooldown { attack_name, remaining: 0.0, total: 0.0 })),
+            auto_cast: Vec::new(),
+        }
+    }
+
+    pub fn attacks(&self) -> impl Iterator<Item = AttackName> + '_ {
+        self.abilities.iter().flatten().map(|slot| slot.attack_name)
+    }
+
+    pub fn tick(&mut self, delta_time: f32) {
+        for slot in self.abilities.iter_mut().flatten() {
+            slot.remaining = (slot.remaining - delta_time).max(0.0);
+        }
+    }
+
+    // Starts the slot's cooldown and returns its attack when it's ready
+    pub fn try_cast(&mut self, index: usize, upgrades: &Vec<Upgrade>) -> Option<AttackName> {
+        let slot = self.abilities.get_mut(index)?.as_mut()?;
+        if slot.remaining > 0.0 {
+            return None;
+        }
+
+        slot.total = get_modified_attack(upgrades, slot.attack_name).attack_stats.map_or(0.0, |attack_stats| attack_stats.cooldown);
+        slot.remaining = slot.total;
+        Some(slot.attack_name)
+    }
+
+    // The total is only looked up again when an attack is new or has just fired
+    pub fn sync_auto_cast(&mut self, cooldowns: &AttackCooldowns, upgrades: &Vec<Upgrade>) {
+        let previous = std::mem::take(&mut self.auto_cast);
+        self.auto_cast = cooldowns.iter().map(|&(attack_name, remaining)| {
+            let total = match previous.iter().find(|slot| slot.attack_name == attack_name) {
+                Some(slot) if remaining <= slot.remaining => slot.total,
+                _ => get_modified_attack(upgrades, attack_name).attack_stats.map_or(0.0, |attack_stats| attack_stats.cooldown),
+            };
+            SlotCooldown { attack_name, remaining: remaining.max(0.0), total }
+        }).collect();
+    }
+}
@@ -0,0 +1,62 @@
+use crate::game::objects::attacks::attack_stats::AttackName;
+use crate::game::objects::loot::Loot;
+use crate::game::objects::unit_defaults::EnemyName;
+use crate::game::objects::upgrades::Upgrade;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BossEncounter {
+    pub boss: BossDefinition,
+    pub trigger: BossTrigger,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum BossTrigger {
+    // Seconds of run time
+    Time(f32),
+    // Enemies killed this run
+    Kills(u32),
+    // The player walks into the map file trigger with this name
+    Area(String),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BossDefinition {
+    pub title: String,
+    pub enemy: EnemyName,
+    pub health: f32,
+    // In tiles from the player
+    pub spawn_radius: f32,
+    // Always dropped, replaces the enemy's own loot
+    pub loot: Loot,
+    // Ordered by descending health_threshold, the first should start at 1.0
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BossPhase {
+    // Fraction of max health at or below which the phase begins
+    pub health_threshold: f32,
+    pub attacks: Vec<AttackName>,
+    pub move_speed_multiplier: f32,
+    pub upgrades: Vec<Upgrade>,
+    pub summons: Option<BossSummons>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BossSummons {
+    pub composition: Vec<(EnemyName, u32)>,
+    pub count: u32,
+    pub interval: f32,
+    // In tiles from the boss
+    pub radius: f32,
+}
+
+impl BossDefinition {
+    pub fn phase_for_health(&self, health_fraction: f32) -> usize {
+        self.phases
+            .iter()
+            .rposition(|phase| health_fraction <= phase.health_threshold)
+            .unwrap_or(0)
+    }
+}
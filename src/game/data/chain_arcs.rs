@@ -0,0 +1,12 @@
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use std::time::{Duration, Instant};
+
+pub const CHAIN_ARC_DURATION: Duration = Duration::from_millis(250);
+
+// A chain jump between two targets, drawn briefly as a lightning arc
+#[derive(Clone)]
+pub struct ChainArc {
+    pub from: Pos2FixedPoint,
+    pub to: Pos2FixedPoint,
+    pub spawn_time: Instant,
+}
\ No newline at end of file
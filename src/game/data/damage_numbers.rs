@@ -1,11 +1,15 @@
 use crate::game::maths::pos_2::Pos2FixedPoint;
 use egui::Color32;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+pub const DAMAGE_NUMBER_DURATION: Duration = Duration::from_millis(800);
 
 #[derive(Clone)]
 pub struct DamageNumber {
     pub position: Pos2FixedPoint,
     pub value: f64,
     pub spawn_time: Instant,
+    // From the damage type, or the status effect for burn and poison ticks
     pub colour: Color32,
+    pub critical: bool,
 }
\ No newline at end of file
@@ -1,14 +1,25 @@
 use crate::enums::gamestate::GameState;
 use crate::enums::gamestate::GameState::Lair;
 use crate::game::collision::spatial_hash_grid::SpatialHashGrid;
+use crate::game::data::attack_loadout::Hotbar;
+use crate::game::data::chain_arcs::ChainArc;
 use crate::game::data::damage_numbers::DamageNumber;
 use crate::game::data::player_data::PlayerData;
+use crate::game::data::player_level::PlayerLevel;
+use crate::game::data::run_stats::RunStats;
 use crate::game::data::stored_data::StoredData;
+use crate::game::events::event_bus::EventBus;
+use crate::game::events::game_event::GameEvent;
+use crate::game::loops::unit_spawner::SpawnDirector;
 use crate::game::map::camera_state::CameraState;
+use crate::game::map::flow_field::FlowField;
 use crate::game::map::game_map::GameMap;
+use crate::game::map::tile_damage::TileDamage;
+use crate::game::map::visibility::VisibilityMap;
 use crate::game::maths::pos_2::Pos2FixedPoint;
 use crate::game::objects::attacks::attack_stats::AttackName;
 use crate::game::objects::game_object::GameObject;
+use crate::game::objects::unit_store::UnitStore;
 use crate::helper::lock_helper::acquire_lock_mut;
 use crate::ui::graphics::offscreen_renderer::OffscreenRenderer;
 use device_query_revamped::Keycode;
@@ -31,18 +42,28 @@ pub struct GameData {
     pub store: Arc<RwLock<HashMap<String, Arc<RwLock<Box<dyn Any + Send + Sync>>>>>>,
     pub steam_client: Arc<RwLock<Option<Client>>>,
     pub resource_amounts: Arc<RwLock<ResourceAmount>>,
-    pub game_map: Arc<RwLock<Option<GameMap>>>,
-
-    pub units: Arc<RwLock<Vec<Option<GameObject>>>>,
+    pub game_map: Arc<RwLock<Option<Arc<GameMap>>>>,
+    pub spawn_director: Arc<RwLock<Option<SpawnDirector>>>,
+    pub flow_field: Arc<RwLock<Option<Arc<FlowField>>>>,
+    // None when the adventure has no fog of war
+    pub visibility: Arc<RwLock<Option<Arc<VisibilityMap>>>>,
+    pub tile_damage: Arc<RwLock<TileDamage>>,
+
+    pub units: Arc<RwLock<UnitStore>>,
     pub unit_positions: Arc<RwLock<Vec<Pos2FixedPoint>>>,
     pub empty_unit_indexes: Arc<RwLock<Vec<u32>>>,
     pub attack_pools: Arc<RwLock<FxHashMap<AttackName, Vec<GameObject>>>>,
     pub damage_numbers: Arc<RwLock<Vec<DamageNumber>>>,
+    pub chain_arcs: Arc<RwLock<Vec<ChainArc>>>,
+    pub events: Arc<RwLock<EventBus>>,
+    pub run_stats: Arc<RwLock<RunStats>>,
 
     pub player_id: Arc<RwLock<Option<u32>>>,
     pub player_position: Arc<RwLock<Option<Pos2FixedPoint>>>,
     pub player_dead: Arc<RwLock<bool>>,
     pub player_data: Arc<RwLock<PlayerData>>,
+    pub player_level: Arc<RwLock<PlayerLevel>>,
+    pub hotbar: Arc<RwLock<Hotbar>>,
 
     pub spatial_hash_grid: Arc<RwLock<SpatialHashGrid>>,
     pub offscreen_renderer: Arc<RwLock<Option<OffscreenRenderer>>>,
@@ -52,6 +73,8 @@ pub struct GameData {
     pub sprite_shader: Arc<RwLock<Option<NativeProgram>>>,
 
     pub key_queue: Arc<RwLock<Vec<Keycode>>>,
+    // World position under the mouse, None while it's outside the game view
+    pub cursor_position: Arc<RwLock<Option<Pos2FixedPoint>>>,
     pub game_state: Arc<RwLock<GameState>>,
     pub icons: Arc<RwLock<FxHashMap<String, TextureHandle>>>,
     pub icons_inverted: Arc<RwLock<FxHashMap<String, TextureHandle>>>,
@@ -70,17 +93,26 @@ impl GameData {
             steam_client: Arc::new(RwLock::new(None)),
             resource_amounts: Arc::new(RwLock::new(ResourceAmount::default())),
             game_map: Arc::new(RwLock::new(None)),
+            spawn_director: Arc::new(RwLock::new(None)),
+            flow_field: Arc::new(RwLock::new(None)),
+            visibility: Arc::new(RwLock::new(None)),
+            tile_damage: Arc::new(RwLock::new(TileDamage::default())),
 
-            units: Arc::new(RwLock::new(Vec::new())),
+            units: Arc::new(RwLock::new(UnitStore::new())),
             unit_positions: Arc::new(RwLock::new(Vec::new())),
             empty_unit_indexes: Arc::new(RwLock::new(Vec::new())),
             attack_pools: Arc::new(RwLock::new(FxHashMap::default())),
             damage_numbers: Arc::new(RwLock::new(Vec::new())),
+            chain_arcs: Arc::new(RwLock::new(Vec::new())),
+            events: Arc::new(RwLock::new(EventBus::new())),
+            run_stats: Arc::new(RwLock::new(RunStats::default())),
 
             player_id: Arc::new(RwLock::new(None)),
             player_position: Arc::new(RwLock::new(None)),
             player_dead: Arc::new(RwLock::new(false)),
             player_data: Arc::new(RwLock::new(PlayerData::default())),
+            player_level: Arc::new(RwLock::new(PlayerLevel::default())),
+            hotbar: Arc::new(RwLock::new(Hotbar::default())),
 
             spatial_hash_grid: Arc::new(RwLock::new(SpatialHashGrid::new())),
             offscreen_renderer: Arc::new(RwLock::new(None)),
@@ -89,6 +121,7 @@ impl GameData {
             rect_shader: Arc::new(RwLock::new(None)),
             sprite_shader: Arc::new(RwLock::new(None)),
             key_queue: Arc::new(RwLock::new(Vec::new())),
+            cursor_position: Arc::new(RwLock::new(None)),
             game_state: Arc::new(RwLock::new(Lair)),
             icons: Arc::new(RwLock::new(FxHashMap::default())),
             icons_inverted: Arc::new(RwLock::new(FxHashMap::default())),
@@ -100,8 +133,12 @@ impl GameData {
     }
 
     pub fn set_game_state(&self, game_state: GameState) {
-        *acquire_lock_mut(&self.game_state, "game_state") = game_state;
+        let previous_state = std::mem::replace(&mut *acquire_lock_mut(&self.game_state, "game_state"), game_state);
         self.game_loop_active.store(game_state.is_game_active(), Ordering::Relaxed);
+
+        if previous_state != game_state {
+            acquire_lock_mut(&self.events, "events").publish(GameEvent::StateChanged { from: previous_state, to: game_state });
+        }
     }
 
     pub fn set_field<T: Any + Send + Sync>(&self, key: StoredData<T>, value: T) {
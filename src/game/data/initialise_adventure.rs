@@ -1,120 +1,129 @@
+use std::path::Path;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
-use rand::random_range;
+use tracing::{debug, error, info};
+use crate::game::data::adventure_definition::{get_adventure_definition, AdventureDefinition, AdventureName};
+use crate::game::data::attack_loadout::Hotbar;
 use crate::game::data::game_data::GameData;
+use crate::game::data::player_level::PlayerLevel;
 use crate::game::data::resource_cost::ResourceAmount;
+use crate::game::data::run_stats::RunStats;
+use crate::game::loops::unit_spawner::SpawnDirector;
 use crate::game::map::camera_state::CameraState;
-use crate::game::map::game_map::GameMap;
+use crate::game::map::map_generator::generate_map;
+use crate::game::map::map_loader::{load_map_file, parse_map};
+use crate::game::map::tile_damage::TileDamage;
+use crate::game::map::visibility::VisibilityMap;
 use crate::game::maths::pos_2::{Pos2FixedPoint, FIXED_POINT_SCALE};
 use crate::game::objects::animation::Animation;
 use crate::game::objects::attacks::attack_defaults::get_modified_attack;
-use crate::game::objects::attacks::attack_stats::AttackName;
 use crate::game::objects::game_object::{add_units, GameObject};
 use crate::game::objects::object_shape::ObjectShape;
 use crate::game::objects::object_type::ObjectType;
-use crate::game::objects::unit_defaults::{create_01_baby_dragon, create_02_aqua_drake, create_03_adult_white_dragon};
 use crate::game::objects::upgrades::{Upgrade, UpgradeType};
 use crate::game::resources::resource::DEFAULT_MOVE_SPEED;
 use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::GAME_LOOP;
+use crate::ui::asset::loader::embedded_map;
 use crate::ui::asset::sprite::sprite_sheet::BABY_GREEN_DRAGON;
+use rand::Rng;
 
-const TILE_SIZE: i32 = 40 * FIXED_POINT_SCALE;
-const X_TILE_COUNT: usize = 60;
-const Y_TILE_COUNT: usize = 60;
-const X_CENTER: i32 = TILE_SIZE * X_TILE_COUNT as i32 / 2;
-const Y_CENTER: i32 = TILE_SIZE * Y_TILE_COUNT as i32 / 2;
+pub fn initialise_adventure(game_data: &GameData, adventure_name: AdventureName) {
+    let definition = get_adventure_definition(adventure_name);
 
-pub fn initialise_adventure(game_data: &GameData) {
-    init_map(game_data);
-    println!("Adventure Map Initialized");
+    let player_start = init_map(game_data, &definition);
+    debug!(target: GAME_LOOP, "Adventure Map Initialized");
 
-    init_player(game_data);
-    println!("Adventure Player Initialized");
+    init_player(game_data, player_start);
+    debug!(target: GAME_LOOP, "Adventure Player Initialized");
 
-    init_enemies(game_data);
-    println!("Adventure Enemies Initialized");
+    init_spawn_director(game_data, &definition);
+    debug!(target: GAME_LOOP, "Adventure Spawn Director Initialized");
 
     init_resources(game_data);
-    println!("Adventure Resources Initialized");
+    debug!(target: GAME_LOOP, "Adventure Resources Initialized");
 
     init_reset(game_data);
-    println!("Reset complete flagged as false");;
+    info!(target: GAME_LOOP, "Adventure {:?} initialised", adventure_name);
 }
 
-fn init_map(game_data: &GameData) {
-    *acquire_lock_mut(&game_data.game_map, "game_map") = Some(GameMap::new(X_TILE_COUNT, Y_TILE_COUNT, TILE_SIZE));
-    *acquire_lock_mut(&game_data.camera_state, "camera_state") = CameraState::new(Pos2FixedPoint::new(X_CENTER, Y_CENTER), 2048);
+// Returns the player start, a broken map file is logged and falls back to the generator.
+// Maps shipped with the game are compiled in, any other map file is read from disk.
+fn init_map(game_data: &GameData, definition: &AdventureDefinition) -> Pos2FixedPoint {
+    let loaded_map = definition.map_file.as_ref().and_then(|map_file| {
+        match embedded_map(map_file) {
+            Some(text) => parse_map(Path::new(map_file), text, definition.tile_size),
+            None => load_map_file(Path::new(map_file), definition.tile_size),
+        }
+            .inspect(|loaded_map| info!(target: GAME_LOOP, "Loaded map {} with {} triggers", map_file, loaded_map.game_map.triggers.len()))
+            .inspect_err(|error| error!(target: GAME_LOOP, "Failed to load map {}: {}", map_file, error))
+            .ok()
+    });
+
+    let (game_map, (start_x, start_y)) = match loaded_map {
+        Some(loaded_map) => (loaded_map.game_map, loaded_map.player_start),
+        None => {
+            let seed = definition.map_seed.unwrap_or_else(|| rand::rng().random());
+            let start = (definition.map_width / 2, definition.map_height / 2);
+            info!(target: GAME_LOOP, "Generating {:?} map with seed {}", definition.map_generator, seed);
+            (generate_map(definition.map_width, definition.map_height, definition.tile_size, definition.map_generator, &definition.terrain, seed, start), start)
+        }
+    };
+
+    let half_tile = definition.tile_size / 2;
+    let player_start = Pos2FixedPoint::new(start_x as i32 * definition.tile_size + half_tile, start_y as i32 * definition.tile_size + half_tile);
+
+    *acquire_lock_mut(&game_data.visibility, "visibility") = definition.fog_of_war.map(|fog_of_war| Arc::new(VisibilityMap::new(&game_map, fog_of_war)));
+    *acquire_lock_mut(&game_data.game_map, "game_map") = Some(Arc::new(game_map));
+    *acquire_lock_mut(&game_data.flow_field, "flow_field") = None;
+    *acquire_lock_mut(&game_data.tile_damage, "tile_damage") = TileDamage::default();
+    *acquire_lock_mut(&game_data.camera_state, "camera_state") = CameraState::new(player_start, 2048);
+
+    player_start
 }
 
-fn init_player(game_data: &GameData) {
+fn init_player(game_data: &GameData, player_start: Pos2FixedPoint) {
     let animation = Animation::new(BABY_GREEN_DRAGON, Duration::from_secs(2), (50, 50));
     let mut player = GameObject::new(ObjectType::Player, ObjectShape::new(40 * FIXED_POINT_SCALE, 40 * FIXED_POINT_SCALE), DEFAULT_MOVE_SPEED, 100.0, 100.0, Some(animation));
 
     let upgrade = Upgrade {
         upgrade_type: UpgradeType::DecreaseCooldown,
         level: 2,
+        attack: None,
     };
+    player.upgrades.push(upgrade);
 
-    for name in [AttackName::Swipe, AttackName::FireBolt, AttackName::LightningBolt] {
-        player.attack_cooldowns.insert(name, get_modified_attack(&vec!(upgrade.clone()), name).attack_stats.unwrap().cooldown);
+    let loadout = acquire_lock(&game_data.player_data, "player_data").attack_loadout.clone();
+    for name in loadout.auto_cast_attacks() {
+        if let Some(attack_stats) = get_modified_attack(&player.upgrades, name).attack_stats {
+            player.attack_cooldowns.insert(name, attack_stats.cooldown);
+        }
     }
+    *acquire_lock_mut(&game_data.hotbar, "hotbar") = Hotbar::new(&loadout);
 
-    player.upgrades.push(upgrade);
     player.pickup_radius = Some(300 * FIXED_POINT_SCALE);
 
-    let player_position = Pos2FixedPoint::new(X_CENTER, Y_CENTER);
+    let player_position = player_start;
     add_units(vec![player], vec![player_position], game_data);
 
     let player_id = game_data.units.read().unwrap()
-        .iter()
-        .filter_map(|unit_option| unit_option.as_ref())
-        .find(|unit| unit.object_type == ObjectType::Player)
-        .map(|player| player.id);
+        .ids_of_type(ObjectType::Player)
+        .next();
 
-    *acquire_lock_mut(&game_data.player_id, "player_dead") = player_id;
-    *acquire_lock_mut(&game_data.player_position, "player_dead") = Some(player_position);
+    *acquire_lock_mut(&game_data.player_id, "player_id") = player_id;
+    *acquire_lock_mut(&game_data.player_position, "player_position") = Some(player_position);
     *acquire_lock_mut(&game_data.player_dead, "player_dead") = false;
 }
 
-fn init_enemies(game_data: &GameData) {
-    if let Some(map) = acquire_lock(&game_data.game_map, "game_map").as_ref() {
-        let mut units = vec![];
-        let mut positions = vec![];
-
-        let map_x = map.width as i32 * map.tile_size;
-        let map_y = map.height as i32 * map.tile_size;
-
-        let baby_count = 1000;
-        let drake_count = 50;
-        let adult_count = 15;
-
-        for _i in 0..baby_count {
-            let pos = Pos2FixedPoint::new(random_range(0..=map_x), random_range(0..=map_y));
-            let unit = create_01_baby_dragon();
-            units.push(unit);
-            positions.push(pos);
-        }
-
-        for _i in 0..drake_count {
-            let pos = Pos2FixedPoint::new(random_range(0..=map_x), random_range(0..=map_y));
-            let unit = create_02_aqua_drake();
-            units.push(unit);
-            positions.push(pos);
-        }
-
-        for _i in 0..adult_count {
-            let pos = Pos2FixedPoint::new(random_range(0..=map_x), random_range(0..=map_y));
-            let unit = create_03_adult_white_dragon();
-            units.push(unit);
-            positions.push(pos);
-        }
-
-        add_units(units, positions, game_data);
-    }
+fn init_spawn_director(game_data: &GameData, definition: &AdventureDefinition) {
+    *acquire_lock_mut(&game_data.spawn_director, "spawn_director") = Some(SpawnDirector::new(definition.spawn_table.clone()));
 }
 
 fn init_resources(game_data: &GameData) {
     *acquire_lock_mut(&game_data.resource_amounts, "resource_amounts") = ResourceAmount::default();
+    *acquire_lock_mut(&game_data.run_stats, "run_stats") = RunStats { level_reached: 1, ..RunStats::default() };
+    *acquire_lock_mut(&game_data.player_level, "player_level") = PlayerLevel::default();
 }
 
 fn init_reset(game_data: &GameData) {
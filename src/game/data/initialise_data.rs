@@ -8,7 +8,9 @@ use crate::game::settings::Settings;
 use std::sync::Arc;
 use eframe::emath::Vec2;
 use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::GAME_LOOP;
 use crate::ui::component::widget::lair_object::get_lair_object;
+use tracing::debug;
 
 pub fn initialise_data(game_data: GameData) -> GameData {
 
@@ -17,13 +19,13 @@ pub fn initialise_data(game_data: GameData) -> GameData {
     // *acquire_lock_mut(&game_data.steam_client, "steam_client") = Some(steam_client);
 
     init_attacks(&game_data);
-    println!("Initialised Attacks");
+    debug!(target: GAME_LOOP, "Initialised Attacks");
 
     game_data.set_field(KEY_STATE, Arc::new(KeyState::new()));
     game_data.set_field(CURRENT_TAB, GameTab::default());
 
     if game_data.get_field(SETTINGS).is_none() {
-        println!("Initialised Settings");
+        debug!(target: GAME_LOOP, "Initialised Settings");
         game_data.set_field(SETTINGS, Settings::default());
     }
 
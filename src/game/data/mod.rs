@@ -5,4 +5,11 @@ pub mod stored_data;
 pub mod damage_numbers;
 pub mod player_data;
 pub mod initialise_adventure;
-pub mod resource_cost;
\ No newline at end of file
+pub mod resource_cost;
+pub mod run_stats;
+pub mod spawn_table;
+pub mod adventure_definition;
+pub mod boss_definition;
+pub mod player_level;
+pub mod attack_loadout;
+pub mod chain_arcs;
\ No newline at end of file
@@ -1,3 +1,4 @@
+use crate::game::data::attack_loadout::AttackLoadout;
 use crate::game::data::resource_cost::ResourceAmount;
 use crate::game::objects::upgrades::Upgrade;
 use serde::{Deserialize, Serialize};
@@ -8,6 +9,8 @@ pub struct PlayerData {
     pub upgrades: Vec<Upgrade>,
     pub resources_persistent: ResourceAmount,
     pub lair_objects: Vec<LairObject>,
+    #[serde(default)]
+    pub attack_loadout: AttackLoadout,
 }
 
 impl Default for PlayerData {
@@ -16,6 +19,7 @@ impl Default for PlayerData {
             upgrades: Vec::new(),
             resources_persistent: ResourceAmount::default(),
             lair_objects: Vec::new(),
+            attack_loadout: AttackLoadout::default(),
         }
     }
 }
\ No newline at end of file
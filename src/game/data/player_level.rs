@@ -0,0 +1,202 @@
+use crate::enums::gamestate::GameState;
+use crate::game::data::game_data::GameData;
+use crate::game::objects::attacks::attack_defaults::{get_basic_attack, get_modified_attack};
+use crate::game::objects::attacks::attack_stats::AttackName;
+use crate::game::objects::game_object::apply_upgrade;
+use crate::game::objects::upgrades::{Upgrade, UpgradeType};
+use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::GAME_LOOP;
+use rand::Rng;
+use tracing::info;
+
+// Exp needed for the first level up, each level after needs EXP_GROWTH times more than the last
+const EXP_BASE: f64 = 5.0;
+const EXP_GROWTH: f64 = 1.2;
+const CHOICE_COUNT: usize = 3;
+const NEW_ATTACK_WEIGHT: f64 = 2.0;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum LevelUpChoice {
+    NewAttack(AttackName),
+    // Attack specific upgrades name the attack they apply to
+    Upgrade(UpgradeType, Option<AttackName>),
+}
+
+impl LevelUpChoice {
+    fn weight(&self) -> f64 {
+        match self {
+            LevelUpChoice::NewAttack(_) => NEW_ATTACK_WEIGHT,
+            LevelUpChoice::Upgrade(..) => 1.0,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            LevelUpChoice::NewAttack(attack_name) => format!("New Attack: {:?}", attack_name),
+            LevelUpChoice::Upgrade(upgrade_type, None) => format!("{:?}", upgrade_type),
+            LevelUpChoice::Upgrade(upgrade_type, Some(attack_name)) => format!("{:?}: {:?}", attack_name, upgrade_type),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            LevelUpChoice::NewAttack(_) => "Cast automatically alongside your other attacks",
+            LevelUpChoice::Upgrade(UpgradeType::IncreaseDamage, _) => "+10 damage on every attack",
+            LevelUpChoice::Upgrade(UpgradeType::DecreaseCooldown, _) => "5% shorter cooldowns",
+            LevelUpChoice::Upgrade(UpgradeType::IncreaseAOE, _) => "15% larger attack area",
+            LevelUpChoice::Upgrade(UpgradeType::IncreaseRange, _) => "Attacks last longer and travel faster",
+            LevelUpChoice::Upgrade(UpgradeType::IncreaseSpeed, _) => "Faster projectiles",
+            LevelUpChoice::Upgrade(UpgradeType::ExtraProjectiles, _) => "One more projectile per cast",
+            LevelUpChoice::Upgrade(UpgradeType::ExtraBurst, _) => "Casts one more time in quick succession",
+        }
+    }
+}
+
+// Experience itself is the run's resource_amounts.exp, this only tracks which levels it has paid for
+#[derive(Clone, Debug)]
+pub struct PlayerLevel {
+    pub level: u32,
+    pub pending_level_ups: u32,
+    pub choices: Vec<LevelUpChoice>,
+}
+
+impl Default for PlayerLevel {
+    fn default() -> Self {
+        Self { level: 1, pending_level_ups: 0, choices: Vec::new() }
+    }
+}
+
+impl PlayerLevel {
+    // Total exp needed to go from level 1 to the level after this one
+    pub fn exp_for_level(level: u32) -> f64 {
+        EXP_BASE * (EXP_GROWTH.powi(level as i32) - 1.0) / (EXP_GROWTH - 1.0)
+    }
+
+    // Returns every level reached
+    pub fn gain_levels(&mut self, total_exp: f64) -> Vec<u32> {
+        let mut levels = Vec::new();
+        while total_exp >= Self::exp_for_level(self.level) {
+            self.level += 1;
+            self.pending_level_ups += 1;
+            levels.push(self.level);
+        }
+        levels
+    }
+
+    // Fraction of the way to the next level
+    pub fn progress(&self, total_exp: f64) -> f64 {
+        let level_start = Self::exp_for_level(self.level - 1);
+        ((total_exp - level_start) / (Self::exp_for_level(self.level) - level_start)).clamp(0.0, 1.0)
+    }
+}
+
+// Weighted draw without repeats from attacks the player doesn't have and upgrades below their max level.
+// Attack specific upgrades are offered for each projectile attack the player has.
+pub fn roll_choices(upgrades: &[Upgrade], attacks: &[AttackName], rng: &mut impl Rng) -> Vec<LevelUpChoice> {
+    let below_max_level = |upgrade_type: &UpgradeType, attack: Option<AttackName>| {
+        let level = upgrades.iter().find(|upgrade| upgrade.upgrade_type == *upgrade_type && upgrade.attack == attack).map_or(0, |upgrade| upgrade.level);
+        level < upgrade_type.max_level()
+    };
+    let is_projectile = |attack_name: AttackName| get_basic_attack(attack_name).attack_stats.is_some_and(|attack_stats| attack_stats.speed > 0);
+
+    let new_attacks = AttackName::ALL.into_iter().filter(|attack_name| !attacks.contains(attack_name)).map(LevelUpChoice::NewAttack);
+    let upgrade_choices = UpgradeType::ALL.into_iter().flat_map(|upgrade_type| {
+        let targets: Vec<Option<AttackName>> = if upgrade_type.is_attack_specific() {
+            attacks.iter().copied().filter(|&attack_name| is_projectile(attack_name)).map(Some).collect()
+        } else {
+            vec![None]
+        };
+        targets
+            .into_iter()
+            .filter(|&attack| below_max_level(&upgrade_type, attack))
+            .map(|attack| LevelUpChoice::Upgrade(upgrade_type.clone(), attack))
+            .collect::<Vec<_>>()
+    });
+    let mut candidates: Vec<LevelUpChoice> = new_attacks.chain(upgrade_choices).collect();
+
+    let mut choices = Vec::with_capacity(CHOICE_COUNT);
+    while choices.len() < CHOICE_COUNT && !candidates.is_empty() {
+        let total_weight: f64 = candidates.iter().map(LevelUpChoice::weight).sum();
+        let mut roll = rng.random_range(0.0..total_weight);
+        let index = candidates.iter().position(|candidate| {
+            roll -= candidate.weight();
+            roll < 0.0
+        }).unwrap_or(candidates.len() - 1);
+
+        choices.push(candidates.swap_remove(index));
+    }
+
+    choices
+}
+
+// Rolls choices for a queued level up and pauses the run on the level up screen.
+// Level ups are dropped when there's nothing left to offer.
+pub fn offer_level_up(game_data: &GameData) {
+    if !acquire_lock(&game_data.player_level, "player_level").choices.is_empty() {
+        return;
+    }
+
+    let Some(player_id) = *acquire_lock(&game_data.player_id, "player_id") else { return; };
+    let (upgrades, mut attacks) = {
+        let game_units = acquire_lock(&game_data.units, "game_units");
+        if !game_units.is_alive(player_id) {
+            return;
+        }
+        let attacks: Vec<AttackName> = game_units.attack_cooldowns[player_id as usize].iter().map(|(attack_name, _)| *attack_name).collect();
+        (game_units.upgrades[player_id as usize].clone(), attacks)
+    };
+    // Hotbar abilities can be upgraded but aren't offered again as new auto cast attacks
+    attacks.extend(acquire_lock(&game_data.hotbar, "hotbar").attacks());
+
+    let choices = roll_choices(&upgrades, &attacks, &mut rand::rng());
+    {
+        let mut player_level = acquire_lock_mut(&game_data.player_level, "player_level");
+        if player_level.pending_level_ups == 0 {
+            return;
+        }
+        if choices.is_empty() {
+            player_level.pending_level_ups = 0;
+            return;
+        }
+        player_level.choices = choices;
+    }
+
+    if *acquire_lock(&game_data.game_state, "game_state") == GameState::Playing {
+        game_data.set_game_state(GameState::LevelUp);
+    }
+}
+
+// Applies the picked choice to the player, then offers the next queued level up or resumes the run
+pub fn choose_level_up(game_data: &GameData, index: usize) {
+    let choice = {
+        let mut player_level = acquire_lock_mut(&game_data.player_level, "player_level");
+        if index >= player_level.choices.len() {
+            return;
+        }
+        player_level.pending_level_ups = player_level.pending_level_ups.saturating_sub(1);
+        let choice = player_level.choices[index].clone();
+        player_level.choices.clear();
+        choice
+    };
+
+    let player_id = *acquire_lock(&game_data.player_id, "player_id");
+    if let Some(player_id) = player_id {
+        let mut game_units = acquire_lock_mut(&game_data.units, "game_units");
+        if let Some(mut player) = game_units.take(player_id) {
+            match &choice {
+                LevelUpChoice::Upgrade(upgrade_type, attack) => apply_upgrade(&mut player, upgrade_type.clone(), *attack),
+                LevelUpChoice::NewAttack(attack_name) => {
+                    let cooldown = get_modified_attack(&player.upgrades, *attack_name).attack_stats.map_or(0.0, |attack_stats| attack_stats.cooldown);
+                    player.attack_cooldowns.insert(*attack_name, cooldown);
+                }
+            }
+            game_units.insert(player_id, player);
+        }
+    }
+    info!(target: GAME_LOOP, "Level up choice taken: {}", choice.title());
+
+    offer_level_up(game_data);
+    if acquire_lock(&game_data.player_level, "player_level").choices.is_empty() {
+        game_data.set_game_state(GameState::Playing);
+    }
+}
@@ -0,0 +1,11 @@
+#[derive(Clone, Default, Debug)]
+pub struct RunStats {
+    pub enemies_killed: u32,
+    pub bosses_defeated: u32,
+    pub damage_dealt: f64,
+    pub damage_taken: f64,
+    pub gold_collected: f64,
+    pub exp_collected: f64,
+    pub attacks_cast: u32,
+    pub level_reached: u32,
+}
@@ -3,32 +3,45 @@ use crate::game::data::initialise_data::initialise_data;
 use crate::game::data::stored_data::SETTINGS;
 use crate::game::settings::Settings;
 use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::SAVE;
 use chrono::Local;
 use rustc_hash::FxHashMap;
 use serde_json::{from_str, from_value, to_string_pretty, to_value, Map, Value};
+use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread::sleep;
 use std::time::Duration;
+use tracing::{debug, error, info};
 use crate::game::data::player_data::PlayerData;
 
 const SAVE_FILE: &str = "saved_file";
 
+// Saves and the log file sit next to the executable rather than wherever the game was launched from
+pub fn data_path(file_name: &str) -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_default()
+        .join(file_name)
+}
+
 pub fn load_game_or_new() -> GameData {
     let game_data = GameData::new();
 
-    if let Ok(save_data) = fs::read_to_string(SAVE_FILE) {
+    if let Ok(save_data) = fs::read_to_string(data_path(SAVE_FILE)) {
         if let Ok(json_data) = from_str::<Value>(&save_data) {
             if let Some(player_data) = json_data.get("player_data")
                 .and_then(|v| from_value::<PlayerData>(v.clone()).ok()) {
                 *acquire_lock_mut(&game_data.player_data, "player_data") = player_data;
-                println!("Loaded player data successfully!");
+                info!(target: SAVE, "Loaded player data successfully!");
             }
 
             if let Some(settings) = json_data.get("settings")
                 .and_then(|v| from_value::<Settings>(v.clone()).ok()) {
                 game_data.set_field(SETTINGS, settings);
-                println!("Loaded settings successfully!");
+                info!(target: SAVE, "Loaded settings successfully!");
             }
         }
     }
@@ -53,8 +66,10 @@ pub fn save_game(game_data: &Arc<GameData>) {
 
     if !save_map.is_empty() {
         if let Ok(serialized_data) = to_string_pretty(&Value::Object(save_map)) {
-            if fs::write(SAVE_FILE, serialized_data).is_ok() {
-                println!("Game saved successfully {}", Local::now().to_rfc2822());
+            let save_path = data_path(SAVE_FILE);
+            match fs::write(&save_path, serialized_data) {
+                Ok(()) => debug!(target: SAVE, "Game saved successfully {}", Local::now().to_rfc2822()),
+                Err(e) => error!(target: SAVE, "Failed to write {}: {}", save_path.display(), e),
             }
         }
     }
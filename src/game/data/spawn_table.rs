@@ -0,0 +1,79 @@
+use crate::game::data::boss_definition::BossEncounter;
+use crate::game::objects::unit_defaults::EnemyName;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SpawnTable {
+    pub enemy_cap: usize,
+    // In tiles, spawns closer than this to the player are rejected
+    pub min_player_distance: f32,
+    pub difficulty: DifficultyRamp,
+    #[serde(default)]
+    pub elites: EliteChance,
+    pub waves: Vec<Wave>,
+    // Each encounter triggers at most once per run
+    #[serde(default)]
+    pub bosses: Vec<BossEncounter>,
+}
+
+// Times are seconds of run time
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Wave {
+    pub start_at: f32,
+    pub end_at: Option<f32>,
+    // None spawns the groups once at start_at
+    pub interval: Option<f32>,
+    pub groups: Vec<SpawnGroup>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SpawnGroup {
+    // Weighted choice per spawned unit
+    pub composition: Vec<(EnemyName, u32)>,
+    pub count: u32,
+    pub pattern: SpawnPattern,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub enum SpawnPattern {
+    // Evenly spaced around the player, radius in tiles
+    Ring { radius: f32 },
+    // Just outside the visible area
+    OffScreen,
+    SpawnPoints,
+    Anywhere,
+}
+
+// Multipliers grow linearly per minute of run time up to their max
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct DifficultyRamp {
+    pub count_per_minute: f32,
+    pub max_count_multiplier: f32,
+    pub health_per_minute: f32,
+    pub max_health_multiplier: f32,
+}
+
+impl DifficultyRamp {
+    pub fn count_multiplier(&self, elapsed: f32) -> f32 {
+        (1.0 + self.count_per_minute * elapsed / 60.0).min(self.max_count_multiplier)
+    }
+
+    pub fn health_multiplier(&self, elapsed: f32) -> f32 {
+        (1.0 + self.health_per_minute * elapsed / 60.0).min(self.max_health_multiplier)
+    }
+}
+
+// Chance per spawned enemy, grows linearly per minute of run time up to max_chance
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default)]
+pub struct EliteChance {
+    pub base_chance: f32,
+    pub chance_per_minute: f32,
+    pub max_chance: f32,
+    pub max_affixes: usize,
+}
+
+impl EliteChance {
+    pub fn chance(&self, elapsed: f32) -> f32 {
+        (self.base_chance + self.chance_per_minute * elapsed / 60.0).min(self.max_chance)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use crate::game::events::game_event::GameEvent;
+use std::mem::take;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+
+// Every event published during one simulation tick, in publish order.
+pub type EventBatch = Arc<[GameEvent]>;
+
+#[derive(Default)]
+pub struct EventBus {
+    queued: Vec<GameEvent>,
+    subscribers: Vec<Sender<EventBatch>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn publish(&mut self, event: GameEvent) {
+        self.queued.push(event);
+    }
+
+    pub fn publish_all(&mut self, events: impl IntoIterator<Item = GameEvent>) {
+        self.queued.extend(events);
+    }
+
+    pub fn drain_queued(&mut self) -> Vec<GameEvent> {
+        take(&mut self.queued)
+    }
+
+    // Each subscriber receives one batch per tick that had any events
+    pub fn subscribe(&mut self) -> Receiver<EventBatch> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub fn dispatch(&mut self, events: Vec<GameEvent>) {
+        if events.is_empty() {
+            return;
+        }
+
+        let batch: EventBatch = events.into();
+        self.subscribers.retain(|subscriber| subscriber.send(Arc::clone(&batch)).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(batch: &[GameEvent]) -> Vec<u32> {
+        batch
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::PlayerLeveled { level } => Some(*level),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dispatch_keeps_publish_order() {
+        let mut bus = EventBus::new();
+        let receiver = bus.subscribe();
+
+        bus.publish(GameEvent::PlayerLeveled { level: 1 });
+        bus.publish_all([GameEvent::PlayerLeveled { level: 2 }, GameEvent::PlayerLeveled { level: 3 }]);
+        let queued = bus.drain_queued();
+        bus.dispatch(queued);
+
+        assert_eq!(levels(&receiver.try_recv().unwrap()), vec![1, 2, 3]);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn events_published_while_handling_land_in_the_next_drain() {
+        let mut bus = EventBus::new();
+        bus.publish(GameEvent::PlayerLeveled { level: 1 });
+
+        let first = bus.drain_queued();
+        // A handler reacting to the first drain
+        bus.publish(GameEvent::PlayerLeveled { level: 2 });
+
+        assert_eq!(levels(&first), vec![1]);
+        assert_eq!(levels(&bus.drain_queued()), vec![2]);
+        assert!(bus.drain_queued().is_empty());
+    }
+
+    #[test]
+    fn empty_ticks_send_nothing() {
+        let mut bus = EventBus::new();
+        let receiver = bus.subscribe();
+
+        bus.dispatch(Vec::new());
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn dropped_subscribers_are_removed() {
+        let mut bus = EventBus::new();
+        drop(bus.subscribe());
+        let receiver = bus.subscribe();
+
+        bus.dispatch(vec![GameEvent::PlayerLeveled { level: 1 }]);
+
+        assert_eq!(bus.subscribers.len(), 1);
+        assert_eq!(levels(&receiver.try_recv().unwrap()), vec![1]);
+    }
+}
@@ -0,0 +1,380 @@
+use crate::enums::gamestate::GameState;
+use crate::game::data::chain_arcs::{ChainArc, CHAIN_ARC_DURATION};
+use crate::game::data::damage_numbers::{DamageNumber, DAMAGE_NUMBER_DURATION};
+use crate::game::data::game_data::GameData;
+use crate::game::data::player_level::offer_level_up;
+use crate::game::events::game_event::GameEvent;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::damage::{resolve_damage, DamageType, Hit};
+use crate::game::objects::game_object::{add_units, remove_units};
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_defaults::collectable_01_basic_monster;
+use crate::game::resources::loot::collect_loot;
+use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::GAME_LOOP;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info};
+
+// Reactions can publish further events, so keep draining until the queue settles,
+// then hand the whole tick's batch to subscribers.
+pub fn process_events(game_data: &Arc<GameData>) {
+    let mut batch = Vec::new();
+
+    loop {
+        let events = acquire_lock_mut(&game_data.events, "events").drain_queued();
+        if events.is_empty() {
+            break;
+        }
+
+        handle_player_death(&events, game_data);
+        handle_boss_deaths(&events, game_data);
+        handle_explosions(&events, game_data);
+        handle_unit_splits(&events, game_data);
+        handle_loot_drops(&events, game_data);
+        handle_tile_changes(&events, game_data);
+        handle_loot_collected(&events, game_data);
+        handle_level_ups(&events, game_data);
+        handle_damage_numbers(&events, game_data);
+        handle_chain_arcs(&events, game_data);
+        handle_run_stats(&events, game_data);
+        log_events(&events);
+
+        batch.extend(events);
+    }
+
+    acquire_lock_mut(&game_data.events, "events").dispatch(batch);
+}
+
+fn handle_player_death(events: &[GameEvent], game_data: &GameData) {
+    let player_died = events.iter().any(|event| matches!(event, GameEvent::UnitDied { object_type: ObjectType::Player, .. }));
+    if !player_died {
+        return;
+    }
+
+    *acquire_lock_mut(&game_data.player_dead, "player_dead") = true;
+
+    // Written directly rather than through set_game_state so the loop stays active for reset_on_death
+    let mut game_state = acquire_lock_mut(&game_data.game_state, "game_state");
+    let previous_state = *game_state;
+    *game_state = GameState::Dead;
+    drop(game_state);
+
+    acquire_lock_mut(&game_data.events, "events").publish(GameEvent::StateChanged { from: previous_state, to: GameState::Dead });
+}
+
+fn handle_boss_deaths(events: &[GameEvent], game_data: &GameData) {
+    let mut spawn_director = acquire_lock_mut(&game_data.spawn_director, "spawn_director");
+    let Some(spawn_director) = spawn_director.as_mut() else { return; };
+
+    let defeated: Vec<GameEvent> = events
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::UnitDied { unit_id, object_type: ObjectType::Enemy, position, .. } if spawn_director.on_unit_died(*unit_id) => {
+                Some(GameEvent::BossDefeated { unit_id: *unit_id, position: *position })
+            }
+            _ => None,
+        })
+        .collect();
+
+    if !defeated.is_empty() {
+        acquire_lock_mut(&game_data.events, "events").publish_all(defeated);
+    }
+}
+
+// Elite explosions only hurt the player
+fn handle_explosions(events: &[GameEvent], game_data: &Arc<GameData>) {
+    let Some(player_id) = *acquire_lock(&game_data.player_id, "player_id") else { return; };
+    let mut player_killed = false;
+
+    {
+        let mut units = acquire_lock_mut(&game_data.units, "game_units");
+        let unit_positions = acquire_lock(&game_data.unit_positions, "unit_positions");
+        let mut event_bus = acquire_lock_mut(&game_data.events, "events");
+
+        for event in events {
+            let GameEvent::Explosion { position, radius, damage } = event else { continue; };
+            if player_killed || !units.is_alive(player_id) {
+                break;
+            }
+
+            let Some(&player_position) = unit_positions.get(player_id as usize) else { break; };
+            let offset = player_position.sub(*position);
+            let distance_squared = offset.x as i64 * offset.x as i64 + offset.y as i64 * offset.y as i64;
+            if distance_squared > *radius as i64 * *radius as i64 {
+                continue;
+            }
+
+            let damage = resolve_damage(&units, player_id, Hit::new(*damage, DamageType::Fire), &mut rand::rng());
+            player_killed = units.apply_damage(player_id, damage.amount);
+            event_bus.publish(GameEvent::DamageDealt {
+                target_type: ObjectType::Player,
+                position: player_position,
+                amount: damage.amount,
+                damage_type: damage.damage_type,
+                critical: damage.critical,
+                killed: player_killed,
+            });
+        }
+    }
+
+    if player_killed {
+        remove_units(vec![player_id], Arc::clone(game_data));
+    }
+}
+
+fn handle_unit_splits(events: &[GameEvent], game_data: &GameData) {
+    let mut units = Vec::new();
+    let mut positions = Vec::new();
+
+    for event in events {
+        if let GameEvent::UnitSplit { position, units: children } = event {
+            let count = children.len() as i32;
+
+            // Side by side so they don't start fully overlapped
+            for (index, child) in children.iter().enumerate() {
+                let offset = (index as i32 * 2 - (count - 1)) * child.object_shape.width / 2;
+                units.push(child.clone());
+                positions.push(Pos2FixedPoint::new(position.x + offset, position.y));
+            }
+        }
+    }
+
+    if !units.is_empty() {
+        add_units(units, positions, game_data);
+    }
+}
+
+fn handle_loot_drops(events: &[GameEvent], game_data: &GameData) {
+    let mut collectables = Vec::new();
+    let mut positions = Vec::new();
+
+    for event in events {
+        if let GameEvent::UnitDied { object_type: ObjectType::Enemy, position, loot: Some(loot), .. } = event {
+            collectables.push(collectable_01_basic_monster(Some(loot.clone())));
+            positions.push(*position);
+        }
+    }
+
+    if !collectables.is_empty() {
+        add_units(collectables, positions, game_data);
+    }
+}
+
+// Pathing is rebuilt on the next tick against the changed map
+fn handle_tile_changes(events: &[GameEvent], game_data: &GameData) {
+    let mut collectables = Vec::new();
+    let mut positions = Vec::new();
+    let mut tiles_changed = false;
+
+    for event in events {
+        if let GameEvent::TileChanged { position, loot, .. } = event {
+            tiles_changed = true;
+            if let Some(loot) = loot {
+                collectables.push(collectable_01_basic_monster(Some(loot.clone())));
+                positions.push(*position);
+            }
+        }
+    }
+
+    if tiles_changed {
+        *acquire_lock_mut(&game_data.flow_field, "flow_field") = None;
+        if let Some(visibility) = acquire_lock_mut(&game_data.visibility, "visibility").as_mut() {
+            Arc::make_mut(visibility).invalidate();
+        }
+    }
+
+    if !collectables.is_empty() {
+        add_units(collectables, positions, game_data);
+    }
+}
+
+fn handle_loot_collected(events: &[GameEvent], game_data: &Arc<GameData>) {
+    let loot: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::LootCollected { loot, .. } => Some(loot.clone()),
+            _ => None,
+        })
+        .collect();
+
+    if !loot.is_empty() {
+        collect_loot(loot, Arc::clone(game_data));
+    }
+}
+
+fn handle_level_ups(events: &[GameEvent], game_data: &GameData) {
+    if !events.iter().any(|event| matches!(event, GameEvent::LootCollected { .. })) {
+        return;
+    }
+
+    let total_exp = acquire_lock(&game_data.resource_amounts, "resource_amounts").exp.unwrap_or(0.0);
+    let levels = acquire_lock_mut(&game_data.player_level, "player_level").gain_levels(total_exp);
+    if levels.is_empty() {
+        return;
+    }
+
+    acquire_lock_mut(&game_data.events, "events").publish_all(levels.into_iter().map(|level| GameEvent::PlayerLeveled { level }));
+    offer_level_up(game_data);
+}
+
+fn handle_damage_numbers(events: &[GameEvent], game_data: &GameData) {
+    let mut damage_numbers = acquire_lock_mut(&game_data.damage_numbers, "damage_numbers");
+    damage_numbers.retain(|damage_number| damage_number.spawn_time.elapsed() < DAMAGE_NUMBER_DURATION);
+
+    for event in events {
+        match event {
+            GameEvent::DamageDealt { position, amount, damage_type, critical, killed: false, .. } => damage_numbers.push(DamageNumber {
+                position: *position,
+                value: *amount,
+                spawn_time: Instant::now(),
+                colour: damage_type.colour(),
+                critical: *critical,
+            }),
+            GameEvent::StatusDamage { position, amount, effect_type, killed: false, .. } => damage_numbers.push(DamageNumber {
+                position: *position,
+                value: *amount,
+                spawn_time: Instant::now(),
+                colour: effect_type.tint(),
+                critical: false,
+            }),
+            _ => {}
+        }
+    }
+}
+
+// Old arcs are dropped whenever new ones come in
+fn handle_chain_arcs(events: &[GameEvent], game_data: &GameData) {
+    if !events.iter().any(|event| matches!(event, GameEvent::ChainJumped { .. })) {
+        return;
+    }
+
+    let mut chain_arcs = acquire_lock_mut(&game_data.chain_arcs, "chain_arcs");
+    chain_arcs.retain(|chain_arc| chain_arc.spawn_time.elapsed() < CHAIN_ARC_DURATION);
+
+    for event in events {
+        if let GameEvent::ChainJumped { from, to, .. } = event {
+            chain_arcs.push(ChainArc { from: *from, to: *to, spawn_time: Instant::now() });
+        }
+    }
+}
+
+fn handle_run_stats(events: &[GameEvent], game_data: &GameData) {
+    let player_id = *acquire_lock(&game_data.player_id, "player_id");
+    let mut run_stats = acquire_lock_mut(&game_data.run_stats, "run_stats");
+
+    for event in events {
+        match event {
+            GameEvent::UnitDied { object_type: ObjectType::Enemy, .. } => run_stats.enemies_killed += 1,
+            GameEvent::DamageDealt { target_type: ObjectType::Player, amount, .. } => run_stats.damage_taken += amount,
+            GameEvent::DamageDealt { target_type: ObjectType::Enemy, amount, .. } => run_stats.damage_dealt += amount,
+            GameEvent::StatusDamage { target_type: ObjectType::Player, amount, .. } => run_stats.damage_taken += amount,
+            GameEvent::StatusDamage { target_type: ObjectType::Enemy, amount, .. } => run_stats.damage_dealt += amount,
+            GameEvent::LootCollected { loot, .. } => {
+                run_stats.gold_collected += loot.gold;
+                run_stats.exp_collected += loot.exp;
+            }
+            GameEvent::AttackCast { caster_id: Some(caster_id), .. } if Some(*caster_id) == player_id => run_stats.attacks_cast += 1,
+            GameEvent::BossDefeated { .. } => run_stats.bosses_defeated += 1,
+            GameEvent::PlayerLeveled { level } => run_stats.level_reached = run_stats.level_reached.max(*level),
+            _ => {}
+        }
+    }
+}
+
+fn log_events(events: &[GameEvent]) {
+    for event in events {
+        match event {
+            GameEvent::BossSpawned { unit_id, title, position } => info!(target: GAME_LOOP, "Boss {} ({}) spawned at {:?}", title, unit_id, position),
+            GameEvent::BossDefeated { unit_id, position } => info!(target: GAME_LOOP, "Boss {} defeated at {:?}", unit_id, position),
+            GameEvent::StateChanged { from, to } => debug!(target: GAME_LOOP, "Game state {:?} -> {:?}", from, to),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::events::event_bus::EventBatch;
+    use crate::game::maths::pos_2::FIXED_POINT_SCALE;
+    use crate::game::objects::animation::Animation;
+    use crate::game::objects::elite_affix::EliteAffix;
+    use crate::game::objects::game_object::GameObject;
+    use crate::game::objects::loot::Loot;
+    use crate::game::objects::object_shape::ObjectShape;
+    use crate::game::objects::unit_defaults::{create_enemy, EnemyName};
+    use crate::ui::asset::sprite::sprite_sheet::BABY_GREEN_DRAGON;
+    use std::sync::mpsc::Receiver;
+    use std::time::Duration;
+
+    const PLAYER_POSITION: Pos2FixedPoint = Pos2FixedPoint { x: 1000 * FIXED_POINT_SCALE, y: 1000 * FIXED_POINT_SCALE };
+
+    // A playing run with the player at PLAYER_POSITION and one enemy beside them
+    fn run(player_health: f32, enemy: GameObject) -> (Arc<GameData>, Receiver<EventBatch>, u32, u32) {
+        let game_data = Arc::new(GameData::new());
+        *acquire_lock_mut(&game_data.game_state, "game_state") = GameState::Playing;
+        let events = acquire_lock_mut(&game_data.events, "events").subscribe();
+
+        let animation = Animation::new(BABY_GREEN_DRAGON, Duration::from_secs(2), (50, 50));
+        let player = GameObject::new(ObjectType::Player, ObjectShape::new(40 * FIXED_POINT_SCALE, 40 * FIXED_POINT_SCALE), 0, 100.0, player_health, Some(animation));
+        let enemy_position = Pos2FixedPoint::new(PLAYER_POSITION.x + 20 * FIXED_POINT_SCALE, PLAYER_POSITION.y);
+        let ids = add_units(vec![player, enemy], vec![PLAYER_POSITION, enemy_position], &game_data);
+        *acquire_lock_mut(&game_data.player_id, "player_id") = Some(ids[0]);
+
+        (game_data, events, ids[0], ids[1])
+    }
+
+    fn kill(game_data: &Arc<GameData>, unit_id: u32, events: &Receiver<EventBatch>) -> Vec<GameEvent> {
+        remove_units(vec![unit_id], Arc::clone(game_data));
+        process_events(game_data);
+        events.try_iter().flat_map(|batch| batch.to_vec()).collect()
+    }
+
+    #[test]
+    fn enemy_deaths_drop_their_loot() {
+        let mut enemy = create_enemy(EnemyName::BabyDragon);
+        enemy.loot = Some(Loot { gold: 5.0, exp: 2.0, ruby: 0.0 });
+        let (game_data, events, _, enemy_id) = run(100.0, enemy);
+
+        let events = kill(&game_data, enemy_id, &events);
+
+        assert!(matches!(events.as_slice(), [GameEvent::UnitDied { object_type: ObjectType::Enemy, loot: Some(_), .. }]));
+        let units = acquire_lock(&game_data.units, "game_units");
+        let collectable = units.ids_of_type(ObjectType::Collectable).next().expect("loot should drop");
+        assert_eq!(units.loot[collectable as usize].as_ref().map(|loot| loot.gold), Some(5.0));
+        assert_eq!(acquire_lock(&game_data.run_stats, "run_stats").enemies_killed, 1);
+    }
+
+    #[test]
+    fn player_deaths_end_the_run() {
+        let (game_data, events, player_id, _) = run(100.0, create_enemy(EnemyName::BabyDragon));
+
+        let events = kill(&game_data, player_id, &events);
+
+        assert!(matches!(events.as_slice(), [
+            GameEvent::UnitDied { object_type: ObjectType::Player, .. },
+            GameEvent::StateChanged { from: GameState::Playing, to: GameState::Dead },
+        ]));
+        assert!(*acquire_lock(&game_data.player_dead, "player_dead"));
+    }
+
+    #[test]
+    fn explosive_elites_can_kill_the_player_as_they_die() {
+        let mut enemy = create_enemy(EnemyName::BabyDragon);
+        enemy.affixes = vec![EliteAffix::Explosive];
+        enemy.loot = None;
+        let (game_data, events, _, enemy_id) = run(1.0, enemy);
+
+        let events = kill(&game_data, enemy_id, &events);
+
+        assert!(matches!(events.as_slice(), [
+            GameEvent::UnitDied { object_type: ObjectType::Enemy, .. },
+            GameEvent::Explosion { .. },
+            GameEvent::DamageDealt { target_type: ObjectType::Player, killed: true, .. },
+            GameEvent::UnitDied { object_type: ObjectType::Player, .. },
+            GameEvent::StateChanged { to: GameState::Dead, .. },
+        ]));
+        assert_eq!(acquire_lock(&game_data.game_state, "game_state").clone(), GameState::Dead);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::enums::gamestate::GameState;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::damage::DamageType;
+use crate::game::objects::game_object::GameObject;
+use crate::game::objects::loot::Loot;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::sound::Sound;
+use crate::game::objects::status_effect::StatusEffectType;
+
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    UnitDied {
+        unit_id: u32,
+        object_type: ObjectType,
+        position: Pos2FixedPoint,
+        loot: Option<Loot>,
+        sound: Option<Sound>,
+    },
+    DamageDealt {
+        target_type: ObjectType,
+        position: Pos2FixedPoint,
+        amount: f64,
+        damage_type: DamageType,
+        critical: bool,
+        killed: bool,
+    },
+    // Burn and poison ticks, these have no attack behind them
+    StatusDamage {
+        target_type: ObjectType,
+        effect_type: StatusEffectType,
+        position: Pos2FixedPoint,
+        amount: f64,
+        killed: bool,
+    },
+    ChainJumped {
+        from: Pos2FixedPoint,
+        to: Pos2FixedPoint,
+    },
+    LootCollected {
+        loot: Loot,
+    },
+    AttackCast {
+        caster_id: Option<u32>,
+    },
+    Explosion {
+        position: Pos2FixedPoint,
+        radius: i32,
+        damage: f64,
+    },
+    UnitSplit {
+        position: Pos2FixedPoint,
+        units: Vec<GameObject>,
+    },
+    BossSpawned {
+        unit_id: u32,
+        title: String,
+        position: Pos2FixedPoint,
+    },
+    BossDefeated {
+        unit_id: u32,
+        position: Pos2FixedPoint,
+    },
+    TileChanged {
+        position: Pos2FixedPoint,
+        loot: Option<Loot>,
+    },
+    PlayerLeveled {
+        level: u32,
+    },
+    StateChanged {
+        from: GameState,
+        to: GameState,
+    },
+}
@@ -0,0 +1,3 @@
+pub mod game_event;
+pub mod event_bus;
+pub mod event_handlers;
\ No newline at end of file
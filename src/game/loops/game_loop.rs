@@ -3,39 +3,62 @@ use crate::enums::gametab::GameTab;
 use crate::enums::gametab::GameTab::NullGameTab;
 use crate::game::collision::detect_collision::handle_collision;
 use crate::game::constants::GAME_RATE;
+use crate::game::data::attack_loadout::ability_slot_for_key;
 use crate::game::data::game_data::GameData;
 use crate::game::data::stored_data::{CURRENT_TAB, GAME_IN_FOCUS, KEY_STATE};
+use crate::game::events::event_handlers::process_events;
+use crate::game::events::game_event::GameEvent;
 use crate::game::loops::key_state::KeyState;
+use crate::game::map::flow_field::FlowField;
+use crate::game::map::game_map::GameMap;
+use crate::game::map::game_tile::GameTile;
+use crate::game::map::tile_type::TileType;
+use crate::game::map::terrain::{apply_terrain_health, terrain_effect_at, terrain_movement};
 use crate::game::maths::integers::int_sqrt_64;
 use crate::game::maths::pos_2::{Pos2FixedPoint, INVALID_POSITION};
 use crate::game::objects::attacks::attack_defaults::get_modified_attack;
 use crate::game::objects::attacks::attack_stats::AttackName;
 use crate::game::objects::attacks::create_attacks::{despawn_attack, spawn_attack};
-use crate::game::objects::game_object::move_units_batched;
+use crate::game::objects::attacks::targeting::{direction_to, nearest_target, steer_towards, TargetingContext};
+use crate::game::objects::behaviour::update_behaviours;
+use crate::game::objects::elite_affix::regenerate;
+use crate::game::objects::game_object::{move_units_batched, remove_units};
 use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::status_effect::{attack_speed_multiplier, can_attack, movement_multiplier, tick_status_effects};
+use crate::game::objects::unit_store::UnitStore;
 use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::GAME_LOOP;
+use crate::helper::triple_buffer::TripleBufferWriter;
+use crate::ui::graphics::rendering_data::RenderData;
 use device_query_revamped::Keycode;
 use rand::prelude::IndexedRandom;
 use rayon::current_num_threads;
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
-use rayon::prelude::ParallelSliceMut;
+use rayon::prelude::{IndexedParallelIterator, ParallelSlice};
 use std::cmp::max;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
+use rustc_hash::FxHashSet;
+use tracing::{debug, trace, trace_span};
 use crate::game::loops::idle_loop::IdleLoop;
 
 pub struct GameLoop {
     pub game_data: Arc<GameData>,
     pub updated_at: Instant,
+    render_snapshot: TripleBufferWriter<RenderData>,
+    // Portals only fire when the player steps onto them, not when arriving through one
+    last_player_tile: Option<(usize, usize)>,
 }
 
 impl GameLoop {
-    pub fn new(game_data: Arc<GameData>) -> Self {
+    pub fn new(game_data: Arc<GameData>, render_snapshot: TripleBufferWriter<RenderData>) -> Self {
         Self {
             game_data,
             updated_at: Instant::now(),
+            render_snapshot,
+            last_player_tile: None,
         }
     }
 
@@ -43,11 +66,30 @@ impl GameLoop {
         let delta_time = Instant::now().duration_since(self.updated_at).as_secs_f64();
         self.updated_at = Instant::now();
 
-        self.handle_input_actions();
-        self.handle_animations(delta_time);
-        self.handle_attacks(delta_time);
-        self.handle_movement(delta_time);
-        self.reset_on_death();
+        let _update_span = trace_span!(target: GAME_LOOP, "update", delta_time).entered();
+
+        timed_phase("input", || self.handle_input_actions());
+        timed_phase("animations", || self.handle_animations(delta_time));
+        timed_phase("attacks", || self.handle_attacks(delta_time));
+        timed_phase("homing", || self.handle_homing(delta_time));
+        timed_phase("regeneration", || self.handle_regeneration(delta_time));
+        // Spawning runs before anything that can kill, so a dead boss's id isn't reused or forgotten before its death event is handled
+        timed_phase("spawning", || self.handle_spawning(delta_time));
+        timed_phase("status_effects", || self.handle_status_effects(delta_time));
+        timed_phase("pathfinding", || self.handle_pathfinding());
+        timed_phase("behaviour", || self.handle_behaviours(delta_time));
+        timed_phase("movement", || self.handle_movement(delta_time));
+        timed_phase("terrain", || self.handle_terrain(delta_time));
+        timed_phase("tile_interactions", || self.handle_tile_interactions());
+        timed_phase("events", || process_events(&self.game_data));
+        timed_phase("visibility", || self.handle_visibility());
+        timed_phase("reset_on_death", || self.reset_on_death());
+        timed_phase("render_snapshot", || self.publish_render_snapshot());
+    }
+
+    fn publish_render_snapshot(&mut self) {
+        self.render_snapshot.write().update_from(&self.game_data);
+        self.render_snapshot.publish();
     }
 
     fn reset_on_death(&mut self) {
@@ -55,16 +97,21 @@ impl GameLoop {
         let reset_complete = self.game_data.reset_complete.load(Ordering::Relaxed);
 
         if game_state == GameState::Dead && !reset_complete {
+            *acquire_lock_mut(&self.game_data.spawn_director, "spawn_director") = None;
+
             let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
             let mut unit_positions = acquire_lock_mut(&self.game_data.unit_positions, "unit_positions");
             let mut empty_unit_indexes = acquire_lock_mut(&self.game_data.empty_unit_indexes, "empty_unit_indexes");
             let mut spatial_hash_grid = acquire_lock_mut(&self.game_data.spatial_hash_grid, "spatial_hash_grid");
             let mut damage_numbers = acquire_lock_mut(&self.game_data.damage_numbers, "damage_numbers");
+            let mut chain_arcs = acquire_lock_mut(&self.game_data.chain_arcs, "chain_arcs");
             let mut game_map = acquire_lock_mut(&self.game_data.game_map, "game_map");
-            let mut player_data = acquire_lock_mut(&self.game_data.player_data, "game_map");
+            let mut flow_field = acquire_lock_mut(&self.game_data.flow_field, "flow_field");
+            let mut visibility = acquire_lock_mut(&self.game_data.visibility, "visibility");
+            let mut player_data = acquire_lock_mut(&self.game_data.player_data, "player_data");
 
             let persistent_resources = &mut player_data.resources_persistent;
-            let current_resources = acquire_lock(&self.game_data.resource_amounts, "game_map").clone();
+            let current_resources = acquire_lock(&self.game_data.resource_amounts, "resource_amounts").clone();
 
             IdleLoop::add_production(persistent_resources, &current_resources);
             game_units.clear();
@@ -72,7 +119,10 @@ impl GameLoop {
             empty_unit_indexes.clear();
             spatial_hash_grid.clear();
             damage_numbers.clear();
+            chain_arcs.clear();
             *game_map = None;
+            *flow_field = None;
+            *visibility = None;
             self.game_data.reset_complete.store(true, Ordering::Relaxed);
         }
     }
@@ -85,23 +135,32 @@ impl GameLoop {
         let (player_id, player_position) = get_player_position(&self.game_data);
 
         if let Some(player_id) = player_id {
-            let attack_name = Some(AttackName::LightningBolt);
             while let Some(key) = key_queue.pop() {
-                match key {
-                    Keycode::Space => {
-                        if !player_dead && game_loop_active {
-                            if let Some(attack_name) = &attack_name {
-                                println!("Spawning {:?} attack at {:?}", attack_name.clone(), player_position);
-                                spawn_attack(Arc::clone(&self.game_data), attack_name.clone(), player_position, Some(player_id), true);
-                            } else {
-                                println!("Player has no attacks assigned.");
-                            }
-                        }
-                    }
-                    Keycode::Escape => {
-                        self.game_data.set_game_state(GameState::Paused);
-                    }
-                    _ => {}
+                if key == Keycode::Escape {
+                    self.game_data.set_game_state(GameState::Paused);
+                    continue;
+                }
+
+                let Some(slot) = ability_slot_for_key(key) else { continue; };
+                if player_dead || !game_loop_active {
+                    continue;
+                }
+
+                let (upgrades, stunned) = {
+                    let game_units = acquire_lock(&self.game_data.units, "game_units");
+                    (game_units.upgrades[player_id as usize].clone(), !can_attack(&game_units.status_effects[player_id as usize]))
+                };
+                if stunned {
+                    trace!(target: GAME_LOOP, "Can't cast ability slot {} while stunned", slot);
+                    continue;
+                }
+
+                let attack_name = acquire_lock_mut(&self.game_data.hotbar, "hotbar").try_cast(slot, &upgrades);
+                if let Some(attack_name) = attack_name {
+                    trace!(target: GAME_LOOP, "Spawning {:?} attack at {:?}", attack_name, player_position);
+                    spawn_attack(Arc::clone(&self.game_data), attack_name, player_position, Some(player_id), true);
+                } else {
+                    trace!(target: GAME_LOOP, "Ability slot {} is empty or cooling down", slot);
                 }
             }
         }
@@ -110,34 +169,37 @@ impl GameLoop {
     fn handle_attacks(&self, delta_time: f64) {
         let mut expired_attacks = Vec::new();
         let mut attacks_to_spawn: Vec<(AttackName, Pos2FixedPoint, u32)> = Vec::new();
+        let player_id = *acquire_lock(&self.game_data.player_id, "player_id");
 
         {
             let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
-            let mut unit_positions = acquire_lock_mut(&self.game_data.unit_positions, "unit_positions");
+            let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
+            let UnitStore { object_types, attack_stats, attack_cooldowns, upgrades, status_effects, .. } = &mut *game_units;
 
             // **Handle attack lifetimes** → Remove expired attacks
-            for unit in game_units.iter_mut().flatten() {
-                if unit.object_type == ObjectType::Attack {
-                    if let Some(attack_stats) = &mut unit.attack_stats {
+            for (attack_id, attack_stats) in attack_stats.iter_mut().enumerate() {
+                if object_types[attack_id] == Some(ObjectType::Attack) {
+                    if let Some(attack_stats) = attack_stats {
                         attack_stats.elapsed += delta_time as f32;
 
-                        if attack_stats.elapsed >= attack_stats.lifetime || attack_stats.hit_count >= attack_stats.max_targets {
-                            expired_attacks.push(unit.id);
+                        if attack_stats.elapsed >= attack_stats.lifetime || attack_stats.hit_count >= attack_stats.target_limit() {
+                            expired_attacks.push(attack_id as u32);
                         }
                     }
                 }
             }
 
-            for unit in game_units.iter_mut().flatten() {
-                if unit.object_type != ObjectType::Attack {
-                    let unit_position = unit_positions[unit.id as usize];
-                    for (attack_name, cooldown) in unit.attack_cooldowns.iter_mut() {
-                        *cooldown -= delta_time as f32;
-                        if *cooldown <= 0.0 {
-                            let attack = get_modified_attack(&unit.upgrades, attack_name.clone());
+            for (unit_id, cooldowns) in attack_cooldowns.iter_mut().enumerate() {
+                if object_types[unit_id].is_some_and(|object_type| object_type != ObjectType::Attack) {
+                    let unit_position = unit_positions[unit_id];
+                    let attack_speed = attack_speed_multiplier(&status_effects[unit_id]);
+                    for (attack_name, cooldown) in cooldowns.iter_mut() {
+                        *cooldown -= delta_time as f32 * attack_speed;
+                        if *cooldown <= 0.0 && attack_speed > 0.0 {
+                            let attack = get_modified_attack(&upgrades[unit_id], attack_name.clone());
                             if let Some(attack_stats) = attack.attack_stats.as_ref() {
                                 if !attack_stats.proximity_attack {
-                                    attacks_to_spawn.push((attack_name.clone(), unit_position, unit.id));
+                                    attacks_to_spawn.push((attack_name.clone(), unit_position, unit_id as u32));
                                     *cooldown = attack_stats.cooldown;
                                 }
                             }
@@ -145,6 +207,13 @@ impl GameLoop {
                     }
                 }
             }
+
+            let player_attack_speed = player_id.and_then(|id| status_effects.get(id as usize)).map_or(1.0, |effects| attack_speed_multiplier(effects));
+            let mut hotbar = acquire_lock_mut(&self.game_data.hotbar, "hotbar");
+            hotbar.tick(delta_time as f32 * player_attack_speed);
+            if let Some(player_id) = player_id.filter(|&id| (id as usize) < attack_cooldowns.len()) {
+                hotbar.sync_auto_cast(&attack_cooldowns[player_id as usize], &upgrades[player_id as usize]);
+            }
         }
 
         for attack_id in expired_attacks {
@@ -156,21 +225,139 @@ impl GameLoop {
         }
     }
 
+    // Homing projectiles turn towards the nearest enemy they haven't hit yet
+    fn handle_homing(&self, delta_time: f64) {
+        let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+        let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
+        let spatial_grid = acquire_lock(&self.game_data.spatial_hash_grid, "spatial_hash_grid");
+
+        let steering: Vec<(usize, (f32, f32))> = game_units.attack_stats
+            .iter()
+            .enumerate()
+            .filter_map(|(attack_id, attack_stats)| {
+                let attack_stats = attack_stats.as_ref().filter(|attack_stats| attack_stats.enabled && attack_stats.homing_turn_rate > 0.0)?;
+                let origin = *unit_positions.get(attack_id)?;
+                let context = TargetingContext {
+                    units: &game_units,
+                    unit_positions: &unit_positions,
+                    spatial_hash_grid: &spatial_grid,
+                    caster_id: game_units.parent_ids[attack_id],
+                    origin,
+                    cursor_position: None,
+                };
+                let target_id = nearest_target(attack_stats.homing_range, &context, |id| attack_stats.units_hit.contains(&id))?;
+                let desired = direction_to(origin, unit_positions[target_id as usize])?;
+                let max_turn = attack_stats.homing_turn_rate.to_radians() * delta_time as f32;
+                Some((attack_id, steer_towards(attack_stats.direction, desired, max_turn)))
+            })
+            .collect();
+
+        for (attack_id, direction) in steering {
+            if let Some(attack_stats) = game_units.attack_stats[attack_id].as_mut() {
+                attack_stats.direction = direction;
+            }
+        }
+    }
+
+    fn handle_regeneration(&self, delta_time: f64) {
+        let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+        regenerate(&mut game_units, delta_time as f32);
+    }
+
+    // Burn and poison ticks are published so they show up as damage numbers in the effect's colour
+    fn handle_status_effects(&self, delta_time: f64) {
+        let (killed, status_events) = {
+            let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+            let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
+            let ticks = tick_status_effects(&mut game_units, delta_time as f32);
+
+            let status_events: Vec<GameEvent> = ticks
+                .iter()
+                .filter_map(|tick| Some(GameEvent::StatusDamage {
+                    target_type: game_units.object_type(tick.unit_id)?,
+                    effect_type: tick.effect_type,
+                    position: *unit_positions.get(tick.unit_id as usize)?,
+                    amount: tick.amount as f64,
+                    killed: tick.killed,
+                }))
+                .collect();
+            let killed: Vec<u32> = ticks.iter().filter(|tick| tick.killed).map(|tick| tick.unit_id).collect();
+            (killed, status_events)
+        };
+
+        if !status_events.is_empty() {
+            acquire_lock_mut(&self.game_data.events, "events").publish_all(status_events);
+        }
+
+        if !killed.is_empty() {
+            trace!(target: GAME_LOOP, "{} units killed by status effects", killed.len());
+            remove_units(killed, Arc::clone(&self.game_data));
+        }
+    }
+
+    fn handle_spawning(&self, delta_time: f64) {
+        let mut spawn_director = acquire_lock_mut(&self.game_data.spawn_director, "spawn_director");
+        if let Some(spawn_director) = spawn_director.as_mut() {
+            spawn_director.update(delta_time as f32, &self.game_data);
+        }
+    }
+
     fn handle_animations(&self, delta_time: f64) {
         let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
-        game_units.par_iter_mut().for_each(|unit| {
-            if let Some(unit) = unit {
-                if let Some(mut animation) = unit.animation.as_mut() {
-                    if !animation.fixed_frame_index.is_some() {
-                        animation.animation_frame =
-                            (animation.animation_frame + delta_time as f32 / animation.animation_length.as_secs_f32()).fract();
-                    }
+        game_units.animations.par_iter_mut().for_each(|animation| {
+            if let Some(animation) = animation.as_mut() {
+                if !animation.fixed_frame_index.is_some() {
+                    animation.animation_frame =
+                        (animation.animation_frame + delta_time as f32 / animation.animation_length.as_secs_f32()).fract();
                 }
             }
         });
         drop(game_units);
     }
 
+    // Only rebuilt when the player moves onto another tile
+    fn handle_pathfinding(&self) {
+        let Some(game_map) = acquire_lock(&self.game_data.game_map, "game_map").clone() else { return; };
+        let Some(player_position) = *acquire_lock(&self.game_data.player_position, "player_position") else { return; };
+        let Some(player_tile) = game_map.tile_at(player_position) else { return; };
+
+        let current_target = acquire_lock(&self.game_data.flow_field, "flow_field").as_ref().map(|flow_field| flow_field.target);
+        if current_target == Some(player_tile) {
+            return;
+        }
+
+        let flow_field = FlowField::build(&game_map, player_tile);
+        trace!(target: GAME_LOOP, "Rebuilt flow field towards {:?}", player_tile);
+        *acquire_lock_mut(&self.game_data.flow_field, "flow_field") = Some(Arc::new(flow_field));
+    }
+
+    // Runs after events so doors opened or walls broken this tick are seen through straight away
+    fn handle_visibility(&self) {
+        let Some(game_map) = acquire_lock(&self.game_data.game_map, "game_map").clone() else { return; };
+        let Some(player_position) = *acquire_lock(&self.game_data.player_position, "player_position") else { return; };
+        let Some(player_tile) = game_map.tile_at(player_position) else { return; };
+
+        let mut visibility = acquire_lock_mut(&self.game_data.visibility, "visibility");
+        let Some(visibility) = visibility.as_mut() else { return; };
+        if visibility.needs_update(player_tile) {
+            Arc::make_mut(visibility).update(&game_map, player_tile);
+        }
+    }
+
+    fn handle_behaviours(&self, delta_time: f64) {
+        let (_, mut player_position) = get_player_position(&self.game_data);
+        if let Some(position) = *acquire_lock(&self.game_data.player_position, "player_position") {
+            player_position = position;
+        }
+
+        let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+        let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
+        let spatial_grid = acquire_lock(&self.game_data.spatial_hash_grid, "spatial_hash_grid");
+        let flow_field = acquire_lock(&self.game_data.flow_field, "flow_field").clone();
+
+        update_behaviours(&mut game_units, &unit_positions, &spatial_grid, flow_field.as_deref(), player_position, delta_time as f32);
+    }
+
     fn handle_movement(&self, delta_time: f64) {
         let (player_id, mut player_position) = get_player_position(&self.game_data);
 
@@ -180,60 +367,69 @@ impl GameLoop {
             }
         }
 
-        let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+        let game_map = acquire_lock(&self.game_data.game_map, "game_map").clone();
+        let game_units = acquire_lock(&self.game_data.units, "game_units");
         let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
         let current_tab = self.game_data.get_field(CURRENT_TAB).unwrap_or(NullGameTab).clone();
         let in_focus = self.game_data.get_field(GAME_IN_FOCUS).unwrap_or(false).clone();
         let key_state = self.game_data.get_field(KEY_STATE).unwrap_or(Arc::new(KeyState::new())).clone();
 
         let pickup_radius = player_id
-            .and_then(|id| game_units.get(id as usize))
-            .and_then(|unit_option| unit_option.as_ref())
-            .and_then(|player| player.pickup_radius)
-            .unwrap_or(0).clone();
+            .and_then(|id| game_units.pickup_radii.get(id as usize).copied())
+            .flatten()
+            .unwrap_or(0);
 
         let game_units_len = game_units.len();
         let num_threads = current_num_threads();
         let estimated_per_thread = (game_units_len / num_threads).max(1);
 
-        let mut unit_movements: Vec<(u32, Pos2FixedPoint, Pos2FixedPoint)> = game_units
-            .par_chunks_mut(estimated_per_thread)
-            .map(|chunk| {
+        let units = &*game_units;
+        let terrain_move = |unit_index: usize, position: Pos2FixedPoint, intended: (f32, f32)| {
+            let effect = game_map.as_ref().and_then(|game_map| terrain_effect_at(game_map, position, &units.terrain_immunities[unit_index]));
+            terrain_movement(effect, intended, units.velocities[unit_index], delta_time as f32)
+        };
+
+        let mut unit_movements: Vec<(u32, Pos2FixedPoint, Pos2FixedPoint)> = units.object_types
+            .par_chunks(estimated_per_thread)
+            .enumerate()
+            .map(|(chunk_index, chunk)| {
                 let mut local_buffer = Vec::with_capacity(chunk.len() * 2);
-                for unit in chunk.iter_mut() {
-                    if let Some(unit) = unit {
-                        let movement_speed = unit.move_speed;
-                        let distance: f32 = movement_speed as f32 * delta_time as f32;
+                for (offset, object_type) in chunk.iter().enumerate() {
+                    if let Some(object_type) = object_type {
+                        let unit_id = (chunk_index * estimated_per_thread + offset) as u32;
+                        let movement_speed = units.move_speeds[unit_id as usize];
+                        let distance: f32 = movement_speed as f32 * delta_time as f32 * movement_multiplier(&units.status_effects[unit_id as usize]);
 
-                        let old_position = unit_positions[unit.id as usize];
+                        let old_position = unit_positions[unit_id as usize];
                         let mut new_position = old_position;
 
-                        match unit.object_type {
+                        match object_type {
                             ObjectType::Attack => {
-                                if let Some(attack_stats) = &unit.attack_stats {
-                                    new_position.x += (attack_stats.direction.0 * (unit.move_speed) as f32 * delta_time as f32) as i32;
-                                    new_position.y += (attack_stats.direction.1 * (unit.move_speed) as f32 * delta_time as f32) as i32;
+                                if let Some(attack_stats) = &units.attack_stats[unit_id as usize] {
+                                    new_position.x += (attack_stats.direction.0 * movement_speed as f32 * delta_time as f32) as i32;
+                                    new_position.y += (attack_stats.direction.1 * movement_speed as f32 * delta_time as f32) as i32;
                                 }
                             }
                             ObjectType::Player => {
+                                let mut intended = (0.0, 0.0);
                                 if current_tab == GameTab::Adventure && in_focus {
                                     let dx = key_state.d.load(Ordering::Relaxed) as i32 - key_state.a.load(Ordering::Relaxed) as i32;
                                     let dy = key_state.s.load(Ordering::Relaxed) as i32 - key_state.w.load(Ordering::Relaxed) as i32;
 
-                                    new_position.x += dx * distance as i32;
-                                    new_position.y += dy * distance as i32;
+                                    intended = (dx as f32 * distance, dy as f32 * distance);
                                 }
+
+                                let (move_x, move_y) = terrain_move(unit_id as usize, old_position, intended);
+                                new_position.x += move_x as i32;
+                                new_position.y += move_y as i32;
                             }
                             ObjectType::Enemy => {
-                                let direction_vec = player_position.sub(old_position);
-                                let length_squared = direction_vec.x as i64 * direction_vec.x as i64 + direction_vec.y as i64 * direction_vec.y as i64;
-                                if length_squared > 0 {
-                                    let abs_length = int_sqrt_64(length_squared);
-                                    if abs_length > 0 {
-                                        new_position.x += ((direction_vec.x as i64 * distance as i64) / abs_length) as i32;
-                                        new_position.y += ((direction_vec.y as i64 * distance as i64) / abs_length) as i32;
-                                    }
-                                }
+                                let state = &units.behaviour_states[unit_id as usize];
+                                let intended = (state.heading.0 * distance * state.speed_multiplier, state.heading.1 * distance * state.speed_multiplier);
+
+                                let (move_x, move_y) = terrain_move(unit_id as usize, old_position, intended);
+                                new_position.x += move_x as i32;
+                                new_position.y += move_y as i32;
                             }
                             ObjectType::Collectable => {
                                 let direction_vec = player_position.sub(old_position);
@@ -241,7 +437,7 @@ impl GameLoop {
                                 let pickup_radius_squared = pickup_radius as i64 * pickup_radius as i64;
 
                                 if length_squared >= pickup_radius_squared {
-                                    local_buffer.push((unit.id, old_position, new_position));
+                                    local_buffer.push((unit_id, old_position, new_position));
                                     continue;
                                 }
 
@@ -268,7 +464,7 @@ impl GameLoop {
                                 }
                             }
                         };
-                        local_buffer.push((unit.id, old_position, new_position));
+                        local_buffer.push((unit_id, old_position, new_position));
                     } else {
                         let invalid_position = Pos2FixedPoint::new(INVALID_POSITION, INVALID_POSITION);
                         local_buffer.push((u32::MAX, invalid_position, invalid_position));
@@ -290,16 +486,149 @@ impl GameLoop {
 
         let attacks_to_spawn = handle_collision(&mut unit_movements, Arc::clone(&self.game_data), delta_time);
         move_units_batched(&unit_movements, &self.game_data, player_id);
+        self.update_velocities(&unit_movements, delta_time);
 
         for (attack_name, unit_position, unit_id) in attacks_to_spawn {
             spawn_attack(Arc::clone(&self.game_data), attack_name.clone(), unit_position, Some(unit_id), true);
-            let mut units = acquire_lock_mut(&self.game_data.units, "");
-            if let Some(Some(unit)) = units.get_mut(unit_id as usize) {
-                if let Some(attack_stats) = get_modified_attack(&unit.upgrades, attack_name.clone()).attack_stats {
-                    unit.attack_cooldowns.insert(attack_name.clone(), attack_stats.cooldown);
+            let mut units = acquire_lock_mut(&self.game_data.units, "game_units");
+            if units.is_alive(unit_id) {
+                if let Some(attack_stats) = get_modified_attack(&units.upgrades[unit_id as usize], attack_name.clone()).attack_stats {
+                    units.set_cooldown(unit_id, attack_name.clone(), attack_stats.cooldown);
+                }
+            }
+        }
+    }
+
+    // Movement after collision, so walls stop a slide on ice
+    fn update_velocities(&self, unit_movements: &[(u32, Pos2FixedPoint, Pos2FixedPoint)], delta_time: f64) {
+        if delta_time <= 0.0 {
+            return;
+        }
+
+        let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+        for &(unit_id, old_position, new_position) in unit_movements {
+            if matches!(game_units.object_type(unit_id), Some(ObjectType::Player) | Some(ObjectType::Enemy)) {
+                game_units.velocities[unit_id as usize] = (
+                    (new_position.x - old_position.x) as f32 / delta_time as f32,
+                    (new_position.y - old_position.y) as f32 / delta_time as f32,
+                );
+            }
+        }
+    }
+
+    // Lava and thorns damage is published like any hit, so it shows up as damage numbers and in the run stats
+    fn handle_terrain(&self, delta_time: f64) {
+        let Some(game_map) = acquire_lock(&self.game_data.game_map, "game_map").clone() else { return; };
+
+        let (killed, damage_events) = {
+            let mut game_units = acquire_lock_mut(&self.game_data.units, "game_units");
+            let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
+            let damage_dealt = apply_terrain_health(&mut game_units, &unit_positions, &game_map, delta_time as f32);
+
+            let damage_events: Vec<GameEvent> = damage_dealt
+                .iter()
+                .filter_map(|terrain_damage| Some(GameEvent::DamageDealt {
+                    target_type: game_units.object_type(terrain_damage.unit_id)?,
+                    position: *unit_positions.get(terrain_damage.unit_id as usize)?,
+                    amount: terrain_damage.damage.amount,
+                    damage_type: terrain_damage.damage.damage_type,
+                    critical: terrain_damage.damage.critical,
+                    killed: terrain_damage.killed,
+                }))
+                .collect();
+            let killed: Vec<u32> = damage_dealt.iter().filter(|terrain_damage| terrain_damage.killed).map(|terrain_damage| terrain_damage.unit_id).collect();
+            (killed, damage_events)
+        };
+
+        if !damage_events.is_empty() {
+            acquire_lock_mut(&self.game_data.events, "events").publish_all(damage_events);
+        }
+
+        if !killed.is_empty() {
+            trace!(target: GAME_LOOP, "{} units killed by terrain", killed.len());
+            remove_units(killed, Arc::clone(&self.game_data));
+        }
+    }
+
+    fn handle_tile_interactions(&mut self) {
+        let Some(game_map) = acquire_lock(&self.game_data.game_map, "game_map").clone() else { return; };
+        if game_map.links.is_empty() {
+            return;
+        }
+
+        let occupied: FxHashSet<(usize, usize)> = {
+            let game_units = acquire_lock(&self.game_data.units, "game_units");
+            let unit_positions = acquire_lock(&self.game_data.unit_positions, "unit_positions");
+            game_units.ids()
+                .filter(|&id| matches!(game_units.object_type(id), Some(ObjectType::Player) | Some(ObjectType::Enemy)))
+                .filter_map(|id| game_map.tile_at(*unit_positions.get(id as usize)?))
+                .collect()
+        };
+
+        // A door opens while any plate linked to it is pressed, and only closes once nothing stands in it
+        let open_doors: FxHashSet<(usize, usize)> = game_map.links
+            .iter()
+            .filter(|&(&plate, _)| game_map.get_tile(plate.0, plate.1).tile_type == TileType::PressurePlate && occupied.contains(&plate))
+            .flat_map(|(_, doors)| doors.iter().copied())
+            .collect();
+
+        let mut door_changes: Vec<((usize, usize), TileType)> = game_map.links
+            .iter()
+            .filter(|&(&plate, _)| game_map.get_tile(plate.0, plate.1).tile_type == TileType::PressurePlate)
+            .flat_map(|(_, doors)| doors.iter().copied())
+            .filter_map(|door| match (game_map.get_tile(door.0, door.1).tile_type, open_doors.contains(&door)) {
+                (TileType::Door, true) => Some((door, TileType::OpenDoor)),
+                (TileType::OpenDoor, false) if !occupied.contains(&door) => Some((door, TileType::Door)),
+                _ => None,
+            })
+            .collect();
+        door_changes.sort_unstable_by_key(|&(door, _)| door);
+        door_changes.dedup_by_key(|&mut (door, _)| door);
+
+        if !door_changes.is_empty() {
+            if let Some(game_map) = acquire_lock_mut(&self.game_data.game_map, "game_map").as_mut() {
+                let game_map = Arc::make_mut(game_map);
+                for &((x, y), to) in &door_changes {
+                    game_map.set_tile(x, y, GameTile::new(to));
                 }
             }
+
+            let events = door_changes
+                .into_iter()
+                .map(|(door, _)| GameEvent::TileChanged { position: game_map.tile_center(door), loot: None });
+            acquire_lock_mut(&self.game_data.events, "events").publish_all(events);
         }
+
+        self.handle_portals(&game_map);
+    }
+
+    fn handle_portals(&mut self, game_map: &GameMap) {
+        let (Some(player_id), player_position) = get_player_position(&self.game_data) else { return; };
+        let Some(player_tile) = game_map.tile_at(player_position) else { return; };
+
+        let entered_portal = self.last_player_tile != Some(player_tile) && game_map.get_tile(player_tile.0, player_tile.1).tile_type == TileType::Portal;
+        self.last_player_tile = Some(player_tile);
+        if !entered_portal {
+            return;
+        }
+
+        let Some(&exit) = game_map.links.get(&player_tile).and_then(|exits| exits.first()) else { return; };
+        let exit_position = game_map.tile_center(exit);
+
+        {
+            let mut unit_positions = acquire_lock_mut(&self.game_data.unit_positions, "unit_positions");
+            let mut spatial_grid = acquire_lock_mut(&self.game_data.spatial_hash_grid, "spatial_hash_grid");
+            unit_positions[player_id as usize] = exit_position;
+            spatial_grid.move_unit(player_id, player_position, exit_position);
+        }
+
+        *acquire_lock_mut(&self.game_data.player_position, "player_position") = Some(exit_position);
+        let mut camera_state = acquire_lock_mut(&self.game_data.camera_state, "camera_state");
+        camera_state.set_target(exit_position);
+        camera_state.move_to_target();
+
+        self.last_player_tile = Some(exit);
+        debug!(target: GAME_LOOP, "Player took portal {:?} -> {:?}", player_tile, exit);
     }
 
     pub fn start_game(mut self) {
@@ -324,6 +653,12 @@ impl GameLoop {
     }
 }
 
+// Closed spans are logged with their busy time when the game_loop target is at trace
+fn timed_phase<R>(phase: &'static str, f: impl FnOnce() -> R) -> R {
+    let _span = trace_span!(target: GAME_LOOP, "update_phase", phase).entered();
+    f()
+}
+
 pub fn get_player_position(game_data: &GameData) -> (Option<u32>, Pos2FixedPoint) {
     let player_id = *game_data.player_id.read().unwrap();
     let unit_positions = game_data.unit_positions.read().unwrap();
@@ -335,4 +670,67 @@ pub fn get_player_position(game_data: &GameData) -> (Option<u32>, Pos2FixedPoint
     }
 
     (None, Pos2FixedPoint::default())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::data::adventure_definition::{get_adventure_definition, AdventureName};
+    use crate::game::data::initialise_adventure::initialise_adventure;
+    use crate::game::events::event_bus::EventBatch;
+    use crate::game::objects::status_effect::{StatusEffect, StatusEffectType};
+    use crate::helper::triple_buffer::triple_buffer;
+    use std::sync::mpsc::Receiver;
+
+    fn run_tick(game_loop: &mut GameLoop, delta_time: f64, events: &Receiver<EventBatch>) -> Vec<GameEvent> {
+        game_loop.updated_at = Instant::now() - Duration::from_secs_f64(delta_time);
+        game_loop.update();
+        events.try_iter().flat_map(|batch| batch.to_vec()).collect()
+    }
+
+    // Walks the player into the arena and ticks until the boss arrives
+    fn arena_with_boss() -> (GameLoop, Receiver<EventBatch>, u32) {
+        let game_data = Arc::new(GameData::new());
+        initialise_adventure(&game_data, AdventureName::EmberArena);
+        game_data.game_loop_active.store(true, Ordering::Relaxed);
+        let events = acquire_lock_mut(&game_data.events, "events").subscribe();
+
+        let tile_size = get_adventure_definition(AdventureName::EmberArena).tile_size;
+        let arena_centre = Pos2FixedPoint::new(30 * tile_size + tile_size / 2, 15 * tile_size + tile_size / 2);
+        let player_id = game_data.player_id.read().unwrap().unwrap();
+        {
+            let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "unit_positions");
+            let mut spatial_hash_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "spatial_hash_grid");
+            spatial_hash_grid.move_unit(player_id, unit_positions[player_id as usize], arena_centre);
+            unit_positions[player_id as usize] = arena_centre;
+        }
+
+        let mut game_loop = GameLoop::new(game_data, triple_buffer().0);
+        for _ in 0..20 {
+            let boss_id = run_tick(&mut game_loop, 0.016, &events).into_iter().find_map(|event| match event {
+                GameEvent::BossSpawned { unit_id, .. } => Some(unit_id),
+                _ => None,
+            });
+            if let Some(boss_id) = boss_id {
+                return (game_loop, events, boss_id);
+            }
+        }
+        panic!("the arena boss never spawned");
+    }
+
+    #[test]
+    fn bosses_killed_by_status_effects_are_defeated() {
+        let (mut game_loop, events, boss_id) = arena_with_boss();
+        {
+            let mut units = acquire_lock_mut(&game_loop.game_data.units, "game_units");
+            units.health[boss_id as usize].current = 1.0;
+            units.apply_status_effect(boss_id, StatusEffect::new(StatusEffectType::Burn, 5.0, 100.0));
+        }
+
+        let events = run_tick(&mut game_loop, 0.6, &events);
+
+        let died_at = events.iter().position(|event| matches!(event, GameEvent::UnitDied { unit_id, .. } if *unit_id == boss_id));
+        let defeated_at = events.iter().position(|event| matches!(event, GameEvent::BossDefeated { unit_id, .. } if *unit_id == boss_id));
+        assert!(died_at.is_some_and(|died_at| defeated_at.is_some_and(|defeated_at| died_at < defeated_at)));
+        assert_eq!(acquire_lock(&game_loop.game_data.run_stats, "run_stats").bosses_defeated, 1);
+    }
+}
@@ -2,11 +2,13 @@ use std::cmp::max;
 use crate::game::data::game_data::GameData;
 use crate::game::data::resource_cost::ResourceAmount;
 use crate::helper::lock_helper::acquire_lock_mut;
+use crate::helper::logging::IDLE;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 use crate::game::constants::GAME_RATE;
+use tracing::trace;
 
 pub struct IdleLoop {
     pub game_data: Arc<GameData>,
@@ -47,18 +49,23 @@ impl IdleLoop {
             let elapsed = object.last_produced.elapsed().as_secs_f64() + delta_time;
 
             let ticks = (elapsed / duration_secs).floor() as u64;
-            let mut produced_any = false;
+            let mut produced_count = 0;
 
             for _ in 0..ticks {
                 if Self::can_afford(resources, &object.production_cost) {
                     Self::pay_cost(resources, &object.production_cost);
                     Self::add_production(resources, &object.production_amount);
-                    produced_any = true;
+                    produced_count += 1;
                 } else {
                     break;
                 }
             }
 
+            let produced_any = produced_count > 0;
+            if produced_any {
+                trace!(target: IDLE, "{} produced {} time(s)", object.name, produced_count);
+            }
+
             updated_last_produced_times.push(if produced_any {
                 Instant::now()
             } else {
@@ -1,11 +1,13 @@
 use crate::game::data::game_data::GameData;
 use crate::game::data::stored_data::KEY_STATE;
+use crate::helper::logging::INPUT;
 use device_query_revamped::{DeviceQuery, DeviceState, Keycode};
 use rdev::{listen, EventType};
 use std::sync::{atomic::Ordering, Arc, Mutex};
 use std::thread;
 use std::thread::sleep;
 use std::time::Duration;
+use tracing::{error, trace};
 
 pub struct InputListener {
     game_data: Arc<GameData>,
@@ -45,7 +47,7 @@ impl InputListener {
                 _ => (),
             }
         }) {
-            eprintln!("Error: {:?}", error);
+            error!(target: INPUT, "Mouse listener stopped: {:?}", error);
         }
     }
 
@@ -84,7 +86,7 @@ impl InputListener {
 
             for &key in &keys {
                 if !last_pressed.contains(&key) {
-                    println!("Key pressed: {:?}", key);
+                    trace!(target: INPUT, "Key pressed: {:?}", key);
                     key_queue.push(key);
                 }
             }
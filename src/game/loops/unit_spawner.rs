@@ -1,28 +1,471 @@
+use crate::game::collision::detect_collision::check_tile_collision;
+use crate::game::data::boss_definition::{BossDefinition, BossPhase, BossSummons, BossTrigger};
 use crate::game::data::game_data::GameData;
-use crate::game::maths::pos_2::Pos2FixedPoint;
-use crate::game::objects::game_object::add_units;
-use crate::game::objects::unit_defaults::create_01_baby_dragon;
-use crate::helper::lock_helper::acquire_lock;
-use rand::random_range;
-use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
-
-pub fn spawn_units(game_data: Arc<GameData>) {
-    if let Some(map) = acquire_lock(&game_data.game_map, "game_map").as_ref() {
-        loop {
-                let mut units = vec![];
-                let mut positions = vec![];
-
-                let map_x = map.width as i32 * map.tile_size;
-                let map_y = map.height as i32 * map.tile_size;
-
-                let pos = Pos2FixedPoint::new(random_range(0..=map_x), random_range(0..=map_y));
-                units.push(create_01_baby_dragon());
-                positions.push(pos);
-
-                add_units(units, positions, &game_data);
-                thread::sleep(Duration::from_micros(1_000_000));
+use crate::game::data::spawn_table::{SpawnGroup, SpawnPattern, SpawnTable};
+use crate::game::events::game_event::GameEvent;
+use crate::game::loops::game_loop::get_player_position;
+use crate::game::map::game_map::GameMap;
+use crate::game::maths::pos_2::{Pos2FixedPoint, FIXED_POINT_SCALE};
+use crate::game::objects::elite_affix::{apply_affixes, roll_affixes};
+use crate::game::objects::game_object::{add_units, GameObject};
+use crate::game::objects::object_shape::ObjectShape;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_defaults::{create_enemy, EnemyName};
+use crate::game::objects::unit_store::{Health, UnitStore};
+use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::GAME_LOOP;
+use rand::prelude::IndexedRandom;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::f32::consts::TAU;
+use tracing::debug;
+
+// Used before the game view has reported its size
+const DEFAULT_VIEW_TILES: i32 = 10;
+const SPAWN_ATTEMPTS: usize = 8;
+// Random tiles tried per attempt when spawning anywhere, most maps are largely floor
+const WALKABLE_TILE_ATTEMPTS: usize = 16;
+// Summons appear around the boss, which may be right next to the player
+const SUMMON_MIN_PLAYER_DISTANCE: f32 = 2.0;
+// Gives the player a moment before a new phase's attacks start
+const PHASE_ATTACK_DELAY: f32 = 1.0;
+
+pub struct SpawnDirector {
+    spawn_table: SpawnTable,
+    elapsed: f32,
+    // Per wave, None once the wave has finished
+    next_spawn_at: Vec<Option<f32>>,
+    // Per boss encounter
+    bosses_triggered: Vec<bool>,
+    active_boss: Option<ActiveBoss>,
+}
+
+struct ActiveBoss {
+    encounter_index: usize,
+    unit_id: u32,
+    phase_index: usize,
+    base_move_speed: i32,
+    next_summon_at: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct BossStatus {
+    pub title: String,
+    pub phase: usize,
+    pub phase_count: usize,
+    pub health: Health,
+    pub position: Pos2FixedPoint,
+}
+
+impl SpawnDirector {
+    pub fn new(spawn_table: SpawnTable) -> Self {
+        let next_spawn_at = spawn_table.waves.iter().map(|wave| Some(wave.start_at)).collect();
+        let bosses_triggered = vec![false; spawn_table.bosses.len()];
+
+        Self {
+            spawn_table,
+            elapsed: 0.0,
+            next_spawn_at,
+            bosses_triggered,
+            active_boss: None,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32, game_data: &GameData) {
+        self.elapsed += delta_time;
+
+        let mut groups_due: Vec<&SpawnGroup> = Vec::new();
+
+        for (wave, next_spawn_at) in self.spawn_table.waves.iter().zip(self.next_spawn_at.iter_mut()) {
+            let Some(spawn_at) = *next_spawn_at else { continue; };
+            if self.elapsed < spawn_at {
+                continue;
+            }
+
+            if wave.end_at.is_some_and(|end_at| self.elapsed > end_at) {
+                *next_spawn_at = None;
+                continue;
+            }
+
+            groups_due.extend(wave.groups.iter());
+            *next_spawn_at = wave.interval.map(|interval| spawn_at + interval.max(0.1));
+        }
+
+        if !groups_due.is_empty() {
+            spawn_groups(&groups_due, &self.spawn_table, self.elapsed, game_data);
+        }
+
+        if self.active_boss.is_some() {
+            self.update_active_boss(game_data);
+        } else {
+            self.trigger_boss(game_data);
+        }
+    }
+
+    pub fn boss_status(&self, units: &UnitStore, unit_positions: &[Pos2FixedPoint]) -> Option<BossStatus> {
+        let active_boss = self.active_boss.as_ref().filter(|active_boss| units.is_alive(active_boss.unit_id))?;
+        let boss = &self.spawn_table.bosses[active_boss.encounter_index].boss;
+
+        Some(BossStatus {
+            title: boss.title.clone(),
+            phase: active_boss.phase_index + 1,
+            phase_count: boss.phases.len(),
+            health: units.health[active_boss.unit_id as usize],
+            position: *unit_positions.get(active_boss.unit_id as usize)?,
+        })
+    }
+
+    // Returns true if the unit was the active boss
+    pub fn on_unit_died(&mut self, unit_id: u32) -> bool {
+        if self.active_boss.as_ref().is_some_and(|active_boss| active_boss.unit_id == unit_id) {
+            self.active_boss = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn trigger_boss(&mut self, game_data: &GameData) {
+        let enemies_killed = acquire_lock(&game_data.run_stats, "run_stats").enemies_killed;
+        let elapsed = self.elapsed;
+        let game_map = acquire_lock(&game_data.game_map, "game_map").clone();
+        let (player_id, player_position) = get_player_position(game_data);
+        let player_tile = game_map.as_ref().filter(|_| player_id.is_some()).and_then(|game_map| game_map.tile_at(player_position));
+
+        let Some(encounter_index) = self.spawn_table.bosses
+            .iter()
+            .zip(&self.bosses_triggered)
+            .position(|(encounter, &triggered)| !triggered && match &encounter.trigger {
+                BossTrigger::Time(spawn_at) => elapsed >= *spawn_at,
+                BossTrigger::Kills(kills) => enemies_killed >= *kills,
+                BossTrigger::Area(name) => game_map.as_ref().zip(player_tile).is_some_and(|(game_map, player_tile)| {
+                    game_map.triggers.iter().any(|trigger| trigger.name == *name && trigger.contains(player_tile))
+                }),
+            }) else { return; };
+
+        // Left untriggered when there's no safe position so it retries next tick
+        let boss = &self.spawn_table.bosses[encounter_index].boss;
+        if let Some(active_boss) = spawn_boss(boss, encounter_index, &self.spawn_table, elapsed, game_data) {
+            self.bosses_triggered[encounter_index] = true;
+            self.active_boss = Some(active_boss);
+        }
+    }
+
+    fn update_active_boss(&mut self, game_data: &GameData) {
+        let Some(active_boss) = self.active_boss.as_mut() else { return; };
+        let boss = &self.spawn_table.bosses[active_boss.encounter_index].boss;
+
+        {
+            let mut units = acquire_lock_mut(&game_data.units, "game_units");
+            // Cleared by on_unit_died so the death is still reported as a boss kill
+            if !units.is_alive(active_boss.unit_id) {
+                return;
             }
+
+            let health = units.health[active_boss.unit_id as usize];
+            let phase_index = boss.phase_for_health(health.current / health.max.max(1.0)).max(active_boss.phase_index);
+
+            if phase_index != active_boss.phase_index {
+                active_boss.phase_index = phase_index;
+                active_boss.next_summon_at = self.elapsed;
+                apply_phase(&mut units, active_boss.unit_id, &boss.phases[phase_index], active_boss.base_move_speed);
+                debug!(target: GAME_LOOP, "{} entered phase {} at {:.0}/{:.0} health", boss.title, phase_index + 1, health.current, health.max);
+            }
+        }
+
+        let Some(summons) = boss.phases.get(active_boss.phase_index).and_then(|phase| phase.summons.as_ref()) else { return; };
+        if self.elapsed >= active_boss.next_summon_at {
+            active_boss.next_summon_at = self.elapsed + summons.interval.max(0.1);
+            summon_adds(summons, active_boss.unit_id, &self.spawn_table, game_data);
+        }
+    }
+}
+
+struct SpawnContext<'a> {
+    map: &'a GameMap,
+    spawn_points: Vec<(usize, usize)>,
+    player_position: Pos2FixedPoint,
+    // Ring patterns are centred here
+    center: Pos2FixedPoint,
+    view_half_extent: (i32, i32),
+    min_player_distance: i64,
+}
+
+impl<'a> SpawnContext<'a> {
+    fn new(map: &'a GameMap, spawn_table: &SpawnTable, game_data: &GameData) -> Self {
+        let (_, player_position) = get_player_position(game_data);
+
+        Self {
+            map,
+            spawn_points: map.spawn_points(),
+            player_position,
+            center: player_position,
+            view_half_extent: view_half_extent(game_data, map),
+            min_player_distance: (spawn_table.min_player_distance * map.tile_size as f32) as i64,
+        }
+    }
+}
+
+struct SpawnBatch {
+    units: Vec<GameObject>,
+    positions: Vec<Pos2FixedPoint>,
+    rejected: usize,
+    health_multiplier: f32,
+    elite_chance: f32,
+    max_affixes: usize,
+}
+
+impl SpawnBatch {
+    fn new(health_multiplier: f32, elite_chance: f32, max_affixes: usize) -> Self {
+        Self {
+            units: Vec::new(),
+            positions: Vec::new(),
+            rejected: 0,
+            health_multiplier,
+            elite_chance,
+            max_affixes,
+        }
+    }
+
+    fn add_enemies(
+        &mut self,
+        composition: &[(EnemyName, u32)],
+        count: usize,
+        pattern: SpawnPattern,
+        context: &SpawnContext,
+        rng: &mut ThreadRng,
+    ) {
+        for index in 0..count {
+            let Some(enemy_name) = choose_enemy(composition, rng) else { break; };
+            let mut unit = create_enemy(enemy_name);
+
+            let Some(position) = find_spawn_position(pattern, index, count, context, &unit.object_shape, rng) else {
+                self.rejected += 1;
+                continue;
+            };
+
+            unit.health_max *= self.health_multiplier;
+            unit.health_current *= self.health_multiplier;
+            apply_affixes(&mut unit, roll_affixes(self.elite_chance, self.max_affixes, rng));
+
+            self.units.push(unit);
+            self.positions.push(position);
+        }
+    }
+}
+
+fn spawn_groups(groups: &[&SpawnGroup], spawn_table: &SpawnTable, elapsed: f32, game_data: &GameData) {
+    let Some(map) = acquire_lock(&game_data.game_map, "game_map").clone() else { return; };
+    let context = SpawnContext::new(&map, spawn_table, game_data);
+
+    let enemy_count = acquire_lock(&game_data.units, "game_units").ids_of_type(ObjectType::Enemy).count();
+    let mut remaining = spawn_table.enemy_cap.saturating_sub(enemy_count);
+
+    let count_multiplier = spawn_table.difficulty.count_multiplier(elapsed);
+    let health_multiplier = spawn_table.difficulty.health_multiplier(elapsed);
+    let elite_chance = spawn_table.elites.chance(elapsed);
+    let mut rng = rand::rng();
+    let mut batch = SpawnBatch::new(health_multiplier, elite_chance, spawn_table.elites.max_affixes);
+
+    for group in groups {
+        let count = ((group.count as f32 * count_multiplier).round() as usize).min(remaining);
+        remaining -= count;
+
+        batch.add_enemies(&group.composition, count, group.pattern, &context, &mut rng);
+    }
+
+    if batch.rejected > 0 {
+        debug!(target: GAME_LOOP, "Skipped {} spawns with no safe position", batch.rejected);
+    }
+
+    if !batch.units.is_empty() {
+        debug!(target: GAME_LOOP, "Spawning {} enemies at {:.0}s ({} alive, cap {})", batch.units.len(), elapsed, enemy_count, spawn_table.enemy_cap);
+        add_units(batch.units, batch.positions, game_data);
+    }
+}
+
+fn spawn_boss(boss: &BossDefinition, encounter_index: usize, spawn_table: &SpawnTable, elapsed: f32, game_data: &GameData) -> Option<ActiveBoss> {
+    let first_phase = boss.phases.first()?;
+    let map = acquire_lock(&game_data.game_map, "game_map").clone()?;
+    let context = SpawnContext::new(&map, spawn_table, game_data);
+    let mut rng = rand::rng();
+
+    let mut unit = create_enemy(boss.enemy);
+    unit.health_max = boss.health;
+    unit.health_current = boss.health;
+    unit.loot = Some(boss.loot.clone());
+    let base_move_speed = unit.move_speed;
+
+    // A random slot on the ring so the boss doesn't always arrive from the same side
+    let slot = rng.random_range(0..SPAWN_ATTEMPTS);
+    let position = find_spawn_position(SpawnPattern::Ring { radius: boss.spawn_radius }, slot, SPAWN_ATTEMPTS, &context, &unit.object_shape, &mut rng)?;
+
+    let unit_id = *add_units(vec![unit], vec![position], game_data).first()?;
+    apply_phase(&mut acquire_lock_mut(&game_data.units, "game_units"), unit_id, first_phase, base_move_speed);
+
+    acquire_lock_mut(&game_data.events, "events").publish(GameEvent::BossSpawned {
+        unit_id,
+        title: boss.title.clone(),
+        position,
+    });
+
+    Some(ActiveBoss {
+        encounter_index,
+        unit_id,
+        phase_index: 0,
+        base_move_speed,
+        next_summon_at: elapsed,
+    })
+}
+
+fn apply_phase(units: &mut UnitStore, unit_id: u32, phase: &BossPhase, base_move_speed: i32) {
+    let index = unit_id as usize;
+
+    units.move_speeds[index] = (base_move_speed as f32 * phase.move_speed_multiplier) as i32;
+    units.upgrades[index] = phase.upgrades.clone();
+    units.attack_cooldowns[index] = phase.attacks
+        .iter()
+        .map(|&attack_name| (attack_name, PHASE_ATTACK_DELAY))
+        .collect();
+}
+
+fn summon_adds(summons: &BossSummons, boss_id: u32, spawn_table: &SpawnTable, game_data: &GameData) {
+    let Some(map) = acquire_lock(&game_data.game_map, "game_map").clone() else { return; };
+    let Some(boss_position) = acquire_lock(&game_data.unit_positions, "unit_positions").get(boss_id as usize).copied() else { return; };
+
+    let mut context = SpawnContext::new(&map, spawn_table, game_data);
+    context.center = boss_position;
+    context.min_player_distance = (SUMMON_MIN_PLAYER_DISTANCE * map.tile_size as f32) as i64;
+
+    let enemy_count = acquire_lock(&game_data.units, "game_units").ids_of_type(ObjectType::Enemy).count();
+    let count = (summons.count as usize).min(spawn_table.enemy_cap.saturating_sub(enemy_count));
+
+    let mut rng = rand::rng();
+    // Summons are never elites
+    let mut batch = SpawnBatch::new(1.0, 0.0, 0);
+    batch.add_enemies(&summons.composition, count, SpawnPattern::Ring { radius: summons.radius }, &context, &mut rng);
+
+    if !batch.units.is_empty() {
+        debug!(target: GAME_LOOP, "Boss summoned {} enemies", batch.units.len());
+        add_units(batch.units, batch.positions, game_data);
+    }
+}
+
+fn choose_enemy(composition: &[(EnemyName, u32)], rng: &mut ThreadRng) -> Option<EnemyName> {
+    composition
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .ok()
+        .map(|(enemy_name, _)| *enemy_name)
+}
+
+fn find_spawn_position(
+    pattern: SpawnPattern,
+    index: usize,
+    count: usize,
+    context: &SpawnContext,
+    unit_shape: &ObjectShape,
+    rng: &mut ThreadRng,
+) -> Option<Pos2FixedPoint> {
+    (0..SPAWN_ATTEMPTS)
+        .map(|attempt| spawn_position(pattern, index, count, attempt, context, rng))
+        .find(|&position| is_spawn_safe(position, unit_shape, context))
+}
+
+fn is_spawn_safe(position: Pos2FixedPoint, unit_shape: &ObjectShape, context: &SpawnContext) -> bool {
+    is_away_from_player(position, context) && !check_tile_collision(position, unit_shape, context.map, context.map.tile_size)
+}
+
+fn is_away_from_player(position: Pos2FixedPoint, context: &SpawnContext) -> bool {
+    let offset = position.sub(context.player_position);
+    let distance_squared = offset.x as i64 * offset.x as i64 + offset.y as i64 * offset.y as i64;
+
+    distance_squared >= context.min_player_distance * context.min_player_distance
+}
+
+fn spawn_position(
+    pattern: SpawnPattern,
+    index: usize,
+    count: usize,
+    attempt: usize,
+    context: &SpawnContext,
+    rng: &mut ThreadRng,
+) -> Pos2FixedPoint {
+    let map = context.map;
+    let player_position = context.player_position;
+    let center = context.center;
+
+    let position = match pattern {
+        SpawnPattern::Ring { radius } => {
+            // Retries nudge the slot around the ring so a wall doesn't block it every time
+            let jitter = if attempt == 0 { 0.0 } else { rng.random_range(-0.5..0.5) };
+            let angle = TAU * (index as f32 + jitter) / count.max(1) as f32;
+            let radius = radius * map.tile_size as f32;
+            Pos2FixedPoint::new(
+                center.x + (angle.cos() * radius) as i32,
+                center.y + (angle.sin() * radius) as i32,
+            )
+        }
+        SpawnPattern::OffScreen => {
+            let half_x = context.view_half_extent.0 + map.tile_size;
+            let half_y = context.view_half_extent.1 + map.tile_size;
+
+            match rng.random_range(0..4) {
+                0 => Pos2FixedPoint::new(player_position.x + rng.random_range(-half_x..=half_x), player_position.y - half_y),
+                1 => Pos2FixedPoint::new(player_position.x + rng.random_range(-half_x..=half_x), player_position.y + half_y),
+                2 => Pos2FixedPoint::new(player_position.x - half_x, player_position.y + rng.random_range(-half_y..=half_y)),
+                _ => Pos2FixedPoint::new(player_position.x + half_x, player_position.y + rng.random_range(-half_y..=half_y)),
+            }
+        }
+        SpawnPattern::SpawnPoints => match context.spawn_points.choose(rng) {
+            Some(&(x, y)) => Pos2FixedPoint::new(
+                x as i32 * map.tile_size + rng.random_range(0..map.tile_size),
+                y as i32 * map.tile_size + rng.random_range(0..map.tile_size),
+            ),
+            None => random_walkable_position(context, rng),
+        },
+        SpawnPattern::Anywhere => random_walkable_position(context, rng),
+    };
+
+    clamp_to_map(position, map)
+}
+
+// The centre of a random floor tile outside the player's safe radius. On a map that is mostly
+// wall it settles for the last pick and leaves is_spawn_safe to reject it.
+fn random_walkable_position(context: &SpawnContext, rng: &mut ThreadRng) -> Pos2FixedPoint {
+    let map = context.map;
+    let mut position = context.player_position;
+
+    for _ in 0..WALKABLE_TILE_ATTEMPTS {
+        let tile = (rng.random_range(0..map.width), rng.random_range(0..map.height));
+        position = map.tile_center(tile);
+        if !map.get_tile(tile.0, tile.1).blocks_collision() && is_away_from_player(position, context) {
+            break;
+        }
+    }
+
+    position
+}
+
+// Keeps spawns off the border wall
+fn clamp_to_map(position: Pos2FixedPoint, map: &GameMap) -> Pos2FixedPoint {
+    let max_x = (map.width as i32 - 1) * map.tile_size - 1;
+    let max_y = (map.height as i32 - 1) * map.tile_size - 1;
+
+    Pos2FixedPoint::new(
+        position.x.clamp(map.tile_size, max_x.max(map.tile_size)),
+        position.y.clamp(map.tile_size, max_y.max(map.tile_size)),
+    )
+}
+
+// Half the visible area in world units
+fn view_half_extent(game_data: &GameData, map: &GameMap) -> (i32, i32) {
+    let zoom_scaled = acquire_lock(&game_data.camera_state, "camera_state").get_zoom_scaled();
+
+    match *acquire_lock(&game_data.graphic_window_size, "graphic_window_size") {
+        Some(size) if zoom_scaled > 0.0 => (
+            (size.x / 2.0 * FIXED_POINT_SCALE as f32 / zoom_scaled) as i32,
+            (size.y / 2.0 * FIXED_POINT_SCALE as f32 / zoom_scaled) as i32,
+        ),
+        _ => (DEFAULT_VIEW_TILES * map.tile_size, DEFAULT_VIEW_TILES * map.tile_size),
     }
-}
\ No newline at end of file
+}
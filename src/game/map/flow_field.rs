@@ -0,0 +1,181 @@
+use crate::game::map::game_map::GameMap;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const ORTHOGONAL_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+pub const UNREACHABLE: u32 = u32::MAX;
+
+const NEIGHBOURS: [(i32, i32, u32); 8] = [
+    (1, 0, ORTHOGONAL_COST),
+    (-1, 0, ORTHOGONAL_COST),
+    (0, 1, ORTHOGONAL_COST),
+    (0, -1, ORTHOGONAL_COST),
+    (1, 1, DIAGONAL_COST),
+    (1, -1, DIAGONAL_COST),
+    (-1, 1, DIAGONAL_COST),
+    (-1, -1, DIAGONAL_COST),
+];
+
+// Tiles either side of the player the field covers. A 129 × 129 window keeps every rebuild
+// to about 16k tiles whatever the map size, and still reaches well past the edge of the screen.
+pub const FLOW_FIELD_RADIUS: usize = 64;
+
+// Dijkstra map from every open tile within FLOW_FIELD_RADIUS of the target to the target tile. Units sample
+// the tile they stand on for the next tile to head towards, so lookups are constant time however many units there are.
+// Paths are only found inside the window, units outside it or cut off within it head straight for the target.
+pub struct FlowField {
+    // World tile of the window's top left corner, width and height are the window's size
+    pub origin: (usize, usize),
+    pub width: usize,
+    pub height: usize,
+    pub tile_size: i32,
+    pub target: (usize, usize),
+    pub costs: Vec<u32>,
+    next_tiles: Vec<Option<(usize, usize)>>,
+}
+
+impl FlowField {
+    pub fn build(map: &GameMap, target: (usize, usize)) -> Self {
+        let origin = (target.0.saturating_sub(FLOW_FIELD_RADIUS), target.1.saturating_sub(FLOW_FIELD_RADIUS));
+        let width = (target.0 + FLOW_FIELD_RADIUS + 1).min(map.width).saturating_sub(origin.0);
+        let height = (target.1 + FLOW_FIELD_RADIUS + 1).min(map.height).saturating_sub(origin.1);
+
+        let blocked: Vec<bool> = (0..width * height)
+            .map(|index| map.get_tile(origin.0 + index % width, origin.1 + index / width).blocks_collision())
+            .collect();
+
+        let mut costs = vec![UNREACHABLE; width * height];
+        let mut queue = BinaryHeap::new();
+
+        // Costs and the queue work in window coordinates
+        let local_target = (target.0.wrapping_sub(origin.0), target.1.wrapping_sub(origin.1));
+        if local_target.0 < width && local_target.1 < height {
+            costs[local_target.1 * width + local_target.0] = 0;
+            queue.push(Reverse((0, local_target.0, local_target.1)));
+        }
+
+        while let Some(Reverse((cost, x, y))) = queue.pop() {
+            if cost > costs[y * width + x] {
+                continue;
+            }
+
+            for (nx, ny, step_cost) in open_neighbours(x, y, width, height, &blocked) {
+                let next_cost = cost + step_cost;
+                let next_index = ny * width + nx;
+                if next_cost < costs[next_index] {
+                    costs[next_index] = next_cost;
+                    queue.push(Reverse((next_cost, nx, ny)));
+                }
+            }
+        }
+
+        let next_tiles = (0..width * height)
+            .into_par_iter()
+            .map(|index| {
+                let (x, y) = (index % width, index / width);
+                open_neighbours(x, y, width, height, &blocked)
+                    .map(|(nx, ny, _)| (costs[ny * width + nx], nx, ny))
+                    .filter(|&(cost, _, _)| cost < costs[index])
+                    .min()
+                    .map(|(_, nx, ny)| (origin.0 + nx, origin.1 + ny))
+            })
+            .collect();
+
+        Self { origin, width, height, tile_size: map.tile_size, target, costs, next_tiles }
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        let (local_x, local_y) = (x.checked_sub(self.origin.0)?, y.checked_sub(self.origin.1)?);
+        (local_x < self.width && local_y < self.height).then_some(local_y * self.width + local_x)
+    }
+
+    // Takes world tile coordinates, tiles outside the window are unreachable
+    pub fn cost(&self, x: usize, y: usize) -> u32 {
+        self.index(x, y).map_or(UNREACHABLE, |index| self.costs[index])
+    }
+
+    pub fn next_tile(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        self.next_tiles[self.index(x, y)?]
+    }
+
+    // None next to the target or where it can't be reached, callers head straight for it instead
+    pub fn direction_at(&self, position: Pos2FixedPoint) -> Option<(f32, f32)> {
+        if position.x < 0 || position.y < 0 {
+            return None;
+        }
+
+        let (x, y) = ((position.x / self.tile_size) as usize, (position.y / self.tile_size) as usize);
+        if self.cost(x, y) <= DIAGONAL_COST {
+            return None;
+        }
+
+        let (next_x, next_y) = self.next_tile(x, y)?;
+        let half_tile = self.tile_size / 2;
+        let offset = (
+            (next_x as i32 * self.tile_size + half_tile - position.x) as f32,
+            (next_y as i32 * self.tile_size + half_tile - position.y) as f32,
+        );
+        let length = (offset.0 * offset.0 + offset.1 * offset.1).sqrt();
+
+        (length > 0.0).then(|| (offset.0 / length, offset.1 / length))
+    }
+}
+
+// Diagonal steps need both adjacent orthogonal tiles open so paths don't cut wall corners
+fn open_neighbours(x: usize, y: usize, width: usize, height: usize, blocked: &[bool]) -> impl Iterator<Item = (usize, usize, u32)> + '_ {
+    let is_open = move |x: i32, y: i32| {
+        x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height && !blocked[y as usize * width + x as usize]
+    };
+
+    NEIGHBOURS.iter().filter_map(move |&(dx, dy, cost)| {
+        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        let diagonal_clear = dx == 0 || dy == 0 || (is_open(x as i32 + dx, y as i32) && is_open(x as i32, y as i32 + dy));
+
+        (is_open(nx, ny) && diagonal_clear).then_some((nx as usize, ny as usize, cost))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::game_tile::{GRASS_DEFAULT, WALL_DEFAULT};
+
+    fn open_map(width: usize, height: usize) -> GameMap {
+        GameMap::from_tiles(width, height, 100, vec![GRASS_DEFAULT; width * height])
+    }
+
+    #[test]
+    fn large_maps_only_build_the_window_around_the_target() {
+        let flow_field = FlowField::build(&open_map(1000, 1000), (500, 500));
+
+        assert_eq!(flow_field.costs.len(), (FLOW_FIELD_RADIUS * 2 + 1).pow(2));
+        assert_eq!(flow_field.cost(500 + FLOW_FIELD_RADIUS, 500), FLOW_FIELD_RADIUS as u32 * ORTHOGONAL_COST);
+        assert_eq!(flow_field.cost(500 + FLOW_FIELD_RADIUS + 1, 500), UNREACHABLE);
+        assert_eq!(flow_field.next_tile(501, 500), Some((500, 500)));
+    }
+
+    #[test]
+    fn the_window_is_clamped_at_the_map_edge() {
+        let flow_field = FlowField::build(&open_map(200, 200), (3, 3));
+
+        assert_eq!(flow_field.origin, (0, 0));
+        assert_eq!((flow_field.width, flow_field.height), (3 + FLOW_FIELD_RADIUS + 1, 3 + FLOW_FIELD_RADIUS + 1));
+        assert_eq!(flow_field.cost(0, 3), 3 * ORTHOGONAL_COST);
+    }
+
+    #[test]
+    fn paths_go_around_walls() {
+        // A wall at x = 5 with a gap at the bottom row
+        let mut map = open_map(10, 10);
+        for y in 0..9 {
+            map.set_tile(5, y, WALL_DEFAULT);
+        }
+        let flow_field = FlowField::build(&map, (2, 0));
+
+        assert_eq!(flow_field.next_tile(6, 0), Some((6, 1)));
+        assert!(flow_field.cost(6, 0) > 4 * ORTHOGONAL_COST);
+    }
+}
@@ -1,36 +1,331 @@
-use crate::game::map::game_tile::{GameTile, EMPTY_DEFAULT, GRASS_DEFAULT, WALL_DEFAULT};
-use std::collections::HashMap;
+use crate::game::map::game_tile::{GameTile, EMPTY_DEFAULT, GRASS_DEFAULT};
+use crate::game::map::tile_type::TileType;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use rustc_hash::FxHashMap;
+
+// Tiles per chunk side
+pub const CHUNK_SIZE: usize = 32;
+// Maps with more tiles than this are stored in chunks
+const CHUNKED_THRESHOLD: usize = 256 * 256;
+
+// Pressure plates to the doors they open, portals to their exit
+pub type TileLinks = FxHashMap<(usize, usize), Vec<(usize, usize)>>;
+
+#[derive(Clone)]
+pub enum TileStorage {
+    // Row major, one entry per tile
+    Dense(Vec<GameTile>),
+    // Row major chunks of CHUNK_SIZE × CHUNK_SIZE tiles, chunks that are entirely empty are left out.
+    // The chunk grid is sized once from the map's width and height. Infinite maps are out of scope: tile
+    // coordinates are bounded by the map size everywhere (pathing, visibility, spawning), and the loader
+    // rejects infinite Tiled maps. Tiles outside the grid read as empty and writes to them are dropped.
+    Chunked { chunks_wide: usize, chunks: Vec<Option<Box<[GameTile]>>> },
+}
+
+// Named tile rectangle from a map file's object layer, coordinates in tiles
+#[derive(Clone, Debug)]
+pub struct MapTrigger {
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl MapTrigger {
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+}
 
 #[derive(Clone)]
 pub struct GameMap {
     pub width: usize,
     pub height: usize,
-    pub tiles: HashMap<(usize, usize), GameTile>,
     pub tile_size: i32,
+    storage: TileStorage,
+    pub links: TileLinks,
+    // Areas gameplay can react to the player entering, e.g. boss encounters
+    pub triggers: Vec<MapTrigger>,
 }
 
 impl GameMap {
-    pub fn new(width: usize, height: usize, tile_size: i32) -> Self {
-        let mut tiles = HashMap::new();
+    // Takes row major tiles and picks the storage from the map size
+    pub fn from_tiles(width: usize, height: usize, tile_size: i32, tiles: Vec<GameTile>) -> Self {
+        debug_assert_eq!(tiles.len(), width * height);
 
-        for x in 0..width {
-            for y in 0..height {
-                if x == 0 || x == width - 1 || y == 0 || y == height - 1 {
-                    tiles.insert((x, y), WALL_DEFAULT);
-                } else {
-                    tiles.insert((x, y), GRASS_DEFAULT);
-                }
-            }
+        let storage = if width * height > CHUNKED_THRESHOLD {
+            chunk_tiles(width, height, &tiles)
+        } else {
+            TileStorage::Dense(tiles)
+        };
+
+        Self { width, height, tile_size, storage, links: FxHashMap::default(), triggers: Vec::new() }
+    }
+
+    // Corners and edge midpoints, a few tiles in from the border wall
+    pub(crate) fn is_default_spawn_point(x: usize, y: usize, width: usize, height: usize) -> bool {
+        let inset = 3;
+        if width <= inset * 2 || height <= inset * 2 {
+            return false;
         }
 
-        Self { width, height, tiles, tile_size }
+        let columns = [inset, width / 2, width - 1 - inset];
+        let rows = [inset, height / 2, height - 1 - inset];
+        columns.contains(&x) && rows.contains(&y) && !(x == width / 2 && y == height / 2)
     }
 
     pub fn get_tile(&self, x: usize, y: usize) -> GameTile {
-        *self.tiles.get(&(x, y)).unwrap_or(&EMPTY_DEFAULT)
+        if x >= self.width || y >= self.height {
+            return EMPTY_DEFAULT;
+        }
+
+        match &self.storage {
+            TileStorage::Dense(tiles) => tiles[y * self.width + x],
+            TileStorage::Chunked { chunks_wide, chunks } => chunks[(y / CHUNK_SIZE) * chunks_wide + x / CHUNK_SIZE]
+                .as_ref()
+                .map_or(EMPTY_DEFAULT, |chunk| chunk[(y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE]),
+        }
+    }
+
+    pub fn set_tile(&mut self, x: usize, y: usize, tile: GameTile) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        match &mut self.storage {
+            TileStorage::Dense(tiles) => tiles[y * self.width + x] = tile,
+            TileStorage::Chunked { chunks_wide, chunks } => {
+                let chunk = chunks[(y / CHUNK_SIZE) * *chunks_wide + x / CHUNK_SIZE]
+                    .get_or_insert_with(|| vec![EMPTY_DEFAULT; CHUNK_SIZE * CHUNK_SIZE].into_boxed_slice());
+                chunk[(y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE] = tile;
+            }
+        }
+    }
+
+    // Broken tiles become grass
+    pub fn break_tile(&mut self, x: usize, y: usize) {
+        self.set_tile(x, y, GRASS_DEFAULT);
+    }
+
+    pub fn tile_center(&self, (x, y): (usize, usize)) -> Pos2FixedPoint {
+        Pos2FixedPoint::new(x as i32 * self.tile_size + self.tile_size / 2, y as i32 * self.tile_size + self.tile_size / 2)
+    }
+
+    pub fn tile_at(&self, position: Pos2FixedPoint) -> Option<(usize, usize)> {
+        if position.x < 0 || position.y < 0 {
+            return None;
+        }
+
+        let (x, y) = ((position.x / self.tile_size) as usize, (position.y / self.tile_size) as usize);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    // Inclusive tile range covering a world space rectangle, clamped to the map
+    pub fn tile_range(&self, min: Pos2FixedPoint, max: Pos2FixedPoint) -> Option<((usize, usize), (usize, usize))> {
+        if self.width == 0 || self.height == 0 || max.x < 0 || max.y < 0 {
+            return None;
+        }
+
+        let to_tile = |value: i32, limit: usize| ((value.max(0) / self.tile_size) as usize).min(limit - 1);
+        let min_tile = (to_tile(min.x, self.width), to_tile(min.y, self.height));
+        let max_tile = (to_tile(max.x, self.width), to_tile(max.y, self.height));
+
+        (min_tile.0 <= max_tile.0 && min_tile.1 <= max_tile.1).then_some((min_tile, max_tile))
+    }
+
+    // Visits the tiles in an inclusive range, chunks that were left out are skipped rather than visited as empty
+    pub fn for_each_tile_in(&self, (min_x, min_y): (usize, usize), (max_x, max_y): (usize, usize), mut visit: impl FnMut(usize, usize, GameTile)) {
+        let (max_x, max_y) = (max_x.min(self.width.saturating_sub(1)), max_y.min(self.height.saturating_sub(1)));
+
+        match &self.storage {
+            TileStorage::Dense(tiles) => {
+                for y in min_y..=max_y {
+                    for x in min_x..=max_x {
+                        visit(x, y, tiles[y * self.width + x]);
+                    }
+                }
+            }
+            TileStorage::Chunked { chunks_wide, chunks } => {
+                for chunk_y in min_y / CHUNK_SIZE..=max_y / CHUNK_SIZE {
+                    for chunk_x in min_x / CHUNK_SIZE..=max_x / CHUNK_SIZE {
+                        let Some(chunk) = &chunks[chunk_y * chunks_wide + chunk_x] else { continue; };
+
+                        let (start_x, start_y) = ((chunk_x * CHUNK_SIZE).max(min_x), (chunk_y * CHUNK_SIZE).max(min_y));
+                        let (end_x, end_y) = ((chunk_x * CHUNK_SIZE + CHUNK_SIZE - 1).min(max_x), (chunk_y * CHUNK_SIZE + CHUNK_SIZE - 1).min(max_y));
+                        for y in start_y..=end_y {
+                            for x in start_x..=end_x {
+                                visit(x, y, chunk[(y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
     }
 
     pub fn get_tile_size(&self) -> i32 {
         self.tile_size
     }
+
+    pub fn spawn_points(&self) -> Vec<(usize, usize)> {
+        let mut spawn_points = Vec::new();
+        if self.width == 0 || self.height == 0 {
+            return spawn_points;
+        }
+
+        self.for_each_tile_in((0, 0), (self.width - 1, self.height - 1), |x, y, tile| {
+            if tile.tile_type == TileType::SpawnPoint {
+                spawn_points.push((x, y));
+            }
+        });
+        spawn_points
+    }
+}
+
+fn chunk_tiles(width: usize, height: usize, tiles: &[GameTile]) -> TileStorage {
+    let (chunks_wide, chunks_high) = (width.div_ceil(CHUNK_SIZE), height.div_ceil(CHUNK_SIZE));
+
+    let chunks = (0..chunks_wide * chunks_high)
+        .map(|chunk_index| {
+            let (chunk_x, chunk_y) = (chunk_index % chunks_wide, chunk_index / chunks_wide);
+            let mut chunk = vec![EMPTY_DEFAULT; CHUNK_SIZE * CHUNK_SIZE];
+            let mut is_empty = true;
+
+            for local_y in 0..CHUNK_SIZE.min(height - chunk_y * CHUNK_SIZE) {
+                for local_x in 0..CHUNK_SIZE.min(width - chunk_x * CHUNK_SIZE) {
+                    let tile = tiles[(chunk_y * CHUNK_SIZE + local_y) * width + chunk_x * CHUNK_SIZE + local_x];
+                    is_empty &= tile == EMPTY_DEFAULT;
+                    chunk[local_y * CHUNK_SIZE + local_x] = tile;
+                }
+            }
+
+            (!is_empty).then(|| chunk.into_boxed_slice())
+        })
+        .collect();
+
+    TileStorage::Chunked { chunks_wide, chunks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::game_tile::WALL_DEFAULT;
+    use std::hint::black_box;
+    use std::time::{Duration, Instant};
+
+    // Grass with a wall every seventh tile, and only every fourth row of chunks filled when sparse
+    fn tiles(width: usize, height: usize, sparse: bool) -> Vec<GameTile> {
+        (0..width * height)
+            .map(|index| {
+                let (x, y) = (index % width, index / width);
+                if sparse && !(y / CHUNK_SIZE).is_multiple_of(4) {
+                    EMPTY_DEFAULT
+                } else if (x + y) % 7 == 0 {
+                    WALL_DEFAULT
+                } else {
+                    GRASS_DEFAULT
+                }
+            })
+            .collect()
+    }
+
+    fn with_storage(width: usize, height: usize, tiles: Vec<GameTile>, chunked: bool) -> GameMap {
+        let mut game_map = GameMap::from_tiles(width, height, 100, tiles.clone());
+        game_map.storage = if chunked { chunk_tiles(width, height, &tiles) } else { TileStorage::Dense(tiles) };
+        game_map
+    }
+
+    fn collect_range(game_map: &GameMap, min: (usize, usize), max: (usize, usize)) -> Vec<(usize, usize, TileType)> {
+        let mut visited = Vec::new();
+        game_map.for_each_tile_in(min, max, |x, y, tile| {
+            if tile != EMPTY_DEFAULT {
+                visited.push((x, y, tile.tile_type));
+            }
+        });
+        visited.sort_by_key(|&(x, y, _)| (y, x));
+        visited
+    }
+
+    #[test]
+    fn dense_and_chunked_storage_agree() {
+        let (width, height) = (100, 70);
+        let tiles = tiles(width, height, true);
+        let (dense, mut chunked) = (with_storage(width, height, tiles.clone(), false), with_storage(width, height, tiles, true));
+
+        for (min, max) in [((0, 0), (99, 69)), ((30, 20), (65, 40)), ((90, 60), (200, 200))] {
+            assert_eq!(collect_range(&dense, min, max), collect_range(&chunked, min, max));
+        }
+
+        // Writing into a chunk that was left out allocates it
+        chunked.set_tile(40, 40, GRASS_DEFAULT);
+        assert!(chunked.get_tile(40, 40) == GRASS_DEFAULT);
+        assert!(chunked.get_tile(100, 0) == EMPTY_DEFAULT);
+    }
+
+    fn best_of<T>(passes: u32, mut run: impl FnMut() -> T) -> Duration {
+        (0..passes)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(run());
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or_default()
+    }
+
+    // A draw_map style pass over the visible tiles of a 1000 × 1000 map, against the old approach of
+    // walking a HashMap of every tile and culling, and a full pass over a sparse map where chunks skip
+    // empty space. Timings print with: cargo test --release map_iteration_timing -- --nocapture
+    #[test]
+    fn map_iteration_timing() {
+        const SIZE: usize = 1000;
+        const PASSES: u32 = 5;
+        // Roughly a 1080p screen of 32 pixel tiles
+        const VIEW: (usize, usize) = (60, 34);
+
+        let tiles = tiles(SIZE, SIZE, false);
+        let hash_map: FxHashMap<(usize, usize), GameTile> = tiles.iter().enumerate().map(|(index, tile)| ((index % SIZE, index / SIZE), *tile)).collect();
+        let dense = with_storage(SIZE, SIZE, tiles.clone(), false);
+        let chunked = with_storage(SIZE, SIZE, tiles, true);
+
+        let views: Vec<((usize, usize), (usize, usize))> = (0..16)
+            .map(|step| {
+                let min = (step * 57 % (SIZE - VIEW.0), step * 91 % (SIZE - VIEW.1));
+                (min, (min.0 + VIEW.0 - 1, min.1 + VIEW.1 - 1))
+            })
+            .collect();
+        let count_walls = |game_map: &GameMap, (min, max): ((usize, usize), (usize, usize))| {
+            let mut walls = 0;
+            game_map.for_each_tile_in(min, max, |_, _, tile| walls += (tile.tile_type == TileType::Wall) as u32);
+            walls
+        };
+
+        let hash_map_view = best_of(PASSES, || {
+            views.iter().map(|&(min, max)| {
+                hash_map.iter()
+                    .filter(|&(&(x, y), _)| x >= min.0 && x <= max.0 && y >= min.1 && y <= max.1)
+                    .filter(|(_, tile)| tile.tile_type == TileType::Wall)
+                    .count()
+            }).sum::<usize>()
+        });
+        let dense_view = best_of(PASSES, || views.iter().map(|&view| count_walls(&dense, view)).sum::<u32>());
+        let chunked_view = best_of(PASSES, || views.iter().map(|&view| count_walls(&chunked, view)).sum::<u32>());
+
+        let sparse_tiles = self::tiles(SIZE, SIZE, true);
+        let sparse_dense = with_storage(SIZE, SIZE, sparse_tiles.clone(), false);
+        let sparse_chunked = with_storage(SIZE, SIZE, sparse_tiles, true);
+        let whole_map = ((0, 0), (SIZE - 1, SIZE - 1));
+        let dense_full = best_of(PASSES, || count_walls(&sparse_dense, whole_map));
+        let chunked_full = best_of(PASSES, || count_walls(&sparse_chunked, whole_map));
+
+        println!("{} × {} map, {} views of {} × {} tiles, best of {} passes", SIZE, SIZE, views.len(), VIEW.0, VIEW.1, PASSES);
+        println!("culled views  HashMap {:?}, Dense {:?}, Chunked {:?}", hash_map_view, dense_view, chunked_view);
+        println!("sparse map    Dense {:?}, Chunked {:?}", dense_full, chunked_full);
+
+        // Release runs show three orders of magnitude for views and about four times for the sparse pass
+        assert!(dense_view * 10 < hash_map_view, "dense views {:?} vs HashMap {:?}", dense_view, hash_map_view);
+        assert!(chunked_view * 10 < hash_map_view, "chunked views {:?} vs HashMap {:?}", chunked_view, hash_map_view);
+        assert!(chunked_full < dense_full, "chunked sparse pass {:?} vs dense {:?}", chunked_full, dense_full);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::game::map::tile_type::TileType;
-use crate::game::map::tile_type::TileType::{Empty, Grass, SpawnPoint, Wall};
+use crate::game::map::tile_type::TileType::{BreakableWall, Crate, Door, Empty, Grass, HealingSpring, Ice, Lava, OpenDoor, Portal, PressurePlate, SpawnPoint, Thorns, Wall, Water};
+use crate::game::objects::loot::Loot;
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct GameTile {
@@ -18,11 +19,36 @@ impl GameTile {
             SpawnPoint => false,
             Grass => false,
             Empty => false,
+            Water | Lava | Ice | Thorns | HealingSpring => false,
+            BreakableWall | Crate | Door => true,
+            PressurePlate | OpenDoor | Portal => false,
+        }
+    }
+
+    // Crates are low enough to see over
+    pub fn blocks_sight(&self) -> bool {
+        matches!(self.tile_type, Wall | BreakableWall | Door)
+    }
+
+    // Damage needed to break the tile, None for tiles attacks can't break
+    pub fn durability(&self) -> Option<f32> {
+        match self.tile_type {
+            BreakableWall => Some(60.0),
+            Crate => Some(20.0),
+            _ => None,
+        }
+    }
+
+    pub fn broken_loot(&self) -> Option<Loot> {
+        match self.tile_type {
+            Crate => Some(Loot { gold: 15.0, exp: 0.0, ruby: 0.0 }),
+            _ => None,
         }
     }
 }
 
 pub const EMPTY_DEFAULT: GameTile = GameTile::new(Empty);
+#[cfg(test)]
 pub const WALL_DEFAULT: GameTile = GameTile::new(Wall);
 pub const SPAWN_POINT_DEFAULT: GameTile = GameTile::new(SpawnPoint);
-pub const GRASS_DEFAULT: GameTile = GameTile::new(Grass);
+pub const GRASS_DEFAULT: GameTile = GameTile::new(Grass);
\ No newline at end of file
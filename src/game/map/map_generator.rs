@@ -0,0 +1,351 @@
+use crate::game::map::game_map::GameMap;
+use crate::game::map::game_tile::GameTile;
+use crate::game::map::tile_type::TileType;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Tiles around the player start that are always left open
+const START_CLEARING: usize = 2;
+// Corridors carved between rooms or to stranded spawn points are 3 tiles wide
+const CORRIDOR_HALF_WIDTH: usize = 1;
+const ROOM_ATTEMPTS_PER_ROOM: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum MapGenerator {
+    // Grass with a wall border
+    #[default]
+    Open,
+    // Random fill smoothed by cellular automata, fill_chance is the starting chance of a wall
+    Caves { fill_chance: f64, smoothing_steps: u32 },
+    // Rectangular rooms joined by corridors, the first room is centred on the player start
+    Dungeon { room_count: u32, min_room_size: usize, max_room_size: usize },
+    // Grass scattered with rectangular obstacles up to max_obstacle_size tiles across
+    Field { obstacle_chance: f64, max_obstacle_size: usize },
+}
+
+// Round blobs of terrain painted over open grass once the layout is connected, tiles that block are placed singly
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub struct TerrainPatch {
+    pub tile_type: TileType,
+    pub count: u32,
+    // In tiles
+    pub radius: usize,
+}
+
+struct TileGrid {
+    width: usize,
+    height: usize,
+    tiles: Vec<TileType>,
+}
+
+impl TileGrid {
+    fn filled(width: usize, height: usize, tile_type: TileType) -> Self {
+        Self { width, height, tiles: vec![tile_type; width * height] }
+    }
+
+    fn get(&self, x: usize, y: usize) -> TileType {
+        self.tiles[y * self.width + x]
+    }
+
+    fn set(&mut self, x: usize, y: usize, tile_type: TileType) {
+        self.tiles[y * self.width + x] = tile_type;
+    }
+
+    fn is_open(&self, x: usize, y: usize) -> bool {
+        self.get(x, y) != TileType::Wall
+    }
+
+    fn is_border(&self, x: usize, y: usize) -> bool {
+        x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1
+    }
+
+    fn add_border(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_border(x, y) {
+                    self.set(x, y, TileType::Wall);
+                }
+            }
+        }
+    }
+
+    // Opens a square around the tile, never touching the border
+    fn clear_area(&mut self, (x, y): (usize, usize), radius: usize) {
+        for cy in y.saturating_sub(radius)..=(y + radius).min(self.height - 1) {
+            for cx in x.saturating_sub(radius)..=(x + radius).min(self.width - 1) {
+                if !self.is_border(cx, cy) && !self.is_open(cx, cy) {
+                    self.set(cx, cy, TileType::Grass);
+                }
+            }
+        }
+    }
+
+    fn carve_corridor(&mut self, from: (usize, usize), to: (usize, usize), horizontal_first: bool) {
+        let corner = if horizontal_first { (to.0, from.1) } else { (from.0, to.1) };
+
+        for (start, end) in [(from, corner), (corner, to)] {
+            for x in start.0.min(end.0)..=start.0.max(end.0) {
+                for y in start.1.min(end.1)..=start.1.max(end.1) {
+                    self.clear_area((x, y), CORRIDOR_HALF_WIDTH);
+                }
+            }
+        }
+    }
+
+    fn wall_neighbours(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for dy in -1i32..=1 {
+            for dx in -1i32..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                let outside = nx < 0 || ny < 0 || nx as usize >= self.width || ny as usize >= self.height;
+                if outside || !self.is_open(nx as usize, ny as usize) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn reachable_from(&self, start: (usize, usize)) -> Vec<bool> {
+        let mut reachable = vec![false; self.width * self.height];
+        let mut queue = VecDeque::from([start]);
+        reachable[start.1 * self.width + start.0] = true;
+
+        while let Some((x, y)) = queue.pop_front() {
+            for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                let index = ny * self.width + nx;
+                if self.is_open(nx, ny) && !reachable[index] {
+                    reachable[index] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        reachable
+    }
+
+    fn into_map(self, tile_size: i32) -> GameMap {
+        let tiles = self.tiles.iter().map(|tile_type| GameTile::new(*tile_type)).collect();
+
+        GameMap::from_tiles(self.width, self.height, tile_size, tiles)
+    }
+}
+
+// Every spawn point and every open tile is reachable from the start, disconnected pockets are walled in
+pub fn generate_map(width: usize, height: usize, tile_size: i32, generator: MapGenerator, terrain: &[TerrainPatch], seed: u64, start: (usize, usize)) -> GameMap {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let (mut grid, spawn_points) = match generator {
+        MapGenerator::Open => (TileGrid::filled(width, height, TileType::Grass), default_spawn_points(width, height)),
+        MapGenerator::Caves { fill_chance, smoothing_steps } => (caves(width, height, fill_chance, smoothing_steps, &mut rng), default_spawn_points(width, height)),
+        MapGenerator::Dungeon { room_count, min_room_size, max_room_size } => dungeon(width, height, start, room_count, min_room_size, max_room_size, &mut rng),
+        MapGenerator::Field { obstacle_chance, max_obstacle_size } => (field(width, height, obstacle_chance, max_obstacle_size, &mut rng), default_spawn_points(width, height)),
+    };
+
+    grid.add_border();
+    grid.clear_area(start, START_CLEARING);
+    for &spawn_point in &spawn_points {
+        grid.clear_area(spawn_point, CORRIDOR_HALF_WIDTH);
+    }
+
+    connect_spawn_points(&mut grid, start, &spawn_points, &mut rng);
+
+    let reachable = grid.reachable_from(start);
+    for (index, tile_type) in grid.tiles.iter_mut().enumerate() {
+        if !reachable[index] {
+            *tile_type = TileType::Wall;
+        }
+    }
+
+    for patch in terrain {
+        paint_terrain(&mut grid, patch, start, &mut rng);
+    }
+
+    for &(x, y) in &spawn_points {
+        grid.set(x, y, TileType::SpawnPoint);
+    }
+
+    grid.into_map(tile_size)
+}
+
+// Blocking tiles such as crates only go where all eight neighbours are grass, so painting can't break connectivity.
+// The player start is kept clear.
+fn paint_terrain(grid: &mut TileGrid, patch: &TerrainPatch, start: (usize, usize), rng: &mut StdRng) {
+    let blocks = GameTile::new(patch.tile_type).blocks_collision();
+    let radius_squared = if blocks { 0 } else { (patch.radius * patch.radius) as i64 };
+    let keep_clear = (START_CLEARING + patch.radius + 1) as i64;
+
+    for _ in 0..patch.count {
+        let centre = (rng.random_range(1..grid.width - 1) as i64, rng.random_range(1..grid.height - 1) as i64);
+        if (centre.0 - start.0 as i64).abs() <= keep_clear && (centre.1 - start.1 as i64).abs() <= keep_clear {
+            continue;
+        }
+
+        if blocks {
+            let surrounded = (-1..=1).all(|dy| (-1..=1).all(|dx| grid.get((centre.0 + dx) as usize, (centre.1 + dy) as usize) == TileType::Grass));
+            if surrounded {
+                grid.set(centre.0 as usize, centre.1 as usize, patch.tile_type);
+            }
+            continue;
+        }
+
+        for y in (centre.1 - patch.radius as i64).max(0)..=(centre.1 + patch.radius as i64).min(grid.height as i64 - 1) {
+            for x in (centre.0 - patch.radius as i64).max(0)..=(centre.0 + patch.radius as i64).min(grid.width as i64 - 1) {
+                let distance_squared = (x - centre.0).pow(2) + (y - centre.1).pow(2);
+                if distance_squared <= radius_squared && grid.get(x as usize, y as usize) == TileType::Grass {
+                    grid.set(x as usize, y as usize, patch.tile_type);
+                }
+            }
+        }
+    }
+}
+
+fn default_spawn_points(width: usize, height: usize) -> Vec<(usize, usize)> {
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| GameMap::is_default_spawn_point(x, y, width, height))
+        .collect()
+}
+
+// Carves from each stranded spawn point to the closest tile already reachable from the start
+fn connect_spawn_points(grid: &mut TileGrid, start: (usize, usize), spawn_points: &[(usize, usize)], rng: &mut StdRng) {
+    for &spawn_point in spawn_points {
+        let reachable = grid.reachable_from(start);
+        if reachable[spawn_point.1 * grid.width + spawn_point.0] {
+            continue;
+        }
+
+        let closest = reachable
+            .iter()
+            .enumerate()
+            .filter(|(_, reachable)| **reachable)
+            .map(|(index, _)| (index % grid.width, index / grid.width))
+            .min_by_key(|&(x, y)| x.abs_diff(spawn_point.0) + y.abs_diff(spawn_point.1))
+            .unwrap_or(start);
+
+        grid.carve_corridor(spawn_point, closest, rng.random_bool(0.5));
+    }
+}
+
+fn caves(width: usize, height: usize, fill_chance: f64, smoothing_steps: u32, rng: &mut StdRng) -> TileGrid {
+    let mut grid = TileGrid::filled(width, height, TileType::Grass);
+    for tile_type in grid.tiles.iter_mut() {
+        if rng.random_bool(fill_chance.clamp(0.0, 1.0)) {
+            *tile_type = TileType::Wall;
+        }
+    }
+
+    for _ in 0..smoothing_steps {
+        let next_tiles = (0..width * height)
+            .map(|index| {
+                let (x, y) = (index % width, index / width);
+                match grid.wall_neighbours(x, y) {
+                    count if count > 4 => TileType::Wall,
+                    count if count < 4 => TileType::Grass,
+                    _ => grid.tiles[index],
+                }
+            })
+            .collect();
+        grid.tiles = next_tiles;
+    }
+
+    grid
+}
+
+// Spawn points are the centres of every room but the first
+fn dungeon(
+    width: usize,
+    height: usize,
+    start: (usize, usize),
+    room_count: u32,
+    min_room_size: usize,
+    max_room_size: usize,
+    rng: &mut StdRng,
+) -> (TileGrid, Vec<(usize, usize)>) {
+    let mut grid = TileGrid::filled(width, height, TileType::Wall);
+    let max_room_size = max_room_size.min(width.min(height).saturating_sub(2)).max(1);
+    let min_room_size = min_room_size.clamp(1, max_room_size);
+
+    let first_size = (min_room_size + max_room_size) / 2;
+    let mut rooms = vec![(start.0.saturating_sub(first_size / 2), start.1.saturating_sub(first_size / 2), first_size, first_size)];
+
+    let mut attempts = 0;
+    while rooms.len() < room_count as usize && attempts < room_count * ROOM_ATTEMPTS_PER_ROOM {
+        attempts += 1;
+
+        let (room_width, room_height) = (rng.random_range(min_room_size..=max_room_size), rng.random_range(min_room_size..=max_room_size));
+        if room_width + 2 >= width || room_height + 2 >= height {
+            continue;
+        }
+        let (x, y) = (rng.random_range(1..width - room_width - 1), rng.random_range(1..height - room_height - 1));
+
+        // Rooms keep at least one wall tile between them
+        let overlaps = rooms.iter().any(|&(other_x, other_y, other_width, other_height)| {
+            x <= other_x + other_width && other_x <= x + room_width && y <= other_y + other_height && other_y <= y + room_height
+        });
+        if !overlaps {
+            rooms.push((x, y, room_width, room_height));
+        }
+    }
+
+    for &(x, y, room_width, room_height) in &rooms {
+        for room_y in y..(y + room_height).min(height) {
+            for room_x in x..(x + room_width).min(width) {
+                grid.set(room_x, room_y, TileType::Grass);
+            }
+        }
+    }
+
+    let centres: Vec<(usize, usize)> = rooms.iter().map(|&(x, y, room_width, room_height)| (x + room_width / 2, y + room_height / 2)).collect();
+    for pair in centres.windows(2) {
+        grid.carve_corridor(pair[0], pair[1], rng.random_bool(0.5));
+    }
+
+    // Spawn points go in every room but the player's, with no other rooms they fall back to the defaults
+    let spawn_points = if centres.len() > 1 { centres.into_iter().skip(1).collect() } else { default_spawn_points(width, height) };
+
+    (grid, spawn_points)
+}
+
+fn field(width: usize, height: usize, obstacle_chance: f64, max_obstacle_size: usize, rng: &mut StdRng) -> TileGrid {
+    let mut grid = TileGrid::filled(width, height, TileType::Grass);
+    let max_obstacle_size = max_obstacle_size.max(1);
+
+    for y in 0..height {
+        for x in 0..width {
+            if !rng.random_bool(obstacle_chance.clamp(0.0, 1.0)) {
+                continue;
+            }
+
+            let (obstacle_width, obstacle_height) = (rng.random_range(1..=max_obstacle_size), rng.random_range(1..=max_obstacle_size));
+            for obstacle_y in y..(y + obstacle_height).min(height) {
+                for obstacle_x in x..(x + obstacle_width).min(width) {
+                    grid.set(obstacle_x, obstacle_y, TileType::Wall);
+                }
+            }
+        }
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_room_dungeons_fall_back_to_default_spawn_points() {
+        let generator = MapGenerator::Dungeon { room_count: 1, min_room_size: 6, max_room_size: 10 };
+        let map = generate_map(40, 40, 100, generator, &[], 7, (20, 20));
+
+        let (mut spawn_points, mut expected) = (map.spawn_points(), default_spawn_points(40, 40));
+        spawn_points.sort();
+        expected.sort();
+        assert_eq!(spawn_points, expected);
+    }
+}
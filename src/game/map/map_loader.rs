@@ -0,0 +1,547 @@
+use crate::game::map::game_map::{GameMap, MapTrigger, TileLinks};
+use crate::game::map::game_tile::GameTile;
+use crate::game::map::tile_type::TileType;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Tiled stores flip and rotation flags in the top bits of each gid
+const TILED_GID_MASK: u32 = 0x0FFF_FFFF;
+
+#[derive(PartialEq, Debug)]
+pub enum MapLoadError {
+    Io(String),
+    Parse(String),
+    UnsupportedFormat(String),
+    UnknownTile { x: usize, y: usize, symbol: char },
+    RaggedRow { y: usize, expected: usize, found: usize },
+    UnknownLayer(String),
+    LayerSize { layer: String, expected: usize, found: usize },
+    UnknownObject { name: String, x: usize, y: usize },
+    OutOfBounds { x: i64, y: i64 },
+    OpenEdge { x: usize, y: usize },
+    MissingPlayerStart,
+    DuplicatePlayerStart { x: usize, y: usize },
+    BlockedPlayerStart { x: usize, y: usize },
+    BlockedSpawnPoint { x: usize, y: usize },
+    UnreachableSpawnPoint { x: usize, y: usize },
+    UnpairedPortal { x: usize, y: usize },
+    UnlinkedPressurePlate { x: usize, y: usize },
+    UnlinkedDoor { x: usize, y: usize },
+}
+
+impl fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapLoadError::Io(error) => write!(f, "could not read map file: {}", error),
+            MapLoadError::Parse(error) => write!(f, "could not parse map file: {}", error),
+            MapLoadError::UnsupportedFormat(format) => write!(f, "unsupported map format: {}", format),
+            MapLoadError::UnknownTile { x, y, symbol } => write!(f, "unknown tile '{}' at ({}, {})", symbol, x, y),
+            MapLoadError::RaggedRow { y, expected, found } => write!(f, "row {} is {} tiles wide, expected {}", y, found, expected),
+            MapLoadError::UnknownLayer(layer) => write!(f, "tile layer '{}' does not name a tile type", layer),
+            MapLoadError::LayerSize { layer, expected, found } => write!(f, "tile layer '{}' has {} tiles, expected {}", layer, found, expected),
+            MapLoadError::UnknownObject { name, x, y } => write!(f, "unknown object '{}' at ({}, {})", name, x, y),
+            MapLoadError::OutOfBounds { x, y } => write!(f, "object at ({}, {}) is outside the map", x, y),
+            MapLoadError::OpenEdge { x, y } => write!(f, "map edge at ({}, {}) is not a wall", x, y),
+            MapLoadError::MissingPlayerStart => write!(f, "map has no player start"),
+            MapLoadError::DuplicatePlayerStart { x, y } => write!(f, "second player start at ({}, {})", x, y),
+            MapLoadError::BlockedPlayerStart { x, y } => write!(f, "player start at ({}, {}) is on a wall", x, y),
+            MapLoadError::BlockedSpawnPoint { x, y } => write!(f, "spawn point at ({}, {}) is on a wall", x, y),
+            MapLoadError::UnreachableSpawnPoint { x, y } => write!(f, "spawn point at ({}, {}) can't be reached from the player start", x, y),
+            MapLoadError::UnpairedPortal { x, y } => write!(f, "portal at ({}, {}) needs exactly one other portal with the same name", x, y),
+            MapLoadError::UnlinkedPressurePlate { x, y } => write!(f, "pressure plate at ({}, {}) has no door with the same name", x, y),
+            MapLoadError::UnlinkedDoor { x, y } => write!(f, "door at ({}, {}) has no pressure plate with the same name", x, y),
+        }
+    }
+}
+
+pub struct LoadedMap {
+    pub game_map: GameMap,
+    pub player_start: (usize, usize),
+}
+
+pub fn load_map_file(path: &Path, tile_size: i32) -> Result<LoadedMap, MapLoadError> {
+    let text = fs::read_to_string(path).map_err(|error| MapLoadError::Io(format!("{}: {}", path.display(), error)))?;
+    parse_map(path, &text, tile_size)
+}
+
+// .tmx and .tmj come from Tiled, anything else is read as ASCII
+pub fn parse_map(path: &Path, text: &str, tile_size: i32) -> Result<LoadedMap, MapLoadError> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tmx") => parse_tmx(text, tile_size),
+        Some("tmj") | Some("json") => parse_tmj(text, tile_size),
+        _ => parse_ascii(text, tile_size),
+    }
+}
+
+// Empty lines are skipped, a row of spaces is a row of empty tiles.
+// '#' wall, '.' grass, ' ' empty, 'S' spawn point, 'P' player start on grass,
+// '~' water, '^' lava, '*' ice, '%' thorns, '+' healing spring, '&' breakable wall, '$' crate,
+// digits are portals paired by digit, lowercase letters are pressure plates opening the doors of the same uppercase letter
+pub fn parse_ascii(text: &str, tile_size: i32) -> Result<LoadedMap, MapLoadError> {
+    let rows: Vec<&str> = text.lines().map(|line| line.trim_end_matches('\r')).filter(|line| !line.is_empty()).collect();
+    let width = rows.first().map(|row| row.chars().count()).unwrap_or(0);
+    let mut builder = MapBuilder::new(width, rows.len());
+
+    for (y, row) in rows.iter().enumerate() {
+        let found = row.chars().count();
+        if found != width {
+            return Err(MapLoadError::RaggedRow { y, expected: width, found });
+        }
+
+        for (x, symbol) in row.chars().enumerate() {
+            match symbol {
+                '#' => builder.set(x, y, TileType::Wall),
+                '.' => builder.set(x, y, TileType::Grass),
+                ' ' => builder.set(x, y, TileType::Empty),
+                'S' => builder.set(x, y, TileType::SpawnPoint),
+                '~' => builder.set(x, y, TileType::Water),
+                '^' => builder.set(x, y, TileType::Lava),
+                '*' => builder.set(x, y, TileType::Ice),
+                '%' => builder.set(x, y, TileType::Thorns),
+                '+' => builder.set(x, y, TileType::HealingSpring),
+                '&' => builder.set(x, y, TileType::BreakableWall),
+                '$' => builder.set(x, y, TileType::Crate),
+                'P' => {
+                    builder.set(x, y, TileType::Grass);
+                    builder.set_player_start(x, y)?;
+                }
+                '0'..='9' => builder.add_linked(&symbol.to_string(), x, y, TileType::Portal),
+                'a'..='z' => builder.add_linked(&symbol.to_string(), x, y, TileType::PressurePlate),
+                'A'..='Z' => builder.add_linked(&symbol.to_ascii_lowercase().to_string(), x, y, TileType::Door),
+                _ => return Err(MapLoadError::UnknownTile { x, y, symbol }),
+            }
+        }
+    }
+
+    builder.finish(tile_size)
+}
+
+// Tile layers are named after the tile type they paint, later layers draw over earlier ones.
+// Object layers hold PlayerStart, SpawnPoint and Trigger objects matched by class, type or name.
+// Portal, PressurePlate and Door objects are linked to the others sharing their name.
+pub fn parse_tmx(text: &str, tile_size: i32) -> Result<LoadedMap, MapLoadError> {
+    let document = roxmltree::Document::parse(text).map_err(|error| MapLoadError::Parse(error.to_string()))?;
+    let root = document.root_element();
+
+    if root.attribute("infinite") == Some("1") {
+        return Err(MapLoadError::UnsupportedFormat("infinite Tiled maps".to_string()));
+    }
+
+    let attribute = |name: &str| -> Result<usize, MapLoadError> {
+        root.attribute(name)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| MapLoadError::Parse(format!("map is missing '{}'", name)))
+    };
+    let (width, height) = (attribute("width")?, attribute("height")?);
+    let tile_pixels = (attribute("tilewidth")? as f64, attribute("tileheight")? as f64);
+    let mut builder = MapBuilder::new(width, height);
+
+    for node in root.descendants() {
+        if node.has_tag_name("layer") {
+            let name = node.attribute("name").unwrap_or_default().to_string();
+            let data = node.children()
+                .find(|child| child.has_tag_name("data"))
+                .ok_or_else(|| MapLoadError::Parse(format!("tile layer '{}' has no data", name)))?;
+
+            let gids = match data.attribute("encoding") {
+                Some("csv") => data.text().unwrap_or_default()
+                    .split(',')
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| value.trim().parse::<u32>().map_err(|error| MapLoadError::Parse(format!("tile layer '{}': {}", name, error))))
+                    .collect::<Result<Vec<u32>, MapLoadError>>()?,
+                None => data.children()
+                    .filter(|child| child.has_tag_name("tile"))
+                    .map(|tile| tile.attribute("gid").and_then(|gid| gid.parse().ok()).unwrap_or(0))
+                    .collect(),
+                Some(encoding) => return Err(MapLoadError::UnsupportedFormat(format!("'{}' encoding in tile layer '{}', save as CSV", encoding, name))),
+            };
+
+            builder.paint_layer(&name, &gids)?;
+        } else if node.has_tag_name("object") {
+            let kind = node.attribute("class").or(node.attribute("type")).filter(|kind| !kind.is_empty()).or(node.attribute("name")).unwrap_or_default();
+            let number = |name: &str| node.attribute(name).and_then(|value| value.parse::<f64>().ok()).unwrap_or(0.0);
+
+            builder.add_object(kind, node.attribute("name").unwrap_or_default(), (number("x"), number("y"), number("width"), number("height")), tile_pixels)?;
+        }
+    }
+
+    builder.finish(tile_size)
+}
+
+#[derive(Deserialize)]
+struct TiledJsonMap {
+    width: usize,
+    height: usize,
+    tilewidth: f64,
+    tileheight: f64,
+    #[serde(default)]
+    infinite: bool,
+    layers: Vec<TiledJsonLayer>,
+}
+
+#[derive(Deserialize)]
+struct TiledJsonLayer {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    layer_type: String,
+    #[serde(default)]
+    data: Option<Value>,
+    #[serde(default)]
+    objects: Vec<TiledJsonObject>,
+    // Group layers
+    #[serde(default)]
+    layers: Vec<TiledJsonLayer>,
+}
+
+#[derive(Deserialize)]
+struct TiledJsonObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    object_type: String,
+    #[serde(default)]
+    class: String,
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    width: f64,
+    #[serde(default)]
+    height: f64,
+}
+
+pub fn parse_tmj(text: &str, tile_size: i32) -> Result<LoadedMap, MapLoadError> {
+    let map: TiledJsonMap = serde_json::from_str(text).map_err(|error| MapLoadError::Parse(error.to_string()))?;
+    if map.infinite {
+        return Err(MapLoadError::UnsupportedFormat("infinite Tiled maps".to_string()));
+    }
+
+    let mut builder = MapBuilder::new(map.width, map.height);
+    add_json_layers(&mut builder, &map.layers, (map.tilewidth, map.tileheight))?;
+    builder.finish(tile_size)
+}
+
+fn add_json_layers(builder: &mut MapBuilder, layers: &[TiledJsonLayer], tile_pixels: (f64, f64)) -> Result<(), MapLoadError> {
+    for layer in layers {
+        match layer.layer_type.as_str() {
+            "tilelayer" => {
+                let gids = match &layer.data {
+                    Some(Value::Array(values)) => values.iter().map(|value| value.as_u64().unwrap_or(0) as u32).collect::<Vec<u32>>(),
+                    _ => return Err(MapLoadError::UnsupportedFormat(format!("encoded data in tile layer '{}', save as CSV", layer.name))),
+                };
+                builder.paint_layer(&layer.name, &gids)?;
+            }
+            "objectgroup" => {
+                for object in &layer.objects {
+                    let kind = [&object.class, &object.object_type, &object.name].into_iter().find(|kind| !kind.is_empty()).map(String::as_str).unwrap_or_default();
+                    builder.add_object(kind, &object.name, (object.x, object.y, object.width, object.height), tile_pixels)?;
+                }
+            }
+            "group" => add_json_layers(builder, &layer.layers, tile_pixels)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+struct MapBuilder {
+    width: usize,
+    height: usize,
+    tiles: Vec<TileType>,
+    player_start: Option<(usize, usize)>,
+    triggers: Vec<MapTrigger>,
+    // Portals, pressure plates and doors by the name that links them
+    linked: BTreeMap<String, Vec<LinkedTile>>,
+}
+
+struct LinkedTile {
+    tile_type: TileType,
+    tile: (usize, usize),
+}
+
+impl MapBuilder {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height, tiles: vec![TileType::Empty; width * height], player_start: None, triggers: Vec::new(), linked: BTreeMap::new() }
+    }
+
+    fn add_linked(&mut self, name: &str, x: usize, y: usize, tile_type: TileType) {
+        self.set(x, y, tile_type);
+        self.linked.entry(name.to_string()).or_default().push(LinkedTile { tile_type, tile: (x, y) });
+    }
+
+    fn set(&mut self, x: usize, y: usize, tile_type: TileType) {
+        self.tiles[y * self.width + x] = tile_type;
+    }
+
+    fn set_player_start(&mut self, x: usize, y: usize) -> Result<(), MapLoadError> {
+        if self.player_start.is_some() {
+            return Err(MapLoadError::DuplicatePlayerStart { x, y });
+        }
+        self.player_start = Some((x, y));
+        Ok(())
+    }
+
+    fn paint_layer(&mut self, name: &str, gids: &[u32]) -> Result<(), MapLoadError> {
+        let tile_type = match name.to_lowercase().replace([' ', '_'], "").as_str() {
+            "wall" | "walls" => TileType::Wall,
+            "grass" | "ground" | "floor" => TileType::Grass,
+            "spawn" | "spawns" | "spawnpoint" | "spawnpoints" => TileType::SpawnPoint,
+            "water" => TileType::Water,
+            "lava" => TileType::Lava,
+            "ice" => TileType::Ice,
+            "thorns" => TileType::Thorns,
+            "healing" | "healingspring" | "healingsprings" => TileType::HealingSpring,
+            "breakablewall" | "breakablewalls" => TileType::BreakableWall,
+            "crate" | "crates" => TileType::Crate,
+            _ => return Err(MapLoadError::UnknownLayer(name.to_string())),
+        };
+
+        if gids.len() != self.tiles.len() {
+            return Err(MapLoadError::LayerSize { layer: name.to_string(), expected: self.tiles.len(), found: gids.len() });
+        }
+
+        for (tile, gid) in self.tiles.iter_mut().zip(gids) {
+            if gid & TILED_GID_MASK != 0 {
+                *tile = tile_type;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Bounds are in pixels, points use the tile they fall in
+    fn add_object(&mut self, kind: &str, name: &str, (x, y, width, height): (f64, f64, f64, f64), tile_pixels: (f64, f64)) -> Result<(), MapLoadError> {
+        let (tile_x, tile_y) = ((x / tile_pixels.0).floor() as i64, (y / tile_pixels.1).floor() as i64);
+        if tile_x < 0 || tile_y < 0 || tile_x as usize >= self.width || tile_y as usize >= self.height {
+            return Err(MapLoadError::OutOfBounds { x: tile_x, y: tile_y });
+        }
+        let (tile_x, tile_y) = (tile_x as usize, tile_y as usize);
+
+        match kind.to_lowercase().replace([' ', '_'], "").as_str() {
+            "playerstart" | "player" => self.set_player_start(tile_x, tile_y),
+            "spawnpoint" | "spawn" => {
+                if self.tiles[tile_y * self.width + tile_x] == TileType::Wall {
+                    return Err(MapLoadError::BlockedSpawnPoint { x: tile_x, y: tile_y });
+                }
+                self.set(tile_x, tile_y, TileType::SpawnPoint);
+                Ok(())
+            }
+            "trigger" => {
+                self.triggers.push(MapTrigger {
+                    name: name.to_string(),
+                    x: tile_x,
+                    y: tile_y,
+                    width: ((width / tile_pixels.0).ceil() as usize).max(1),
+                    height: ((height / tile_pixels.1).ceil() as usize).max(1),
+                });
+                Ok(())
+            }
+            "portal" => {
+                self.add_linked(name, tile_x, tile_y, TileType::Portal);
+                Ok(())
+            }
+            "pressureplate" | "plate" => {
+                self.add_linked(name, tile_x, tile_y, TileType::PressurePlate);
+                Ok(())
+            }
+            "door" => {
+                self.add_linked(name, tile_x, tile_y, TileType::Door);
+                Ok(())
+            }
+            _ => Err(MapLoadError::UnknownObject { name: kind.to_string(), x: tile_x, y: tile_y }),
+        }
+    }
+
+    fn finish(self, tile_size: i32) -> Result<LoadedMap, MapLoadError> {
+        let is_open = |x: usize, y: usize| self.tiles[y * self.width + x] != TileType::Wall;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let is_edge = x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1;
+                if is_edge && is_open(x, y) {
+                    return Err(MapLoadError::OpenEdge { x, y });
+                }
+            }
+        }
+
+        let (start_x, start_y) = self.player_start.ok_or(MapLoadError::MissingPlayerStart)?;
+        if !is_open(start_x, start_y) {
+            return Err(MapLoadError::BlockedPlayerStart { x: start_x, y: start_y });
+        }
+
+        let mut reachable = vec![false; self.tiles.len()];
+        let mut queue = VecDeque::from([(start_x, start_y)]);
+        reachable[start_y * self.width + start_x] = true;
+        while let Some((x, y)) = queue.pop_front() {
+            for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                let index = ny * self.width + nx;
+                if is_open(nx, ny) && !reachable[index] {
+                    reachable[index] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        for (index, tile_type) in self.tiles.iter().enumerate() {
+            if *tile_type == TileType::SpawnPoint && !reachable[index] {
+                return Err(MapLoadError::UnreachableSpawnPoint { x: index % self.width, y: index / self.width });
+            }
+        }
+
+        let links = self.links()?;
+        let tiles = self.tiles.iter().map(|tile_type| GameTile::new(*tile_type)).collect();
+        let mut game_map = GameMap::from_tiles(self.width, self.height, tile_size, tiles);
+        game_map.links = links;
+        game_map.triggers = self.triggers;
+
+        Ok(LoadedMap {
+            game_map,
+            player_start: (start_x, start_y),
+        })
+    }
+
+    // Portals lead to their partner, pressure plates open every door of the same name
+    fn links(&self) -> Result<TileLinks, MapLoadError> {
+        let mut links = TileLinks::default();
+
+        for members in self.linked.values() {
+            let of_type = |wanted: TileType| members.iter().filter(move |linked| linked.tile_type == wanted).map(|linked| linked.tile);
+            let portals: Vec<(usize, usize)> = of_type(TileType::Portal).collect();
+            let plates: Vec<(usize, usize)> = of_type(TileType::PressurePlate).collect();
+            let doors: Vec<(usize, usize)> = of_type(TileType::Door).collect();
+
+            match portals.as_slice() {
+                [] => {}
+                [first, second] => {
+                    links.insert(*first, vec![*second]);
+                    links.insert(*second, vec![*first]);
+                }
+                [(x, y), ..] => return Err(MapLoadError::UnpairedPortal { x: *x, y: *y }),
+            }
+
+            match (plates.first(), doors.first()) {
+                (Some(&(x, y)), None) => return Err(MapLoadError::UnlinkedPressurePlate { x, y }),
+                (None, Some(&(x, y))) => return Err(MapLoadError::UnlinkedDoor { x, y }),
+                _ => {}
+            }
+            for plate in plates {
+                links.insert(plate, doors.clone());
+            }
+        }
+
+        Ok(links)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::asset::loader::EMBER_ARENA_MAP;
+
+    #[test]
+    fn the_shipped_arena_map_loads_with_its_boss_trigger() {
+        let loaded_map = parse_tmj(EMBER_ARENA_MAP, 100).unwrap();
+        let game_map = &loaded_map.game_map;
+
+        assert_eq!((game_map.width, game_map.height), (40, 30));
+        assert_eq!(loaded_map.player_start, (5, 15));
+        assert_eq!(game_map.spawn_points().len(), 7);
+
+        let trigger = game_map.triggers.iter().find(|trigger| trigger.name == "boss_arena").unwrap();
+        assert!(trigger.contains((30, 15)));
+        assert!(!trigger.contains(loaded_map.player_start));
+    }
+
+    fn ascii_error(text: &str) -> MapLoadError {
+        parse_ascii(text, 100).err().expect("map should not load")
+    }
+
+    #[test]
+    fn ascii_maps_load_with_links() {
+        let loaded_map = parse_ascii("######\n#P.1S#\n#a.A1#\n######\n", 100).unwrap();
+
+        assert_eq!(loaded_map.player_start, (1, 1));
+        assert_eq!(loaded_map.game_map.spawn_points().len(), 1);
+        assert_eq!(loaded_map.game_map.links[&(3, 1)], vec![(4, 2)]);
+        assert_eq!(loaded_map.game_map.links[&(1, 2)], vec![(3, 2)]);
+    }
+
+    #[test]
+    fn ascii_errors_name_the_tile() {
+        assert_eq!(ascii_error("####\n#P.\n####"), MapLoadError::RaggedRow { y: 1, expected: 4, found: 3 });
+        assert_eq!(ascii_error("####\n#P?#\n####"), MapLoadError::UnknownTile { x: 2, y: 1, symbol: '?' });
+        assert_eq!(ascii_error("####\n#P..\n####"), MapLoadError::OpenEdge { x: 3, y: 1 });
+        assert_eq!(ascii_error("####\n#..#\n####"), MapLoadError::MissingPlayerStart);
+        assert_eq!(ascii_error("####\n#PP#\n####"), MapLoadError::DuplicatePlayerStart { x: 2, y: 1 });
+        assert_eq!(ascii_error("#####\n#P#S#\n#####"), MapLoadError::UnreachableSpawnPoint { x: 3, y: 1 });
+        assert_eq!(ascii_error("#####\n#P1.#\n#####"), MapLoadError::UnpairedPortal { x: 2, y: 1 });
+        assert_eq!(ascii_error("#####\n#P.a#\n#####"), MapLoadError::UnlinkedPressurePlate { x: 3, y: 1 });
+        assert_eq!(ascii_error("#####\n#P.A#\n#####"), MapLoadError::UnlinkedDoor { x: 3, y: 1 });
+    }
+
+    #[test]
+    fn ascii_rows_of_spaces_keep_their_place() {
+        assert_eq!(ascii_error("####\n#P.#\n    \n####"), MapLoadError::OpenEdge { x: 0, y: 2 });
+    }
+
+    // A 4x3 map of 16 pixel tiles walled in with two grass tiles in the middle
+    fn tmx(objects: &str) -> String {
+        format!(r#"<map width="4" height="3" tilewidth="16" tileheight="16">
+            <layer name="Walls"><data encoding="csv">1,1,1,1, 1,0,0,1, 1,1,1,1</data></layer>
+            <layer name="Floor"><data encoding="csv">0,0,0,0, 0,1,1,0, 0,0,0,0</data></layer>
+            <objectgroup>{}</objectgroup>
+        </map>"#, objects)
+    }
+
+    fn tmx_error(objects: &str) -> MapLoadError {
+        parse_tmx(&tmx(objects), 100).err().expect("map should not load")
+    }
+
+    #[test]
+    fn tmx_objects_use_the_tile_they_fall_in() {
+        let loaded_map = parse_tmx(&tmx(r#"<object type="PlayerStart" x="20" y="20"/><object name="Spawn" x="40" y="30"/>"#), 100).unwrap();
+
+        assert_eq!(loaded_map.player_start, (1, 1));
+        assert_eq!(loaded_map.game_map.get_tile(2, 1).tile_type, TileType::SpawnPoint);
+    }
+
+    #[test]
+    fn tmx_errors_name_the_tile() {
+        assert_eq!(tmx_error(r#"<object type="PlayerStart" x="50" y="4"/>"#), MapLoadError::BlockedPlayerStart { x: 3, y: 0 });
+        assert_eq!(tmx_error(r#"<object type="Chest" x="20" y="20"/>"#), MapLoadError::UnknownObject { name: "Chest".to_string(), x: 1, y: 1 });
+        assert_eq!(tmx_error(r#"<object type="PlayerStart" x="20" y="-20"/>"#), MapLoadError::OutOfBounds { x: 1, y: -2 });
+
+        let short_layer = r#"<map width="4" height="3" tilewidth="16" tileheight="16"><layer name="Walls"><data encoding="csv">1,1,1</data></layer></map>"#;
+        assert_eq!(parse_tmx(short_layer, 100).err(), Some(MapLoadError::LayerSize { layer: "Walls".to_string(), expected: 12, found: 3 }));
+    }
+
+    fn tmj(layer_name: &str, objects: &str) -> String {
+        format!(r#"{{"width": 4, "height": 3, "tilewidth": 16, "tileheight": 16, "layers": [
+            {{"name": "{}", "type": "tilelayer", "data": [1,1,1,1, 1,0,0,1, 1,1,1,1]}},
+            {{"name": "Objects", "type": "objectgroup", "objects": [{}]}}
+        ]}}"#, layer_name, objects)
+    }
+
+    #[test]
+    fn tmj_triggers_cover_whole_tiles() {
+        let loaded_map = parse_tmj(&tmj("Walls", r#"{"type": "PlayerStart", "x": 20, "y": 20}, {"type": "Trigger", "name": "gate", "x": 32, "y": 16, "width": 10, "height": 10}"#), 100).unwrap();
+
+        let trigger = &loaded_map.game_map.triggers[0];
+        assert_eq!((trigger.name.as_str(), trigger.x, trigger.y, trigger.width, trigger.height), ("gate", 2, 1, 1, 1));
+    }
+
+    #[test]
+    fn tmj_errors_name_the_tile() {
+        let error = |layer_name: &str, objects: &str| parse_tmj(&tmj(layer_name, objects), 100).err();
+
+        assert_eq!(error("Walls", r#"{"type": "SpawnPoint", "x": 0, "y": 40}"#), Some(MapLoadError::BlockedSpawnPoint { x: 0, y: 2 }));
+        assert_eq!(error("Walls", r#"{"type": "PlayerStart", "x": 64, "y": 20}"#), Some(MapLoadError::OutOfBounds { x: 4, y: 1 }));
+        assert_eq!(error("Walls", ""), Some(MapLoadError::MissingPlayerStart));
+        assert_eq!(error("Rocks", ""), Some(MapLoadError::UnknownLayer("Rocks".to_string())));
+    }
+}
@@ -1,4 +1,10 @@
 pub mod game_tile;
 pub mod game_map;
 pub mod tile_type;
-pub mod camera_state;
\ No newline at end of file
+pub mod camera_state;
+pub mod flow_field;
+pub mod map_generator;
+pub mod map_loader;
+pub mod terrain;
+pub mod visibility;
+pub mod tile_damage;
\ No newline at end of file
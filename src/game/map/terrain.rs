@@ -0,0 +1,148 @@
+use crate::game::map::game_map::GameMap;
+use crate::game::map::tile_type::TileType;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::damage::{resolve_damage, DamageType, Hit, ResolvedDamage};
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_store::UnitStore;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+const WATER_SPEED_MULTIPLIER: f32 = 0.5;
+// Fraction of the gap between the current and intended velocity closed per second
+const ICE_GRIP: f32 = 1.5;
+const LAVA_DAMAGE_PER_SECOND: f32 = 20.0;
+const THORNS_DAMAGE_PER_SECOND: f32 = 6.0;
+const HEALING_PER_SECOND: f32 = 8.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum TerrainEffect {
+    Slow,
+    Burning,
+    Slippery,
+    Thorns,
+    Healing,
+}
+
+impl TerrainEffect {
+    pub fn for_tile(tile_type: TileType) -> Option<Self> {
+        match tile_type {
+            TileType::Water => Some(TerrainEffect::Slow),
+            TileType::Lava => Some(TerrainEffect::Burning),
+            TileType::Ice => Some(TerrainEffect::Slippery),
+            TileType::Thorns => Some(TerrainEffect::Thorns),
+            TileType::HealingSpring => Some(TerrainEffect::Healing),
+            _ => None,
+        }
+    }
+}
+
+// The effect of the tile under the unit's centre, unless the unit is immune to it
+pub fn terrain_effect_at(game_map: &GameMap, position: Pos2FixedPoint, immunities: &[TerrainEffect]) -> Option<TerrainEffect> {
+    let (x, y) = game_map.tile_at(position)?;
+    TerrainEffect::for_tile(game_map.get_tile(x, y).tile_type).filter(|effect| !immunities.contains(effect))
+}
+
+// Turns the intended move into the move the terrain allows, velocity is last tick's move in world units per second
+pub fn terrain_movement(effect: Option<TerrainEffect>, intended: (f32, f32), velocity: (f32, f32), delta_time: f32) -> (f32, f32) {
+    match effect {
+        Some(TerrainEffect::Slow) => (intended.0 * WATER_SPEED_MULTIPLIER, intended.1 * WATER_SPEED_MULTIPLIER),
+        Some(TerrainEffect::Slippery) => {
+            let grip = (ICE_GRIP * delta_time).min(1.0);
+            let carried = (velocity.0 * delta_time, velocity.1 * delta_time);
+            (carried.0 + (intended.0 - carried.0) * grip, carried.1 + (intended.1 - carried.1) * grip)
+        }
+        _ => intended,
+    }
+}
+
+pub struct TerrainDamage {
+    pub unit_id: u32,
+    pub damage: ResolvedDamage,
+    pub killed: bool,
+}
+
+// Damage and healing over time for players and enemies, returns the damage dealt.
+// Lava burns as fire damage and thorns as physical, both through resolve_damage. Healing is applied as is.
+pub fn apply_terrain_health(units: &mut UnitStore, unit_positions: &[Pos2FixedPoint], game_map: &GameMap, delta_time: f32) -> Vec<TerrainDamage> {
+    let changes: Vec<(u32, TerrainEffect)> = units.health
+        .par_iter()
+        .zip(units.object_types.par_iter())
+        .zip(units.terrain_immunities.par_iter())
+        .enumerate()
+        .filter_map(|(index, ((health, object_type), immunities))| {
+            if !matches!(object_type, Some(ObjectType::Player) | Some(ObjectType::Enemy)) || health.current <= 0.0 {
+                return None;
+            }
+            Some((index as u32, terrain_effect_at(game_map, *unit_positions.get(index)?, immunities)?))
+        })
+        .collect();
+
+    let mut rng = rand::rng();
+    let mut damage_dealt = Vec::new();
+    for (unit_id, effect) in changes {
+        let hit = match effect {
+            TerrainEffect::Burning => Hit::new((LAVA_DAMAGE_PER_SECOND * delta_time) as f64, DamageType::Fire),
+            TerrainEffect::Thorns => Hit::new((THORNS_DAMAGE_PER_SECOND * delta_time) as f64, DamageType::Physical),
+            TerrainEffect::Healing => {
+                units.heal(unit_id, HEALING_PER_SECOND * delta_time);
+                continue;
+            },
+            TerrainEffect::Slow | TerrainEffect::Slippery => continue,
+        };
+
+        let damage = resolve_damage(units, unit_id, hit, &mut rng);
+        let killed = units.apply_damage(unit_id, damage.amount);
+        damage_dealt.push(TerrainDamage { unit_id, damage, killed });
+    }
+    damage_dealt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::game_tile::GameTile;
+    use crate::game::objects::elite_affix::EliteAffix;
+    use crate::game::objects::game_object::{test_enemy, GameObject};
+
+    const TILES: [TileType; 3] = [TileType::Lava, TileType::Thorns, TileType::HealingSpring];
+
+    // A copy of the enemy standing on each tile of TILES
+    fn health_after_one_second(enemy: GameObject) -> (Vec<f32>, Vec<TerrainDamage>) {
+        let game_map = GameMap::from_tiles(3, 1, 100, TILES.iter().map(|&tile_type| GameTile::new(tile_type)).collect());
+        let mut units = UnitStore::new();
+        let mut positions = Vec::new();
+        for index in 0..TILES.len() {
+            units.insert(index as u32, enemy.clone());
+            positions.push(Pos2FixedPoint::new(index as i32 * 100 + 50, 50));
+        }
+
+        let damage_dealt = apply_terrain_health(&mut units, &positions, &game_map, 1.0);
+        (units.health.iter().map(|health| health.current).collect(), damage_dealt)
+    }
+
+    #[test]
+    fn lava_burns_as_fire_and_thorns_cut_as_physical() {
+        let (health, damage_dealt) = health_after_one_second(test_enemy(100.0, 50.0));
+        assert_eq!(health, vec![30.0, 44.0, 58.0]);
+        let dealt: Vec<(u32, DamageType)> = damage_dealt.iter().map(|terrain_damage| (terrain_damage.unit_id, terrain_damage.damage.damage_type)).collect();
+        assert_eq!(dealt, vec![(0, DamageType::Fire), (1, DamageType::Physical)]);
+    }
+
+    #[test]
+    fn damage_reductions_apply_to_terrain_damage_but_not_healing() {
+        let mut fire_resistant = test_enemy(100.0, 50.0);
+        fire_resistant.resistances.fire = 0.5;
+        assert_eq!(health_after_one_second(fire_resistant).0, vec![40.0, 44.0, 58.0]);
+
+        let mut armoured = test_enemy(100.0, 50.0);
+        armoured.affixes = vec![EliteAffix::Armoured];
+        assert_eq!(health_after_one_second(armoured).0, vec![40.0, 47.0, 58.0]);
+    }
+
+    #[test]
+    fn terrain_reports_the_units_it_kills() {
+        let (_, damage_dealt) = health_after_one_second(test_enemy(100.0, 10.0));
+        let killed: Vec<u32> = damage_dealt.iter().filter(|terrain_damage| terrain_damage.killed).map(|terrain_damage| terrain_damage.unit_id).collect();
+        assert_eq!(killed, vec![0]);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::game::map::game_map::GameMap;
+use crate::game::map::game_tile::GameTile;
+use rustc_hash::FxHashMap;
+
+// Damage taken by breakable tiles that are still standing. Kept out of GameMap so a hit
+// doesn't copy the shared map, only a tile breaking changes it.
+#[derive(Default)]
+pub struct TileDamage {
+    damage_taken: FxHashMap<(usize, usize), f32>,
+}
+
+impl TileDamage {
+    // Returns the tile once the hits on it add up to its durability, the caller breaks it on the map
+    pub fn hit(&mut self, game_map: &GameMap, (x, y): (usize, usize), damage: f32) -> Option<GameTile> {
+        let tile = game_map.get_tile(x, y);
+        let durability = tile.durability()?;
+
+        let damage_taken = self.damage_taken.entry((x, y)).or_insert(0.0);
+        *damage_taken += damage;
+        if *damage_taken < durability {
+            return None;
+        }
+
+        self.damage_taken.remove(&(x, y));
+        Some(tile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::game_tile::GRASS_DEFAULT;
+    use crate::game::map::tile_type::TileType;
+
+    #[test]
+    fn tiles_break_once_hits_reach_their_durability() {
+        let mut tiles = vec![GRASS_DEFAULT; 9];
+        tiles[4] = GameTile::new(TileType::Crate);
+        let game_map = GameMap::from_tiles(3, 3, 100, tiles);
+        let mut tile_damage = TileDamage::default();
+
+        assert!(tile_damage.hit(&game_map, (1, 1), 15.0).is_none());
+        assert!(tile_damage.hit(&game_map, (0, 0), 100.0).is_none());
+        assert!(tile_damage.hit(&game_map, (1, 1), 5.0) == Some(GameTile::new(TileType::Crate)));
+        assert!(tile_damage.damage_taken.is_empty());
+    }
+}
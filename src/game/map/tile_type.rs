@@ -1,7 +1,20 @@
-#[derive(Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
 pub enum TileType {
     Empty,
     Wall,
     SpawnPoint,
     Grass,
+    Water,
+    Lava,
+    Ice,
+    Thorns,
+    HealingSpring,
+    BreakableWall,
+    Crate,
+    PressurePlate,
+    Door,
+    OpenDoor,
+    Portal,
 }
\ No newline at end of file
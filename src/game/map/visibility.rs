@@ -0,0 +1,110 @@
+use crate::game::map::game_map::GameMap;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FogOfWar {
+    // In tiles
+    pub sight_radius: usize,
+    // Enemies the player can't see aren't drawn
+    pub hide_enemies: bool,
+}
+
+// Explored tiles stay remembered for the rest of the run, visible tiles are recomputed whenever the player changes tile
+#[derive(Clone)]
+pub struct VisibilityMap {
+    pub width: usize,
+    pub height: usize,
+    pub tile_size: i32,
+    pub fog_of_war: FogOfWar,
+    origin: Option<(usize, usize)>,
+    explored: Vec<bool>,
+    visible: Vec<bool>,
+}
+
+impl VisibilityMap {
+    pub fn new(game_map: &GameMap, fog_of_war: FogOfWar) -> Self {
+        let tile_count = game_map.width * game_map.height;
+        Self {
+            width: game_map.width,
+            height: game_map.height,
+            tile_size: game_map.tile_size,
+            fog_of_war,
+            origin: None,
+            explored: vec![false; tile_count],
+            visible: vec![false; tile_count],
+        }
+    }
+
+    pub fn needs_update(&self, origin: (usize, usize)) -> bool {
+        self.origin != Some(origin)
+    }
+
+    // Forces the next update, for when a tile that blocks sight changed
+    pub fn invalidate(&mut self) {
+        self.origin = None;
+    }
+
+    // A tile is visible when the line from the origin reaches it without passing through a tile that blocks sight.
+    // Tiles that block sight are visible themselves so walls get revealed.
+    pub fn update(&mut self, game_map: &GameMap, origin: (usize, usize)) {
+        self.origin = Some(origin);
+        self.visible.fill(false);
+
+        let radius = self.fog_of_war.sight_radius as i64;
+        let (origin_x, origin_y) = (origin.0 as i64, origin.1 as i64);
+
+        for y in (origin_y - radius).max(0)..=(origin_y + radius).min(self.height as i64 - 1) {
+            for x in (origin_x - radius).max(0)..=(origin_x + radius).min(self.width as i64 - 1) {
+                if (x - origin_x).pow(2) + (y - origin_y).pow(2) > radius * radius {
+                    continue;
+                }
+
+                if has_line_of_sight(game_map, (origin_x, origin_y), (x, y)) {
+                    let index = y as usize * self.width + x as usize;
+                    self.visible[index] = true;
+                    self.explored[index] = true;
+                }
+            }
+        }
+    }
+
+    pub fn is_visible(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.visible[y * self.width + x]
+    }
+
+    pub fn is_explored(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.explored[y * self.width + x]
+    }
+
+    pub fn is_position_visible(&self, position: Pos2FixedPoint) -> bool {
+        position.x >= 0 && position.y >= 0 && self.is_visible((position.x / self.tile_size) as usize, (position.y / self.tile_size) as usize)
+    }
+}
+
+// Walks a Bresenham line, only the tiles strictly between the two ends can block
+fn has_line_of_sight(game_map: &GameMap, from: (i64, i64), to: (i64, i64)) -> bool {
+    let (delta_x, delta_y) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let (mut x, mut y) = from;
+    let mut error = delta_x + delta_y;
+
+    loop {
+        if (x, y) == to {
+            return true;
+        }
+        if (x, y) != from && game_map.get_tile(x as usize, y as usize).blocks_sight() {
+            return false;
+        }
+
+        let doubled_error = error * 2;
+        if doubled_error >= delta_y {
+            error += delta_y;
+            x += step_x;
+        }
+        if doubled_error <= delta_x {
+            error += delta_x;
+            y += step_y;
+        }
+    }
+}
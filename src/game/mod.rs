@@ -7,4 +7,5 @@ pub mod settings;
 pub mod constants;
 pub mod serialise;
 pub mod collision;
-pub mod maths;
\ No newline at end of file
+pub mod maths;
+pub mod events;
\ No newline at end of file
@@ -1,11 +1,11 @@
 use rand::Rng;
 use std::time::{Duration, Instant};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct Animation {
     pub tracked_unit_id: Option<u32>,
     pub fixed_frame_index: Option<usize>,
-    pub sprite_key: String,
+    pub sprite_key: &'static str,
     pub animation_length: Duration,
     pub animation_frame: f32,
     pub last_damage_time: Option<Instant>,
@@ -14,11 +14,11 @@ pub struct Animation {
 }
 
 impl Animation {
-    pub fn new(sprite_key: &str, animation_length: Duration, size: (u32, u32)) -> Self {
+    pub fn new(sprite_key: &'static str, animation_length: Duration, size: (u32, u32)) -> Self {
         Self {
             tracked_unit_id: None,
             fixed_frame_index: None,
-            sprite_key: sprite_key.to_string(),
+            sprite_key,
             animation_length,
             animation_frame: rand::rng().random_range(0.0..=1.0),
             last_damage_time: None,
@@ -28,7 +28,7 @@ impl Animation {
     }
 
     pub fn with_rotation_offset(&self, offset: f32) -> Self {
-        let mut new = self.clone();
+        let mut new = *self;
         new.rotation_offset = Some(offset);
         new
     }
@@ -1,11 +1,16 @@
 use crate::game::maths::pos_2::FIXED_POINT_SCALE;
 use crate::game::objects::animation::Animation;
 use crate::game::objects::attacks::attack_stats::{AttackName, AttackStats};
+use crate::game::objects::attacks::targeting::TargetingMode;
+use crate::game::objects::behaviour::Behaviour;
+use crate::game::objects::damage::{DamageType, Resistances};
 use crate::game::objects::game_object::GameObject;
 use crate::game::objects::object_shape::ObjectShape;
 use crate::game::objects::object_type::ObjectType;
 use crate::game::objects::on_death::OnDeath;
-use crate::game::objects::upgrades::{Upgrade, UpgradeType};
+use crate::game::objects::stat_modifier::{AttackStat, StatModifiers};
+use crate::game::objects::status_effect::{StatusEffect, StatusEffectType, StatusEffects};
+use crate::game::objects::upgrades::Upgrade;
 use crate::ui::asset::sprite::sprite_sheet::{BABY_GREEN_DRAGON, LIGHTNING_ZAP, SLASH_ATTACK};
 use crate::ui::sound::kira_audio::SOUND_01;
 use std::time::Duration;
@@ -50,6 +55,13 @@ pub fn get_basic_attack(attack_name: AttackName) -> GameObject {
         pickup_radius: None,
         loot: None,
         on_death: OnDeath::default(),
+        affixes: Vec::new(),
+        behaviour: Behaviour::default(),
+        terrain_immunities: Vec::new(),
+        status_effects: StatusEffects::new(),
+        status_immunities: Vec::new(),
+        resistances: Resistances::default(),
+        armour: 0.0,
         parent_unit_id: None,
         attack_stats: None,
     };
@@ -78,8 +90,10 @@ pub fn get_basic_attack(attack_name: AttackName) -> GameObject {
             stats.speed = 0;
             stats.lifetime = 0.3;
             stats.direction = (1.0, 0.0);
+            stats.targeting = TargetingMode::MovementDirection;
             stats.damage_duration = 0.1;
             stats.max_targets = u32::MAX;
+            stats.status_effect = Some(StatusEffect::new(StatusEffectType::Slow, 1.5, 0.4));
             stats.cast_sounds = vec![SOUND_01.to_string()];
         }
         AttackName::FireBolt => {
@@ -88,6 +102,12 @@ pub fn get_basic_attack(attack_name: AttackName) -> GameObject {
             stats.speed = 200 * FIXED_POINT_SCALE;
             stats.lifetime = 3.0;
             stats.projectile_count = 1;
+            stats.targeting = TargetingMode::NearestEnemy;
+            stats.targeting_range = 600 * FIXED_POINT_SCALE;
+            stats.damage_type = DamageType::Fire;
+            stats.homing_turn_rate = 180.0;
+            stats.bounces = 2;
+            stats.status_effect = Some(StatusEffect::new(StatusEffectType::Burn, 3.0, 2.0));
             stats.cast_sounds = vec![SOUND_01.to_string()];
         }
         AttackName::LightningBolt => {
@@ -98,6 +118,11 @@ pub fn get_basic_attack(attack_name: AttackName) -> GameObject {
             stats.projectile_count = 60;
             stats.spread_angle = 360.0;
             stats.starting_angle = 90.0;
+            stats.pierce = 1;
+            stats.damage_type = DamageType::Lightning;
+            stats.chain_count = 2;
+            stats.status_effect = Some(StatusEffect::new(StatusEffectType::Stun, 0.5, 0.0));
+            stats.status_chance = 0.2;
             stats.cast_sounds = vec![SOUND_01.to_string()];
         }
     }
@@ -107,28 +132,82 @@ pub fn get_basic_attack(attack_name: AttackName) -> GameObject {
     obj
 }
 
+const MIN_COOLDOWN: f32 = 0.1;
+// Used when upgrades give an attack extra bursts or projectiles it wasn't set up for
+const DEFAULT_BURST_DELAY: f32 = 0.15;
+const SPREAD_PER_EXTRA_PROJECTILE: f32 = 12.0;
+
+// Upgrades without an attack apply to every attack, the rest only to the attack they name
 pub fn get_modified_attack(upgrades: &Vec<Upgrade>, attack_name: AttackName) -> GameObject {
     let mut attack = get_basic_attack(attack_name);
+    let modifiers: StatModifiers = upgrades
+        .iter()
+        .filter(|upgrade| upgrade.applies_to(attack_name))
+        .flat_map(|upgrade| upgrade.upgrade_type.modifiers(upgrade.level))
+        .collect();
 
     if let Some(attack_stats) = &mut attack.attack_stats {
-        for upgrade in upgrades {
-            match upgrade.upgrade_type {
-                UpgradeType::IncreaseDamage => {
-                    attack_stats.damage += 10.0 * upgrade.level as f64;
-                }
-                UpgradeType::DecreaseCooldown => {
-                    attack_stats.cooldown *= 1.0 - (0.05 * upgrade.level as f32);
-                }
-                UpgradeType::IncreaseAOE => {
-                }
-                UpgradeType::IncreaseRange => {
-                }
-                UpgradeType::IncreaseSpeed => {
-                    attack_stats.speed += 1 * FIXED_POINT_SCALE * upgrade.level as i32;
-                }
-            }
+        attack_stats.damage = modifiers.apply(AttackStat::Damage, attack_stats.damage);
+        attack_stats.cooldown = (modifiers.apply(AttackStat::Cooldown, attack_stats.cooldown as f64) as f32).max(MIN_COOLDOWN);
+        attack_stats.lifetime = modifiers.apply(AttackStat::Lifetime, attack_stats.lifetime as f64) as f32;
+        attack_stats.speed = modifiers.apply(AttackStat::Speed, attack_stats.speed as f64) as i32;
+
+        let base_projectiles = attack_stats.projectile_count;
+        attack_stats.projectile_count = (modifiers.apply(AttackStat::ProjectileCount, base_projectiles as f64).round() as u32).max(1);
+        if base_projectiles == 1 && attack_stats.projectile_count > 1 && attack_stats.spread_angle == 0.0 {
+            attack_stats.spread_angle = SPREAD_PER_EXTRA_PROJECTILE * (attack_stats.projectile_count - 1) as f32;
+        }
+
+        attack_stats.burst_count = (modifiers.apply(AttackStat::BurstCount, attack_stats.burst_count as f64).round() as u32).max(1);
+        if attack_stats.burst_count > 1 && attack_stats.burst_delay <= 0.0 {
+            attack_stats.burst_delay = DEFAULT_BURST_DELAY;
+        }
+
+        // Attacks using their parent's shape scale it when they hit
+        let area = modifiers.apply(AttackStat::Area, 1.0) as f32;
+        attack_stats.area_multiplier = area;
+        attack.object_shape = attack.object_shape.scaled(area);
+        if let Some(animation) = attack.animation.as_mut() {
+            animation.size = ((animation.size.0 as f32 * area).round() as u32, (animation.size.1 as f32 * area).round() as u32);
         }
     }
 
+    attack.move_speed = attack.attack_stats.as_ref().map_or(attack.move_speed, |attack_stats| attack_stats.speed);
     attack
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::objects::upgrades::UpgradeType;
+
+    fn damage(upgrades: &Vec<Upgrade>, attack_name: AttackName) -> f64 {
+        get_modified_attack(upgrades, attack_name).attack_stats.unwrap().damage
+    }
+
+    #[test]
+    fn global_upgrades_apply_to_every_attack_and_per_attack_upgrades_only_to_theirs() {
+        let upgrades = vec![
+            Upgrade { upgrade_type: UpgradeType::IncreaseDamage, level: 1, attack: None },
+            Upgrade { upgrade_type: UpgradeType::IncreaseDamage, level: 2, attack: Some(AttackName::FireBolt) },
+        ];
+        let base_fire_bolt = damage(&Vec::new(), AttackName::FireBolt);
+        let base_swipe = damage(&Vec::new(), AttackName::Swipe);
+
+        assert_eq!(damage(&upgrades, AttackName::FireBolt), base_fire_bolt + 30.0);
+        assert_eq!(damage(&upgrades, AttackName::Swipe), base_swipe + 10.0);
+    }
+
+    #[test]
+    fn global_and_per_attack_percentages_stack_additively() {
+        let upgrades = vec![
+            Upgrade { upgrade_type: UpgradeType::DecreaseCooldown, level: 4, attack: None },
+            Upgrade { upgrade_type: UpgradeType::DecreaseCooldown, level: 4, attack: Some(AttackName::FireBolt) },
+        ];
+        let base_cooldown = get_basic_attack(AttackName::FireBolt).attack_stats.unwrap().cooldown;
+        let cooldown = get_modified_attack(&upgrades, AttackName::FireBolt).attack_stats.unwrap().cooldown;
+
+        // Two -20% sources give -40%, not 0.8 * 0.8
+        assert!((cooldown - (base_cooldown * 0.6).max(MIN_COOLDOWN)).abs() < 1e-5);
+    }
 }
\ No newline at end of file
@@ -2,4 +2,6 @@ pub struct AttackLanded {
     pub attack_id: u32,
     pub target_id: u32,
     pub damage: f64,
+    // 0 for a direct hit, chained hits count up from there
+    pub chain_depth: u32,
 }
\ No newline at end of file
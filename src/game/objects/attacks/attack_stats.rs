@@ -1,10 +1,18 @@
 use crate::game::maths::pos_2::FIXED_POINT_SCALE;
+use crate::game::objects::attacks::targeting::TargetingMode;
+use crate::game::objects::damage::{DamageType, Hit};
+use crate::game::objects::status_effect::StatusEffect;
+use egui::Color32;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct AttackStats {
     pub name: AttackName,
     pub damage: f64,
+    pub damage_type: DamageType,
+    pub crit_chance: f32,
+    // Damage multiplier on a critical hit
+    pub crit_multiplier: f64,
     pub cooldown: f32,
     pub direction: (f32, f32),
     pub speed: i32,
@@ -16,9 +24,32 @@ pub struct AttackStats {
     pub hit_count: u32,
     pub max_targets: u32,
     pub units_hit: Vec<u32>,
+    pub tiles_hit: Vec<(usize, usize)>,
     pub cast_sounds: Vec<String>,
     pub use_parent_shape: bool,
     pub proximity_attack: bool,
+    // From area upgrades, already applied to the attack's own shape
+    pub area_multiplier: f32,
+    pub targeting: TargetingMode,
+    // How far enemy seeking targeting modes look for a target
+    pub targeting_range: i32,
+
+    // Projectile behaviours:
+    // Degrees per second, 0 flies straight
+    pub homing_turn_rate: f32,
+    pub homing_range: i32,
+    // Extra targets passed through on top of max_targets
+    pub pierce: u32,
+    // Wall bounces left before the projectile flies through walls like any other
+    pub bounces: u32,
+    // Jumps to the next nearest enemy not yet hit, each keeping chain_falloff of the last hit's damage
+    pub chain_count: u32,
+    pub chain_range: i32,
+    pub chain_falloff: f64,
+
+    // Applied to every unit hit that isn't killed or immune, rolled per hit
+    pub status_effect: Option<StatusEffect>,
+    pub status_chance: f32,
 
     // Multi-projectile settings:
     pub projectile_count: u32,
@@ -37,11 +68,18 @@ pub enum AttackName {
     LightningBolt,
 }
 
+impl AttackName {
+    pub const ALL: [AttackName; 4] = [AttackName::Proximity, AttackName::Swipe, AttackName::FireBolt, AttackName::LightningBolt];
+}
+
 impl Default for AttackStats {
     fn default() -> Self {
         AttackStats {
             name: AttackName::FireBolt,
             damage: 1.0,
+            damage_type: DamageType::Physical,
+            crit_chance: 0.05,
+            crit_multiplier: 1.5,
             cooldown: 8.0,
             direction: (1.0, 0.0),
             speed: 300 * FIXED_POINT_SCALE,
@@ -53,9 +91,24 @@ impl Default for AttackStats {
             hit_count: 0,
             max_targets: 1,
             units_hit: Vec::new(),
+            tiles_hit: Vec::new(),
             cast_sounds: vec![],
             use_parent_shape: false,
             proximity_attack: false,
+            area_multiplier: 1.0,
+            targeting: TargetingMode::Fixed,
+            targeting_range: 500 * FIXED_POINT_SCALE,
+
+            homing_turn_rate: 0.0,
+            homing_range: 400 * FIXED_POINT_SCALE,
+            pierce: 0,
+            bounces: 0,
+            chain_count: 0,
+            chain_range: 250 * FIXED_POINT_SCALE,
+            chain_falloff: 0.7,
+
+            status_effect: None,
+            status_chance: 1.0,
 
             // Multi-projectile defaults:
             projectile_count: 1,  // Single shot by default
@@ -66,4 +119,35 @@ impl Default for AttackStats {
             initial_burst: true,     // Keep track of bursts
         }
     }
+}
+
+impl AttackStats {
+    // Damage is passed in as chained hits deal less than the attack's own damage
+    pub fn hit(&self, damage: f64) -> Hit {
+        Hit {
+            damage,
+            damage_type: self.damage_type,
+            crit_chance: self.crit_chance,
+            crit_multiplier: self.crit_multiplier,
+        }
+    }
+
+    pub fn target_limit(&self) -> u32 {
+        self.max_targets.saturating_add(self.pierce)
+    }
+
+    // Marks out projectiles with special behaviour, chaining takes priority as the most visible
+    pub fn behaviour_tint(&self) -> Option<Color32> {
+        if self.chain_count > 0 {
+            Some(Color32::from_rgb(170, 200, 255))
+        } else if self.homing_turn_rate > 0.0 {
+            Some(Color32::from_rgb(190, 255, 160))
+        } else if self.bounces > 0 {
+            Some(Color32::from_rgb(255, 200, 120))
+        } else if self.pierce > 0 {
+            Some(Color32::from_rgb(140, 255, 255))
+        } else {
+            None
+        }
+    }
 }
\ No newline at end of file
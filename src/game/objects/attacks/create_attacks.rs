@@ -1,8 +1,11 @@
 use crate::game::data::game_data::GameData;
+use crate::game::events::game_event::GameEvent;
 use crate::game::maths::pos_2::{Pos2FixedPoint, INVALID_POSITION};
 use crate::game::objects::attacks::attack_defaults::{get_basic_attack, get_modified_attack};
-use crate::game::objects::attacks::attack_stats::AttackName;
-use crate::helper::lock_helper::acquire_lock_mut;
+use crate::game::objects::attacks::attack_stats::{AttackName, AttackStats};
+use crate::game::objects::attacks::targeting::{aim_direction, direction_to, TargetingContext};
+use crate::game::objects::object_type::ObjectType;
+use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
 use rand::prelude::IndexedRandom;
 use std::sync::Arc;
 use std::thread::{sleep, spawn};
@@ -28,8 +31,9 @@ pub fn spawn_attack(
     let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "unit_positions");
     let mut empty_indexes = acquire_lock_mut(&game_data.empty_unit_indexes, "empty_unit_indexes");
     let mut attack_pools = acquire_lock_mut(&game_data.attack_pools, "attack_pools");
+    let mut events = acquire_lock_mut(&game_data.events, "events");
 
-    let parent_unit = unit_id.and_then(|id| game_units.get(id as usize).and_then(|u| u.as_ref()));
+    let parent_upgrades = unit_id.filter(|&id| game_units.is_alive(id)).map(|id| &game_units.upgrades[id as usize]);
 
     if !initial_burst {
         if let Some(parent_position) = unit_id.and_then(|id| unit_positions.get(id as usize)) {
@@ -39,11 +43,32 @@ pub fn spawn_attack(
 
     let mut base_attack = get_basic_attack(attack_name.clone());
 
-    if let Some(parent) = parent_unit {
-        base_attack = get_modified_attack(&parent.upgrades, attack_name.clone());
+    if let Some(upgrades) = parent_upgrades {
+        base_attack = get_modified_attack(upgrades, attack_name.clone());
     }
 
     if let Some(attack_stats) = base_attack.attack_stats.as_ref() {
+        let targeted_direction = {
+            let spatial_hash_grid = acquire_lock(&game_data.spatial_hash_grid, "spatial_hash_grid");
+            let context = TargetingContext {
+                units: &game_units,
+                unit_positions: &unit_positions,
+                spatial_hash_grid: &spatial_hash_grid,
+                caster_id: unit_id,
+                origin: attack_origin,
+                cursor_position: *acquire_lock(&game_data.cursor_position, "cursor_position"),
+            };
+            aim_direction(attack_stats.targeting, attack_stats.targeting_range, &context)
+        };
+
+        // Enemy attacks with nothing else to aim at go for the player
+        let aimed_direction = targeted_direction.or_else(|| {
+            unit_id
+                .filter(|&id| game_units.object_type(id) == Some(ObjectType::Enemy))
+                .and_then(|_| *acquire_lock(&game_data.player_position, "player_position"))
+                .and_then(|player_position| direction_to(attack_origin, player_position))
+        });
+
         let projectile_count = attack_stats.projectile_count;
         let spread_angle = attack_stats.spread_angle;
         let burst_count = attack_stats.burst_count;
@@ -55,11 +80,12 @@ pub fn spawn_attack(
         } else {
             0.0
         };
+        let base_direction = aimed_direction.unwrap_or(attack_stats.direction);
         let mut directions = Vec::new();
 
         for i in 0..projectile_count {
             let angle = attack_stats.starting_angle + i as f32 * angle_step - (spread_angle / 2.0);
-            let rotated_direction = rotate_vector(attack_stats.direction.0, attack_stats.direction.1, angle.to_radians());
+            let rotated_direction = rotate_vector(base_direction.0, base_direction.1, angle.to_radians());
             directions.push(rotated_direction);
         }
 
@@ -70,36 +96,47 @@ pub fn spawn_attack(
                 if let Some(mut attack_unit) = pool.pop() {
                     attack_unit.parent_unit_id = unit_id;
 
+                    // Pooled attacks start from the basic stats, so the caster's upgrades are copied over on every spawn
+                    attack_unit.object_shape = base_attack.object_shape.clone();
+                    attack_unit.move_speed = base_attack.move_speed;
+
                     if let Some(mut animation) = attack_unit.animation.as_mut() {
                         animation.animation_frame = 0.0;
+                        if let Some(base_animation) = &base_attack.animation {
+                            animation.size = base_animation.size;
+                        }
                     }
 
-                    if let Some(attack_stats) = &mut attack_unit.attack_stats {
-                        attack_stats.enabled = true;
-                        attack_stats.elapsed = 0.0;
-                        attack_stats.units_hit.clear();
-                        attack_stats.direction = direction;
-                        attack_stats.hit_count = 0;
+                    if let Some(pooled_stats) = &mut attack_unit.attack_stats {
+                        pooled_stats.enabled = true;
+                        pooled_stats.elapsed = 0.0;
+                        pooled_stats.units_hit.clear();
+                        pooled_stats.tiles_hit.clear();
+                        pooled_stats.direction = direction;
+                        pooled_stats.hit_count = 0;
+                        copy_modified_stats(pooled_stats, attack_stats);
                     }
 
                     let attack_id = if let Some(reuse_index) = empty_indexes.pop() {
-                        attack_unit.id = reuse_index;
-                        game_units[reuse_index as usize] = Some(attack_unit);
                         unit_positions[reuse_index as usize] = attack_origin;
                         reuse_index
                     } else {
-                        let new_index = game_units.len() as u32;
-                        attack_unit.id = new_index;
-                        game_units.push(Some(attack_unit));
                         unit_positions.push(attack_origin);
-                        new_index
+                        game_units.len() as u32
                     };
 
+                    attack_unit.id = attack_id;
+                    game_units.insert(attack_id, attack_unit);
+
                     spawned_projectiles.push(attack_id);
                 }
             }
         }
 
+        if !spawned_projectiles.is_empty() {
+            events.publish(GameEvent::AttackCast { caster_id: unit_id });
+        }
+
         if initial_burst && burst_count > 1 && burst_delay > 0.0 {
             for i in 0..(burst_count - 1) {
                 let game_data_clone = Arc::clone(&game_data);
@@ -112,26 +149,32 @@ pub fn spawn_attack(
     }
 }
 
+fn copy_modified_stats(attack_stats: &mut AttackStats, modified_stats: &AttackStats) {
+    attack_stats.damage = modified_stats.damage;
+    attack_stats.speed = modified_stats.speed;
+    attack_stats.lifetime = modified_stats.lifetime;
+    attack_stats.area_multiplier = modified_stats.area_multiplier;
+    attack_stats.bounces = modified_stats.bounces;
+}
+
 pub fn despawn_attack(attack_id: u32, game_data: &GameData) {
     let mut game_units = acquire_lock_mut(&game_data.units, "game_units");
     let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "unit_positions");
     let mut empty_indexes = acquire_lock_mut(&game_data.empty_unit_indexes, "empty_unit_indexes");
     let mut attack_pools = acquire_lock_mut(&game_data.attack_pools, "attack_pools");
 
-    if let Some(unit) = game_units.get_mut(attack_id as usize) {
-        if let Some(mut attack_unit) = unit.take() {
-            unit_positions[attack_id as usize] = Pos2FixedPoint::new(INVALID_POSITION, INVALID_POSITION);
-            empty_indexes.push(attack_id);
+    if let Some(mut attack_unit) = game_units.take(attack_id) {
+        unit_positions[attack_id as usize] = Pos2FixedPoint::new(INVALID_POSITION, INVALID_POSITION);
+        empty_indexes.push(attack_id);
 
-            if let Some(attack_stats) = &mut attack_unit.attack_stats {
-                attack_stats.enabled = false;
-                attack_stats.elapsed = 0.0;
-                attack_stats.units_hit.clear();
-            }
+        if let Some(attack_stats) = &mut attack_unit.attack_stats {
+            attack_stats.enabled = false;
+            attack_stats.elapsed = 0.0;
+            attack_stats.units_hit.clear();
+        }
 
-            if let Some(attack_stats) = &attack_unit.attack_stats {
-                attack_pools.get_mut(&attack_stats.name).unwrap().push(attack_unit);
-            }
+        if let Some(attack_stats) = &attack_unit.attack_stats {
+            attack_pools.get_mut(&attack_stats.name).unwrap().push(attack_unit);
         }
     }
 }
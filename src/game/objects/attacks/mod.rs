@@ -1,4 +1,5 @@
 pub mod create_attacks;
 pub mod attack_stats;
 pub mod attack_landed;
-pub mod attack_defaults;
\ No newline at end of file
+pub mod attack_defaults;
+pub mod targeting;
\ No newline at end of file
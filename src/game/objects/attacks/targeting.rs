@@ -0,0 +1,146 @@
+use crate::game::collision::spatial_hash_grid::SpatialHashGrid;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_store::UnitStore;
+use rand::prelude::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, Default)]
+pub enum TargetingMode {
+    // Fires along the attack's own direction
+    #[default]
+    Fixed,
+    NearestEnemy,
+    // Highest current health
+    StrongestEnemy,
+    RandomEnemyInRange,
+    MovementDirection,
+    MouseCursor,
+}
+
+// What an attack needs to know about where it's being cast from
+pub struct TargetingContext<'a> {
+    pub units: &'a UnitStore,
+    pub unit_positions: &'a [Pos2FixedPoint],
+    pub spatial_hash_grid: &'a SpatialHashGrid,
+    pub caster_id: Option<u32>,
+    pub origin: Pos2FixedPoint,
+    pub cursor_position: Option<Pos2FixedPoint>,
+}
+
+// Units the caster's attacks should go for
+fn hostile_type(caster_type: Option<ObjectType>) -> ObjectType {
+    match caster_type {
+        Some(ObjectType::Enemy) => ObjectType::Player,
+        _ => ObjectType::Enemy,
+    }
+}
+
+pub fn direction_to(from: Pos2FixedPoint, to: Pos2FixedPoint) -> Option<(f32, f32)> {
+    let (x, y) = (to.x as f32 - from.x as f32, to.y as f32 - from.y as f32);
+    let length = (x * x + y * y).sqrt();
+    (length > 0.0).then(|| (x / length, y / length))
+}
+
+// None when the mode has nothing to aim at, the attack then keeps its fixed direction
+pub fn aim_direction(targeting: TargetingMode, range: i32, context: &TargetingContext) -> Option<(f32, f32)> {
+    match targeting {
+        TargetingMode::Fixed => None,
+        TargetingMode::MovementDirection => {
+            let (x, y) = context.units.velocities.get(context.caster_id? as usize).copied()?;
+            let length = (x * x + y * y).sqrt();
+            (length > 0.0).then(|| (x / length, y / length))
+        }
+        TargetingMode::MouseCursor => direction_to(context.origin, context.cursor_position?),
+        TargetingMode::NearestEnemy => direction_to(context.origin, context.unit_positions[nearest_target(range, context, |_| false)? as usize]),
+        TargetingMode::StrongestEnemy | TargetingMode::RandomEnemyInRange => {
+            let targets = targets_in_range(range, context);
+            let target = match targeting {
+                TargetingMode::StrongestEnemy => targets
+                    .iter()
+                    .max_by(|(a, _), (b, _)| context.units.health[*a as usize].current.total_cmp(&context.units.health[*b as usize].current))
+                    .map(|&(id, _)| id),
+                _ => targets.choose(&mut rand::rng()).map(|&(id, _)| id),
+            }?;
+            direction_to(context.origin, context.unit_positions[target as usize])
+        }
+    }
+}
+
+// Closest hostile unit in range that isn't excluded, used for homing and chaining too
+pub fn nearest_target(range: i32, context: &TargetingContext, is_excluded: impl Fn(u32) -> bool) -> Option<u32> {
+    targets_in_range(range, context)
+        .into_iter()
+        .filter(|&(id, _)| !is_excluded(id))
+        .min_by_key(|&(_, distance_squared)| distance_squared)
+        .map(|(id, _)| id)
+}
+
+// Hostile units within range of the origin along with their squared distance
+fn targets_in_range(range: i32, context: &TargetingContext) -> SmallVec<[(u32, i64); 64]> {
+    let hostile = hostile_type(context.caster_id.and_then(|id| context.units.object_type(id)));
+    let range_squared = range as i64 * range as i64;
+
+    let mut nearby = SmallVec::new();
+    context.spatial_hash_grid.get_units_in_range_into(context.origin, range, &mut nearby);
+
+    nearby
+        .into_iter()
+        .filter(|&id| context.units.object_type(id) == Some(hostile))
+        .filter_map(|id| {
+            let position = context.unit_positions.get(id as usize)?;
+            let (delta_x, delta_y) = ((position.x - context.origin.x) as i64, (position.y - context.origin.y) as i64);
+            let distance_squared = delta_x * delta_x + delta_y * delta_y;
+            (distance_squared <= range_squared).then_some((id, distance_squared))
+        })
+        .collect()
+}
+
+// Turns the current direction towards the desired one by at most max_turn radians
+pub fn steer_towards(current: (f32, f32), desired: (f32, f32), max_turn: f32) -> (f32, f32) {
+    let current_angle = current.1.atan2(current.0);
+    let mut difference = desired.1.atan2(desired.0) - current_angle;
+    if difference > std::f32::consts::PI {
+        difference -= std::f32::consts::TAU;
+    } else if difference < -std::f32::consts::PI {
+        difference += std::f32::consts::TAU;
+    }
+
+    let angle = current_angle + difference.clamp(-max_turn, max_turn);
+    (angle.cos(), angle.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn angle((x, y): (f32, f32)) -> f32 {
+        y.atan2(x)
+    }
+
+    #[test]
+    fn steering_turns_by_at_most_the_turn_rate() {
+        let direction = steer_towards((1.0, 0.0), (0.0, 1.0), 0.1);
+        assert!((angle(direction) - 0.1).abs() < 1e-5);
+
+        let direction = steer_towards((1.0, 0.0), (0.0, -1.0), 0.1);
+        assert!((angle(direction) + 0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn steering_reaches_targets_within_the_turn_rate() {
+        let desired = (0.05f32.cos(), 0.05f32.sin());
+        let direction = steer_towards((1.0, 0.0), desired, 0.1);
+        assert!((direction.0 - desired.0).abs() < 1e-5 && (direction.1 - desired.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn steering_takes_the_short_way_round() {
+        // Just either side of pointing left, so the raw angle difference is nearly a full turn
+        let current = (-1.0, 0.01);
+        let direction = steer_towards(current, (-1.0, -0.01), 0.5);
+        assert!(direction.0 < -0.99);
+        assert!(direction.1 < 0.0);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::game::collision::spatial_hash_grid::SpatialHashGrid;
+use crate::game::map::flow_field::FlowField;
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_store::UnitStore;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+const KITER_STRAFE_SPEED: f32 = 0.5;
+const CHARGER_RECOVERY_SPEED: f32 = 0.3;
+const FLEE_SPEED: f32 = 1.2;
+const SWARM_SEPARATION_WEIGHT: f32 = 1.5;
+
+// Distances are in world units
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum Behaviour {
+    #[default]
+    Chaser,
+    // Holds a ring around the player and fires its own attacks from there
+    Kiter { preferred_distance: i32, tolerance: i32 },
+    Charger { trigger_distance: i32, wind_up: f32, charge_duration: f32, recovery: f32, charge_speed_multiplier: f32 },
+    // Chases while pulling towards and spacing out from nearby swarmers
+    Swarmer { cohesion: f32, separation_distance: i32 },
+    // Chases until health drops below the fraction, then runs
+    Fleer { flee_below: f32 },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ChargePhase {
+    #[default]
+    Approaching,
+    WindingUp,
+    Charging,
+    Recovering,
+}
+
+// Written by the behaviour phase and read by movement
+#[derive(Clone, Copy, Debug)]
+pub struct BehaviourState {
+    pub heading: (f32, f32),
+    pub speed_multiplier: f32,
+    pub charge_phase: ChargePhase,
+    pub timer: f32,
+}
+
+impl Default for BehaviourState {
+    fn default() -> Self {
+        Self {
+            heading: (0.0, 0.0),
+            speed_multiplier: 1.0,
+            charge_phase: ChargePhase::default(),
+            timer: 0.0,
+        }
+    }
+}
+
+pub fn update_behaviours(
+    units: &mut UnitStore,
+    unit_positions: &[Pos2FixedPoint],
+    spatial_grid: &SpatialHashGrid,
+    flow_field: Option<&FlowField>,
+    player_position: Pos2FixedPoint,
+    delta_time: f32,
+) {
+    let UnitStore { object_types, behaviours, behaviour_states, health, .. } = units;
+    let (object_types, behaviours, health) = (&*object_types, &*behaviours, &*health);
+
+    behaviour_states
+        .par_iter_mut()
+        .enumerate()
+        .for_each_init(SmallVec::<[u32; 64]>::new, |nearby_unit_ids, (index, state)| {
+            if object_types[index] != Some(ObjectType::Enemy) {
+                return;
+            }
+
+            let position = unit_positions[index];
+            let (to_player, distance) = direction_to(position, player_position);
+            // Routes around walls, anything moving away or charging still uses the straight line
+            let path = flow_field.and_then(|flow_field| flow_field.direction_at(position)).unwrap_or(to_player);
+
+            match behaviours[index] {
+                Behaviour::Chaser => steer(state, path, 1.0),
+                Behaviour::Kiter { preferred_distance, tolerance } => {
+                    if distance < (preferred_distance - tolerance) as f32 {
+                        steer(state, (-to_player.0, -to_player.1), 1.0);
+                    } else if distance > (preferred_distance + tolerance) as f32 {
+                        steer(state, path, 1.0);
+                    } else {
+                        steer(state, (-to_player.1, to_player.0), KITER_STRAFE_SPEED);
+                    }
+                }
+                Behaviour::Charger { trigger_distance, wind_up, charge_duration, recovery, charge_speed_multiplier } => {
+                    update_charger(state, (path, to_player), distance, trigger_distance, (wind_up, charge_duration, recovery), charge_speed_multiplier, delta_time);
+                }
+                Behaviour::Swarmer { cohesion, separation_distance } => {
+                    spatial_grid.get_nearby_units_into(position, nearby_unit_ids);
+
+                    let mut neighbour_count = 0;
+                    let mut neighbour_sum = (0.0, 0.0);
+                    let mut separation = (0.0, 0.0);
+
+                    for &other_id in nearby_unit_ids.iter() {
+                        let other_index = other_id as usize;
+                        if other_index == index || !matches!(behaviours[other_index], Behaviour::Swarmer { .. }) || object_types[other_index] != Some(ObjectType::Enemy) {
+                            continue;
+                        }
+
+                        let (to_other, other_distance) = direction_to(position, unit_positions[other_index]);
+                        neighbour_count += 1;
+                        neighbour_sum = (neighbour_sum.0 + to_other.0, neighbour_sum.1 + to_other.1);
+
+                        if other_distance < separation_distance as f32 {
+                            separation = (separation.0 - to_other.0, separation.1 - to_other.1);
+                        }
+                    }
+
+                    let mut heading = path;
+                    if neighbour_count > 0 {
+                        let (cohesion_x, cohesion_y) = normalise(neighbour_sum);
+                        heading.0 += cohesion_x * cohesion + separation.0 * SWARM_SEPARATION_WEIGHT;
+                        heading.1 += cohesion_y * cohesion + separation.1 * SWARM_SEPARATION_WEIGHT;
+                    }
+
+                    steer(state, normalise(heading), 1.0);
+                }
+                Behaviour::Fleer { flee_below } => {
+                    let unit_health = health[index];
+                    if unit_health.current < unit_health.max * flee_below {
+                        steer(state, (-to_player.0, -to_player.1), FLEE_SPEED);
+                    } else {
+                        steer(state, path, 1.0);
+                    }
+                }
+            }
+        });
+}
+
+fn update_charger(
+    state: &mut BehaviourState,
+    (path, to_player): ((f32, f32), (f32, f32)),
+    distance: f32,
+    trigger_distance: i32,
+    (wind_up, charge_duration, recovery): (f32, f32, f32),
+    charge_speed_multiplier: f32,
+    delta_time: f32,
+) {
+    state.timer -= delta_time;
+
+    match state.charge_phase {
+        ChargePhase::Approaching => {
+            steer(state, path, 1.0);
+            if distance <= trigger_distance as f32 {
+                state.charge_phase = ChargePhase::WindingUp;
+                state.timer = wind_up;
+            }
+        }
+        ChargePhase::WindingUp => {
+            // Keeps facing the player so the charge lines up at release
+            steer(state, to_player, 0.0);
+            if state.timer <= 0.0 {
+                state.charge_phase = ChargePhase::Charging;
+                state.timer = charge_duration;
+                state.speed_multiplier = charge_speed_multiplier;
+            }
+        }
+        // Heading is locked for the whole charge
+        ChargePhase::Charging => {
+            if state.timer <= 0.0 {
+                state.charge_phase = ChargePhase::Recovering;
+                state.timer = recovery;
+            }
+        }
+        ChargePhase::Recovering => {
+            steer(state, path, CHARGER_RECOVERY_SPEED);
+            if state.timer <= 0.0 {
+                state.charge_phase = ChargePhase::Approaching;
+            }
+        }
+    }
+}
+
+fn steer(state: &mut BehaviourState, heading: (f32, f32), speed_multiplier: f32) {
+    state.heading = heading;
+    state.speed_multiplier = speed_multiplier;
+}
+
+// Unit vector and distance in world units
+fn direction_to(from: Pos2FixedPoint, to: Pos2FixedPoint) -> ((f32, f32), f32) {
+    let offset = (to.x as f32 - from.x as f32, to.y as f32 - from.y as f32);
+    let distance = (offset.0 * offset.0 + offset.1 * offset.1).sqrt();
+
+    if distance > 0.0 {
+        ((offset.0 / distance, offset.1 / distance), distance)
+    } else {
+        ((0.0, 0.0), 0.0)
+    }
+}
+
+fn normalise(vector: (f32, f32)) -> (f32, f32) {
+    let length = (vector.0 * vector.0 + vector.1 * vector.1).sqrt();
+    if length > 0.0 {
+        (vector.0 / length, vector.1 / length)
+    } else {
+        (0.0, 0.0)
+    }
+}
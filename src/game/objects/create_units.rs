@@ -5,7 +5,7 @@ use crate::game::objects::object_shape::ObjectShape;
 use crate::game::objects::object_type::ObjectType;
 use std::time::Duration;
 
-pub fn create_enemy_at_point(handle: &str) -> GameObject {
+pub fn create_enemy_at_point(handle: &'static str) -> GameObject {
     let animation = Animation::new(handle, Duration::from_secs(1), (20, 20));
     GameObject::new(ObjectType::Enemy, ObjectShape::new(20 * FIXED_POINT_SCALE, 20 * FIXED_POINT_SCALE), 30 * FIXED_POINT_SCALE, 10.0, 10.0, Some(animation))
 }
\ No newline at end of file
@@ -0,0 +1,152 @@
+use crate::game::objects::elite_affix::damage_after_affixes;
+use crate::game::objects::unit_store::UnitStore;
+use egui::Color32;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Armour that blocks half of physical damage, each point past it blocks less
+const ARMOUR_FOR_HALF_DAMAGE: f32 = 50.0;
+// Anything higher would make a unit close to unkillable by that type
+const MAX_RESISTANCE: f32 = 0.75;
+// Negative resistance is a weakness, capped at double damage
+const MIN_RESISTANCE: f32 = -1.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Frost,
+    Lightning,
+}
+
+impl DamageType {
+    pub fn colour(&self) -> Color32 {
+        match self {
+            DamageType::Physical => Color32::RED,
+            DamageType::Fire => Color32::from_rgb(255, 120, 30),
+            DamageType::Frost => Color32::from_rgb(120, 200, 255),
+            DamageType::Lightning => Color32::from_rgb(255, 235, 90),
+        }
+    }
+}
+
+// Fraction of each damage type a unit ignores
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct Resistances {
+    pub physical: f32,
+    pub fire: f32,
+    pub frost: f32,
+    pub lightning: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Frost => self.frost,
+            DamageType::Lightning => self.lightning,
+        }
+    }
+}
+
+// A hit before the target's defences are applied
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    pub damage: f64,
+    pub damage_type: DamageType,
+    pub crit_chance: f32,
+    pub crit_multiplier: f64,
+}
+
+impl Hit {
+    pub fn new(damage: f64, damage_type: DamageType) -> Self {
+        Self { damage, damage_type, crit_chance: 0.0, crit_multiplier: 1.0 }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ResolvedDamage {
+    pub amount: f64,
+    pub damage_type: DamageType,
+    pub critical: bool,
+}
+
+// Fraction of physical damage blocked, approaches but never reaches 1
+pub fn armour_reduction(armour: f32) -> f32 {
+    let armour = armour.max(0.0);
+    armour / (armour + ARMOUR_FOR_HALF_DAMAGE)
+}
+
+// Crits first, then resistance, then armour for physical hits, then elite affixes
+pub fn resolve_damage(units: &UnitStore, target_id: u32, hit: Hit, rng: &mut impl Rng) -> ResolvedDamage {
+    let index = target_id as usize;
+    let critical = hit.crit_chance > 0.0 && rng.random_bool(hit.crit_chance.clamp(0.0, 1.0) as f64);
+
+    let mut amount = if critical { hit.damage * hit.crit_multiplier } else { hit.damage };
+    amount *= 1.0 - units.resistances[index].get(hit.damage_type).clamp(MIN_RESISTANCE, MAX_RESISTANCE) as f64;
+    if hit.damage_type == DamageType::Physical {
+        amount *= 1.0 - armour_reduction(units.armour[index]) as f64;
+    }
+
+    ResolvedDamage {
+        amount: damage_after_affixes(units, target_id, amount).max(0.0),
+        damage_type: hit.damage_type,
+        critical,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::objects::game_object::test_enemy;
+
+    fn damage_taken(hit: Hit, resistances: Resistances, armour: f32) -> f64 {
+        let mut enemy = test_enemy(100.0, 100.0);
+        enemy.resistances = resistances;
+        enemy.armour = armour;
+        let mut units = UnitStore::new();
+        units.insert(0, enemy);
+
+        resolve_damage(&units, 0, hit, &mut rand::rng()).amount
+    }
+
+    #[test]
+    fn armour_has_diminishing_returns_on_physical_hits() {
+        let hit = Hit::new(100.0, DamageType::Physical);
+        assert_eq!(damage_taken(hit, Resistances::default(), ARMOUR_FOR_HALF_DAMAGE), 50.0);
+        assert!((damage_taken(hit, Resistances::default(), 150.0) - 25.0).abs() < 1e-9);
+        assert_eq!(damage_taken(hit, Resistances::default(), -20.0), 100.0);
+    }
+
+    #[test]
+    fn armour_does_not_block_other_damage_types() {
+        assert_eq!(damage_taken(Hit::new(100.0, DamageType::Fire), Resistances::default(), 50.0), 100.0);
+        assert_eq!(damage_taken(Hit::new(100.0, DamageType::Lightning), Resistances::default(), 50.0), 100.0);
+    }
+
+    #[test]
+    fn resistances_are_clamped() {
+        let hit = Hit::new(100.0, DamageType::Frost);
+        assert_eq!(damage_taken(hit, Resistances { frost: 0.5, ..Resistances::default() }, 0.0), 50.0);
+        assert_eq!(damage_taken(hit, Resistances { frost: 0.95, ..Resistances::default() }, 0.0), 25.0);
+        assert_eq!(damage_taken(hit, Resistances { frost: -3.0, ..Resistances::default() }, 0.0), 200.0);
+        // Only the hit's own type counts
+        assert_eq!(damage_taken(hit, Resistances { fire: 0.5, ..Resistances::default() }, 0.0), 100.0);
+    }
+
+    #[test]
+    fn certain_crits_apply_the_multiplier_before_defences() {
+        let hit = Hit { crit_chance: 1.0, crit_multiplier: 2.0, ..Hit::new(100.0, DamageType::Fire) };
+        let mut enemy = test_enemy(100.0, 100.0);
+        enemy.resistances.fire = 0.5;
+        let mut units = UnitStore::new();
+        units.insert(0, enemy);
+
+        let damage = resolve_damage(&units, 0, hit, &mut rand::rng());
+        assert!(damage.critical);
+        assert_eq!(damage.amount, 100.0);
+        assert_eq!(damage.damage_type, DamageType::Fire);
+    }
+}
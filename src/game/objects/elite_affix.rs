@@ -0,0 +1,193 @@
+use crate::game::events::game_event::GameEvent;
+use crate::game::maths::pos_2::{Pos2FixedPoint, FIXED_POINT_SCALE};
+use crate::game::objects::game_object::GameObject;
+use crate::game::objects::object_shape::ObjectShape;
+use crate::game::objects::unit_store::UnitStore;
+use egui::Color32;
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+const ELITE_HEALTH_MULTIPLIER: f32 = 2.0;
+const LOOT_MULTIPLIER_PER_AFFIX: f64 = 0.75;
+const FAST_MOVE_SPEED_MULTIPLIER: f32 = 1.5;
+const ARMOURED_DAMAGE_TAKEN: f64 = 0.5;
+// Fraction of max health per second
+const REGENERATION_RATE: f32 = 0.03;
+// Fraction of damage dealt
+const VAMPIRIC_LEECH: f64 = 0.5;
+const EXPLOSION_RADIUS: i32 = 80 * FIXED_POINT_SCALE;
+const EXPLOSION_DAMAGE: f64 = 15.0;
+const SPLIT_COUNT: usize = 2;
+const SPLIT_HEALTH_FRACTION: f32 = 0.4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum EliteAffix {
+    Fast,
+    Armoured,
+    Regenerating,
+    Explosive,
+    Splitting,
+    Vampiric,
+}
+
+impl EliteAffix {
+    pub const ALL: [EliteAffix; 6] = [
+        EliteAffix::Fast,
+        EliteAffix::Armoured,
+        EliteAffix::Regenerating,
+        EliteAffix::Explosive,
+        EliteAffix::Splitting,
+        EliteAffix::Vampiric,
+    ];
+
+    pub fn tint(&self) -> Color32 {
+        match self {
+            EliteAffix::Fast => Color32::from_rgb(255, 230, 90),
+            EliteAffix::Armoured => Color32::from_rgb(150, 170, 200),
+            EliteAffix::Regenerating => Color32::from_rgb(120, 255, 120),
+            EliteAffix::Explosive => Color32::from_rgb(255, 130, 40),
+            EliteAffix::Splitting => Color32::from_rgb(200, 120, 255),
+            EliteAffix::Vampiric => Color32::from_rgb(200, 40, 60),
+        }
+    }
+}
+
+// Each extra affix needs another successful roll at the same chance
+pub fn roll_affixes(chance: f32, max_affixes: usize, rng: &mut impl Rng) -> Vec<EliteAffix> {
+    let chance = chance.clamp(0.0, 1.0) as f64;
+    if max_affixes == 0 || !rng.random_bool(chance) {
+        return Vec::new();
+    }
+
+    let mut count = 1;
+    while count < max_affixes.min(EliteAffix::ALL.len()) && rng.random_bool(chance) {
+        count += 1;
+    }
+
+    EliteAffix::ALL.choose_multiple(rng, count).copied().collect()
+}
+
+pub fn apply_affixes(unit: &mut GameObject, affixes: Vec<EliteAffix>) {
+    if affixes.is_empty() {
+        return;
+    }
+
+    unit.health_max *= ELITE_HEALTH_MULTIPLIER;
+    unit.health_current *= ELITE_HEALTH_MULTIPLIER;
+
+    if affixes.contains(&EliteAffix::Fast) {
+        unit.move_speed = (unit.move_speed as f32 * FAST_MOVE_SPEED_MULTIPLIER) as i32;
+    }
+
+    if let Some(loot) = unit.loot.as_mut() {
+        let loot_multiplier = 1.0 + LOOT_MULTIPLIER_PER_AFFIX * affixes.len() as f64;
+        loot.gold *= loot_multiplier;
+        loot.exp *= loot_multiplier;
+    }
+
+    unit.affixes = affixes;
+}
+
+// Averages the affix colours, None for regular units
+pub fn elite_tint(affixes: &[EliteAffix]) -> Option<Color32> {
+    if affixes.is_empty() {
+        return None;
+    }
+
+    let (r, g, b) = affixes.iter().fold((0u32, 0u32, 0u32), |(r, g, b), affix| {
+        let tint = affix.tint();
+        (r + tint.r() as u32, g + tint.g() as u32, b + tint.b() as u32)
+    });
+    let count = affixes.len() as u32;
+
+    Some(Color32::from_rgb((r / count) as u8, (g / count) as u8, (b / count) as u8))
+}
+
+pub fn damage_after_affixes(units: &UnitStore, target_id: u32, damage: f64) -> f64 {
+    if units.affixes[target_id as usize].contains(&EliteAffix::Armoured) {
+        damage * ARMOURED_DAMAGE_TAKEN
+    } else {
+        damage
+    }
+}
+
+pub fn leech(units: &mut UnitStore, source_id: u32, damage_dealt: f64) {
+    if units.is_alive(source_id) && units.affixes[source_id as usize].contains(&EliteAffix::Vampiric) {
+        units.heal(source_id, (damage_dealt * VAMPIRIC_LEECH) as f32);
+    }
+}
+
+pub fn regenerate(units: &mut UnitStore, delta_time: f32) {
+    let UnitStore { health, affixes, .. } = units;
+
+    health.par_iter_mut().zip(affixes.par_iter()).for_each(|(health, affixes)| {
+        if affixes.contains(&EliteAffix::Regenerating) && health.current > 0.0 {
+            health.current = (health.current + health.max * REGENERATION_RATE * delta_time).min(health.max);
+        }
+    });
+}
+
+// Published alongside UnitDied when an elite is removed
+pub fn death_events(unit: &GameObject, position: Pos2FixedPoint) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+
+    if unit.affixes.contains(&EliteAffix::Explosive) {
+        events.push(GameEvent::Explosion {
+            position,
+            radius: EXPLOSION_RADIUS,
+            damage: EXPLOSION_DAMAGE,
+        });
+    }
+
+    if unit.affixes.contains(&EliteAffix::Splitting) {
+        events.push(GameEvent::UnitSplit {
+            position,
+            units: (0..SPLIT_COUNT).map(|_| split_child(unit)).collect(),
+        });
+    }
+
+    events
+}
+
+fn split_child(unit: &GameObject) -> GameObject {
+    let mut child = unit.clone();
+
+    child.id = u32::MAX;
+    child.affixes.retain(|affix| *affix != EliteAffix::Splitting);
+    child.health_max = unit.health_max * SPLIT_HEALTH_FRACTION;
+    child.health_current = child.health_max;
+    child.object_shape = ObjectShape::new(unit.object_shape.width * 3 / 4, unit.object_shape.height * 3 / 4);
+
+    if let Some(animation) = child.animation.as_mut() {
+        animation.size = (animation.size.0 * 3 / 4, animation.size.1 * 3 / 4);
+        animation.last_damage_time = None;
+    }
+
+    if let Some(loot) = child.loot.as_mut() {
+        loot.gold /= SPLIT_COUNT as f64;
+        loot.exp /= SPLIT_COUNT as f64;
+    }
+
+    child
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::objects::damage::{resolve_damage, DamageType, Hit};
+    use crate::game::objects::game_object::test_enemy;
+
+    #[test]
+    fn armoured_elites_take_less_damage_after_every_other_defence() {
+        let mut enemy = test_enemy(100.0, 100.0);
+        enemy.affixes = vec![EliteAffix::Armoured];
+        enemy.armour = 50.0;
+        let mut units = UnitStore::new();
+        units.insert(0, enemy);
+
+        assert_eq!(damage_after_affixes(&units, 0, 10.0), 10.0 * ARMOURED_DAMAGE_TAKEN);
+        assert_eq!(resolve_damage(&units, 0, Hit::new(100.0, DamageType::Physical), &mut rand::rng()).amount, 50.0 * ARMOURED_DAMAGE_TAKEN);
+    }
+}
@@ -1,20 +1,25 @@
-use crate::enums::gamestate::GameState;
 use crate::game::data::game_data::GameData;
+use crate::game::map::terrain::TerrainEffect;
+use crate::game::events::game_event::GameEvent;
 use crate::game::maths::pos_2::{Pos2FixedPoint, INVALID_POSITION};
 use crate::game::objects::animation::Animation;
 use crate::game::objects::attacks::attack_stats::{AttackName, AttackStats};
+use crate::game::objects::behaviour::Behaviour;
+use crate::game::objects::damage::Resistances;
+use crate::game::objects::elite_affix::{death_events, EliteAffix};
 use crate::game::objects::loot::Loot;
 use crate::game::objects::object_shape::ObjectShape;
 use crate::game::objects::object_type::ObjectType;
 use crate::game::objects::on_death::OnDeath;
-use crate::game::objects::unit_defaults::collectable_01_basic_monster;
+use crate::game::objects::status_effect::{StatusEffectType, StatusEffects};
 use crate::game::objects::upgrades::{Upgrade, UpgradeType};
 use crate::helper::lock_helper::acquire_lock_mut;
+use crate::helper::logging::GAME_LOOP;
 use rayon::iter::*;
-use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hash::FxHashMap;
 use std::mem::swap;
 use std::sync::Arc;
-use std::time::Instant;
+use tracing::debug;
 
 #[derive(Clone, Debug)]
 pub struct GameObject {
@@ -30,6 +35,14 @@ pub struct GameObject {
     pub pickup_radius: Option<i32>,
     pub loot: Option<Loot>,
     pub on_death: OnDeath,
+    pub affixes: Vec<EliteAffix>,
+    pub behaviour: Behaviour,
+    pub terrain_immunities: Vec<TerrainEffect>,
+    pub status_effects: StatusEffects,
+    pub status_immunities: Vec<StatusEffectType>,
+    pub resistances: Resistances,
+    // Only reduces physical damage
+    pub armour: f32,
 
     pub parent_unit_id: Option<u32>,
     pub attack_stats: Option<AttackStats>,
@@ -50,89 +63,79 @@ impl GameObject {
             pickup_radius: None,
             loot: None,
             on_death: OnDeath::default(),
+            affixes: Vec::new(),
+            behaviour: Behaviour::default(),
+            terrain_immunities: Vec::new(),
+            status_effects: StatusEffects::new(),
+            status_immunities: Vec::new(),
+            resistances: Resistances::default(),
+            armour: 0.0,
             parent_unit_id: None,
             attack_stats: None,
         }
     }
-    pub fn apply_damage(&mut self, damage: f64) -> bool {
-        if let Some(mut animation) = self.animation.as_mut() {
-            self.health_current -= damage as f32;
-            animation.last_damage_time = Some(Instant::now());
-            self.health_current <= 0.0
-        } else {
-            false
-        }
-    }
 }
 
-pub fn add_units(units: Vec<GameObject>, positions: Vec<Pos2FixedPoint>, game_data: &GameData) {
-    let mut game_units = acquire_lock_mut(&game_data.units, "Failed to acquire game_units lock");
-    let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "Failed to acquire unit_positions lock");
-    let mut empty_indexes = acquire_lock_mut(&game_data.empty_unit_indexes, "Failed to acquire empty_unit_indexes lock");
-    let mut spatial_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "Failed to acquire spatial_grid lock");
+// Units without an animation don't take damage, so test enemies get a placeholder one
+#[cfg(test)]
+pub fn test_enemy(health_max: f32, health_current: f32) -> GameObject {
+    let animation = Animation::new(crate::ui::asset::sprite::sprite_sheet::BABY_GREEN_DRAGON, std::time::Duration::from_secs(1), (10, 10));
+    GameObject::new(ObjectType::Enemy, ObjectShape::new(10, 10), 100, health_max, health_current, Some(animation))
+}
+
+pub fn add_units(units: Vec<GameObject>, positions: Vec<Pos2FixedPoint>, game_data: &GameData) -> Vec<u32> {
+    let mut game_units = acquire_lock_mut(&game_data.units, "game_units");
+    let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "unit_positions");
+    let mut empty_indexes = acquire_lock_mut(&game_data.empty_unit_indexes, "empty_unit_indexes");
+    let mut spatial_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "spatial_hash_grid");
+    let mut unit_ids = Vec::with_capacity(units.len());
 
     for (mut unit, position) in units.into_iter().zip(positions.into_iter()) {
         let unit_id = if let Some(reuse_index) = empty_indexes.pop() {
-            unit.id = reuse_index;
-            game_units[reuse_index as usize] = Some(unit.clone());
             unit_positions[reuse_index as usize] = position;
             reuse_index
         } else {
-            let new_index = game_units.len() as u32;
-            unit.id = new_index;
-            game_units.push(Some(unit.clone()));
             unit_positions.push(position);
-            new_index
+            game_units.len() as u32
         };
 
+        unit.id = unit_id;
+        game_units.insert(unit_id, unit);
+
         spatial_grid.insert_unit(unit_id, position);
+        unit_ids.push(unit_id);
     }
-}
 
-pub fn remove_units(unit_ids: Vec<u32>, game_data: Arc<GameData>) -> Vec<(GameObject, Pos2FixedPoint)> {
-    let mut game_units = acquire_lock_mut(&game_data.units, "Failed to acquire game_units lock");
-    let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "Failed to acquire unit_positions lock");
-    let mut spatial_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "Failed to acquire spatial_grid lock");
-    let mut empty_indexes = acquire_lock_mut(&game_data.empty_unit_indexes, "Failed to acquire empty_unit_indexes lock");
+    unit_ids
+}
 
-    let mut collectables_to_spawn = Vec::new();
-    let mut sounds_to_play = FxHashSet::default();
+pub fn remove_units(unit_ids: Vec<u32>, game_data: Arc<GameData>) {
+    let mut game_units = acquire_lock_mut(&game_data.units, "game_units");
+    let mut unit_positions = acquire_lock_mut(&game_data.unit_positions, "unit_positions");
+    let mut spatial_grid = acquire_lock_mut(&game_data.spatial_hash_grid, "spatial_hash_grid");
+    let mut empty_indexes = acquire_lock_mut(&game_data.empty_unit_indexes, "empty_unit_indexes");
+    let mut events = acquire_lock_mut(&game_data.events, "events");
 
     for &unit_id in &unit_ids {
-        if let Some(unit) = game_units.get_mut(unit_id as usize) {
-            if let Some(unit) = unit.take() {
-                let position = unit_positions[unit_id as usize];
-
-                if let Some(sound) = &unit.on_death.sound {
-                    sounds_to_play.insert(sound.clone());
-                }
-
-                if unit.object_type == ObjectType::Player {
-                    *acquire_lock_mut(&game_data.player_dead, "player_dead") = true;
-                    *acquire_lock_mut(&game_data.game_state, "game_state") = GameState::Dead;
-                }
-
-                // If the unit is an enemy with loot, return a collectable
-                if unit.object_type == ObjectType::Enemy {
-                    if let Some(loot) = unit.loot {
-                        let collectable = collectable_01_basic_monster(Some(loot));
-                        collectables_to_spawn.push((collectable, position));
-                    }
-                }
-
-                // Remove the unit itself
-                spatial_grid.remove_unit(&unit_id, position);
-                unit_positions[unit_id as usize] = Pos2FixedPoint::new(INVALID_POSITION, INVALID_POSITION);
-                empty_indexes.push(unit_id);
-            }
+        if let Some(unit) = game_units.take(unit_id) {
+            let position = unit_positions[unit_id as usize];
+            let elite_events = death_events(&unit, position);
+
+            events.publish(GameEvent::UnitDied {
+                unit_id,
+                object_type: unit.object_type,
+                position,
+                loot: unit.loot,
+                sound: unit.on_death.sound,
+            });
+            events.publish_all(elite_events);
+
+            // Remove the unit itself
+            spatial_grid.remove_unit(&unit_id, position);
+            unit_positions[unit_id as usize] = Pos2FixedPoint::new(INVALID_POSITION, INVALID_POSITION);
+            empty_indexes.push(unit_id);
         }
     }
-
-    for sound in sounds_to_play {
-        // play_sound(Arc::clone(&game_data), &sound.name, sound.volume);
-    }
-
-    collectables_to_spawn
 }
 
 pub fn move_units_batched(unit_positions_updates: &[(u32, Pos2FixedPoint, Pos2FixedPoint)], game_data: &GameData, player_id: Option<u32>) {
@@ -156,13 +159,13 @@ pub fn move_units_batched(unit_positions_updates: &[(u32, Pos2FixedPoint, Pos2Fi
     }
 }
 
-pub fn apply_upgrade(unit: &mut GameObject, upgrade_type: UpgradeType) {
-    if let Some(existing_upgrade) = unit.upgrades.iter_mut().find(|u| u.upgrade_type == upgrade_type) {
+pub fn apply_upgrade(unit: &mut GameObject, upgrade_type: UpgradeType, attack: Option<AttackName>) {
+    if let Some(existing_upgrade) = unit.upgrades.iter_mut().find(|u| u.upgrade_type == upgrade_type && u.attack == attack) {
         existing_upgrade.level += 1;
     } else {
-        unit.upgrades.push(Upgrade { upgrade_type, level: 1 });
+        unit.upgrades.push(Upgrade { upgrade_type, level: 1, attack });
     }
 
-    println!("Upgrade Applied: {:?}", unit.upgrades);
+    debug!(target: GAME_LOOP, "Upgrade Applied: {:?}", unit.upgrades);
 }
 
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 pub struct Loot {
     pub gold: f64,
     pub exp: f64,
+    #[serde(default)]
+    pub ruby: f64,
 }
 
 impl Loot {
@@ -11,6 +13,7 @@ impl Loot {
         Self {
             gold: 0.0,
             exp: 0.0,
+            ruby: 0.0,
         }
     }
 }
\ No newline at end of file
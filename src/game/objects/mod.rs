@@ -8,4 +8,10 @@ pub mod loot;
 pub mod unit_defaults;
 pub mod on_death;
 pub mod sound;
-pub mod attacks;
\ No newline at end of file
+pub mod attacks;
+pub mod unit_store;
+pub mod elite_affix;
+pub mod behaviour;
+pub mod stat_modifier;
+pub mod status_effect;
+pub mod damage;
\ No newline at end of file
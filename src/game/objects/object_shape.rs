@@ -15,6 +15,10 @@ impl ObjectShape {
         }
     }
 
+    pub fn scaled(&self, scale: f32) -> Self {
+        Self::new((self.width as f32 * scale) as i32, (self.height as f32 * scale) as i32)
+    }
+
     pub fn bounding_box(&self, position: Pos2FixedPoint) -> (Pos2FixedPoint, Pos2FixedPoint) {
         let half_width = self.width >> 1;
         let half_height = self.height >> 1;
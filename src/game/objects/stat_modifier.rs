@@ -0,0 +1,122 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum AttackStat {
+    Damage,
+    Cooldown,
+    // Scales shape and sprite size
+    Area,
+    Lifetime,
+    Speed,
+    ProjectileCount,
+    BurstCount,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum ModifierKind {
+    // Added to the base value
+    Flat,
+    // Summed with other percentages before scaling, 0.1 is +10%
+    AdditivePercent,
+    // Each one scales the result on its own
+    Multiplicative,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub struct StatModifier {
+    pub stat: AttackStat,
+    pub kind: ModifierKind,
+    pub value: f64,
+}
+
+impl StatModifier {
+    pub fn new(stat: AttackStat, kind: ModifierKind, value: f64) -> Self {
+        Self { stat, kind, value }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct StatTotals {
+    flat: f64,
+    additive_percent: f64,
+    multiplier: f64,
+}
+
+impl Default for StatTotals {
+    fn default() -> Self {
+        Self { flat: 0.0, additive_percent: 0.0, multiplier: 1.0 }
+    }
+}
+
+// Stacks as (base + flat) * (1 + additive percents) * multipliers. Additive percents can't take a stat below zero.
+#[derive(Clone, Default, Debug)]
+pub struct StatModifiers {
+    totals: FxHashMap<AttackStat, StatTotals>,
+}
+
+impl StatModifiers {
+    pub fn add(&mut self, modifier: StatModifier) {
+        let totals = self.totals.entry(modifier.stat).or_default();
+        match modifier.kind {
+            ModifierKind::Flat => totals.flat += modifier.value,
+            ModifierKind::AdditivePercent => totals.additive_percent += modifier.value,
+            ModifierKind::Multiplicative => totals.multiplier *= modifier.value,
+        }
+    }
+
+    pub fn apply(&self, stat: AttackStat, base: f64) -> f64 {
+        let Some(totals) = self.totals.get(&stat) else { return base; };
+        (base + totals.flat) * (1.0 + totals.additive_percent).max(0.0) * totals.multiplier
+    }
+}
+
+impl FromIterator<StatModifier> for StatModifiers {
+    fn from_iter<I: IntoIterator<Item = StatModifier>>(modifiers: I) -> Self {
+        let mut stat_modifiers = Self::default();
+        for modifier in modifiers {
+            stat_modifiers.add(modifier);
+        }
+        stat_modifiers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifiers(list: &[(ModifierKind, f64)]) -> StatModifiers {
+        list.iter().map(|&(kind, value)| StatModifier::new(AttackStat::Damage, kind, value)).collect()
+    }
+
+    #[test]
+    fn unmodified_stats_keep_their_base() {
+        let stat_modifiers = modifiers(&[(ModifierKind::Flat, 5.0)]);
+        assert_eq!(stat_modifiers.apply(AttackStat::Cooldown, 2.0), 2.0);
+    }
+
+    #[test]
+    fn flat_is_added_before_percentages_and_multipliers() {
+        let stat_modifiers = modifiers(&[(ModifierKind::Multiplicative, 2.0), (ModifierKind::AdditivePercent, 0.5), (ModifierKind::Flat, 10.0)]);
+        // (10 + 10) * 1.5 * 2
+        assert_eq!(stat_modifiers.apply(AttackStat::Damage, 10.0), 60.0);
+    }
+
+    #[test]
+    fn additive_percents_sum_before_scaling() {
+        let stat_modifiers = modifiers(&[(ModifierKind::AdditivePercent, 0.25), (ModifierKind::AdditivePercent, 0.25)]);
+        assert_eq!(stat_modifiers.apply(AttackStat::Damage, 100.0), 150.0);
+    }
+
+    #[test]
+    fn negative_additive_percents_clamp_at_zero() {
+        let stat_modifiers = modifiers(&[(ModifierKind::AdditivePercent, -0.75), (ModifierKind::AdditivePercent, -0.75), (ModifierKind::Flat, 5.0)]);
+        assert_eq!(stat_modifiers.apply(AttackStat::Damage, 100.0), 0.0);
+    }
+
+    #[test]
+    fn multiplicative_sources_compound() {
+        let stat_modifiers = modifiers(&[(ModifierKind::Multiplicative, 2.0), (ModifierKind::Multiplicative, 1.5), (ModifierKind::Multiplicative, 0.5)]);
+        assert_eq!(stat_modifiers.apply(AttackStat::Damage, 10.0), 15.0);
+    }
+}
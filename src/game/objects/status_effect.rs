@@ -0,0 +1,301 @@
+use crate::game::objects::damage::{resolve_damage, DamageType, Hit};
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::unit_store::UnitStore;
+use egui::Color32;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+// Burn and poison deal their damage in steps rather than every frame
+const TICK_INTERVAL: f32 = 0.5;
+const MAX_POISON_STACKS: usize = 5;
+const FREEZE_ATTACK_SPEED: f32 = 0.5;
+// Slowed units lose this fraction of the slow from their attack speed as well
+const SLOW_ATTACK_SPEED_FRACTION: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum StatusEffectType {
+    Burn,
+    Poison,
+    Freeze,
+    Slow,
+    Stun,
+}
+
+impl StatusEffectType {
+    // Also the colour of the damage numbers burn and poison ticks show
+    pub fn tint(&self) -> Color32 {
+        match self {
+            StatusEffectType::Burn => Color32::from_rgb(255, 140, 40),
+            StatusEffectType::Poison => Color32::from_rgb(120, 220, 60),
+            StatusEffectType::Freeze => Color32::from_rgb(140, 210, 255),
+            StatusEffectType::Slow => Color32::from_rgb(170, 150, 255),
+            StatusEffectType::Stun => Color32::from_rgb(255, 240, 120),
+        }
+    }
+
+    // Only burn and poison deal damage
+    fn damage_type(&self) -> Option<DamageType> {
+        match self {
+            StatusEffectType::Burn => Some(DamageType::Fire),
+            StatusEffectType::Poison => Some(DamageType::Physical),
+            StatusEffectType::Freeze | StatusEffectType::Slow | StatusEffectType::Stun => None,
+        }
+    }
+}
+
+// What an attack applies on hit. Magnitude is damage per second for burn and poison,
+// the fraction of speed taken away for slow, and unused for freeze and stun.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub struct StatusEffect {
+    pub effect_type: StatusEffectType,
+    pub duration: f32,
+    pub magnitude: f32,
+}
+
+impl StatusEffect {
+    pub fn new(effect_type: StatusEffectType, duration: f32, magnitude: f32) -> Self {
+        Self { effect_type, duration, magnitude }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ActiveStatusEffect {
+    pub effect_type: StatusEffectType,
+    pub remaining: f32,
+    pub magnitude: f32,
+    tick_timer: f32,
+}
+
+pub type StatusEffects = SmallVec<[ActiveStatusEffect; 2]>;
+
+pub struct StatusTick {
+    pub unit_id: u32,
+    pub effect_type: StatusEffectType,
+    pub amount: f32,
+    pub killed: bool,
+}
+
+// Poison stacks up to MAX_POISON_STACKS with each stack on its own timer, replacing the stack closest to running out once full.
+// Every other effect keeps one instance, refreshed to the longer duration and the stronger magnitude.
+pub fn add_status_effect(effects: &mut StatusEffects, immunities: &[StatusEffectType], effect: StatusEffect) -> bool {
+    if immunities.contains(&effect.effect_type) || effect.duration <= 0.0 {
+        return false;
+    }
+
+    let added = ActiveStatusEffect {
+        effect_type: effect.effect_type,
+        remaining: effect.duration,
+        magnitude: effect.magnitude,
+        tick_timer: 0.0,
+    };
+
+    if effect.effect_type == StatusEffectType::Poison {
+        if effects.iter().filter(|active| active.effect_type == StatusEffectType::Poison).count() < MAX_POISON_STACKS {
+            effects.push(added);
+        } else if let Some(oldest) = effects.iter_mut().filter(|active| active.effect_type == StatusEffectType::Poison).min_by(|a, b| a.remaining.total_cmp(&b.remaining)) {
+            *oldest = added;
+        }
+    } else if let Some(existing) = effects.iter_mut().find(|active| active.effect_type == effect.effect_type) {
+        existing.remaining = existing.remaining.max(effect.duration);
+        existing.magnitude = existing.magnitude.max(effect.magnitude);
+    } else {
+        effects.push(added);
+    }
+
+    true
+}
+
+// Freeze and stun stop movement outright, otherwise the strongest slow applies
+pub fn movement_multiplier(effects: &[ActiveStatusEffect]) -> f32 {
+    effects.iter().fold(1.0, |multiplier, effect| match effect.effect_type {
+        StatusEffectType::Freeze | StatusEffectType::Stun => 0.0,
+        StatusEffectType::Slow => multiplier.min(1.0 - effect.magnitude.clamp(0.0, 1.0)),
+        _ => multiplier,
+    })
+}
+
+// How fast attack cooldowns count down, 0 while stunned
+pub fn attack_speed_multiplier(effects: &[ActiveStatusEffect]) -> f32 {
+    effects.iter().fold(1.0, |multiplier, effect| match effect.effect_type {
+        StatusEffectType::Stun => 0.0,
+        StatusEffectType::Freeze => multiplier.min(FREEZE_ATTACK_SPEED),
+        StatusEffectType::Slow => multiplier.min(1.0 - effect.magnitude.clamp(0.0, 1.0) * SLOW_ATTACK_SPEED_FRACTION),
+        _ => multiplier,
+    })
+}
+
+pub fn can_attack(effects: &[ActiveStatusEffect]) -> bool {
+    !effects.iter().any(|effect| effect.effect_type == StatusEffectType::Stun)
+}
+
+// The most disabling effect shows, so a stunned unit doesn't just look burnt
+pub fn status_tint(effects: &[ActiveStatusEffect]) -> Option<Color32> {
+    [StatusEffectType::Stun, StatusEffectType::Freeze, StatusEffectType::Burn, StatusEffectType::Poison, StatusEffectType::Slow]
+        .into_iter()
+        .find(|effect_type| effects.iter().any(|effect| effect.effect_type == *effect_type))
+        .map(|effect_type| effect_type.tint())
+}
+
+// Counts every effect down and deals burn and poison damage as it comes due, one tick per unit and effect type.
+// Ticks go through resolve_damage like any other hit, so resistances, armour and elite affixes apply.
+pub fn tick_status_effects(units: &mut UnitStore, delta_time: f32) -> Vec<StatusTick> {
+    let UnitStore { object_types, health, status_effects, .. } = units;
+
+    let mut ticks: Vec<StatusTick> = health
+        .par_iter()
+        .zip(status_effects.par_iter_mut())
+        .zip(object_types.par_iter())
+        .enumerate()
+        .flat_map_iter(|(index, ((health, effects), object_type))| {
+            let mut ticks: SmallVec<[StatusTick; 2]> = SmallVec::new();
+            if effects.is_empty() || !matches!(object_type, Some(ObjectType::Player) | Some(ObjectType::Enemy)) || health.current <= 0.0 {
+                return ticks;
+            }
+
+            for effect in effects.iter_mut() {
+                effect.remaining -= delta_time;
+                if effect.effect_type.damage_type().is_none() {
+                    continue;
+                }
+
+                effect.tick_timer += delta_time;
+                while effect.tick_timer >= TICK_INTERVAL {
+                    effect.tick_timer -= TICK_INTERVAL;
+                    let amount = effect.magnitude * TICK_INTERVAL;
+                    match ticks.iter_mut().find(|tick| tick.effect_type == effect.effect_type) {
+                        Some(tick) => tick.amount += amount,
+                        None => ticks.push(StatusTick { unit_id: index as u32, effect_type: effect.effect_type, amount, killed: false }),
+                    }
+                }
+            }
+            effects.retain(|effect| effect.remaining > 0.0);
+
+            ticks
+        })
+        .collect();
+
+    let mut rng = rand::rng();
+    for tick in ticks.iter_mut() {
+        let Some(damage_type) = tick.effect_type.damage_type() else { continue; };
+        tick.amount = resolve_damage(units, tick.unit_id, Hit::new(tick.amount as f64, damage_type), &mut rng).amount as f32;
+
+        // Burn and poison can both tick on the same frame, only the first to kill counts
+        let was_alive = units.health[tick.unit_id as usize].current > 0.0;
+        tick.killed = units.apply_damage(tick.unit_id, tick.amount as f64) && was_alive;
+    }
+
+    ticks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::objects::game_object::{test_enemy, GameObject};
+
+    // Damage from one tick of the effect on a fresh copy of the enemy
+    fn tick_damage(effect: StatusEffect, enemy: GameObject) -> f32 {
+        let mut units = UnitStore::new();
+        units.insert(0, enemy);
+        units.apply_status_effect(0, effect);
+
+        let ticks = tick_status_effects(&mut units, TICK_INTERVAL);
+        assert_eq!(units.health[0].current, 100.0 - ticks[0].amount);
+        ticks[0].amount
+    }
+
+    #[test]
+    fn burn_ticks_are_fire_damage() {
+        let burn = StatusEffect::new(StatusEffectType::Burn, 5.0, 10.0);
+        let mut fire_resistant = test_enemy(100.0, 100.0);
+        fire_resistant.resistances.fire = 0.5;
+        let mut armoured = test_enemy(100.0, 100.0);
+        armoured.armour = 50.0;
+
+        assert_eq!(tick_damage(burn, fire_resistant), 2.5);
+        assert_eq!(tick_damage(burn, armoured), 5.0);
+    }
+
+    #[test]
+    fn poison_ticks_are_physical_damage() {
+        let poison = StatusEffect::new(StatusEffectType::Poison, 5.0, 10.0);
+        let mut fire_resistant = test_enemy(100.0, 100.0);
+        fire_resistant.resistances.fire = 0.5;
+        let mut armoured = test_enemy(100.0, 100.0);
+        armoured.armour = 50.0;
+
+        assert_eq!(tick_damage(poison, fire_resistant), 5.0);
+        assert_eq!(tick_damage(poison, armoured), 2.5);
+    }
+
+    fn with_effects(effects: &[StatusEffect]) -> StatusEffects {
+        let mut active = StatusEffects::new();
+        for &effect in effects {
+            assert!(add_status_effect(&mut active, &[], effect));
+        }
+        active
+    }
+
+    #[test]
+    fn poison_stacks_up_to_the_cap_then_replaces_the_stack_closest_to_expiring() {
+        let stacks: Vec<StatusEffect> = (0..MAX_POISON_STACKS).map(|index| StatusEffect::new(StatusEffectType::Poison, 2.0 + index as f32, 1.0)).collect();
+        let mut effects = with_effects(&stacks);
+        assert_eq!(effects.len(), MAX_POISON_STACKS);
+
+        add_status_effect(&mut effects, &[], StatusEffect::new(StatusEffectType::Poison, 10.0, 3.0));
+        let mut remaining: Vec<f32> = effects.iter().map(|effect| effect.remaining).collect();
+        remaining.sort_by(f32::total_cmp);
+        assert_eq!(effects.len(), MAX_POISON_STACKS);
+        assert_eq!(remaining, vec![3.0, 4.0, 5.0, 6.0, 10.0]);
+    }
+
+    #[test]
+    fn other_effects_refresh_to_the_longer_duration_and_stronger_magnitude() {
+        let effects = with_effects(&[
+            StatusEffect::new(StatusEffectType::Slow, 4.0, 0.2),
+            StatusEffect::new(StatusEffectType::Slow, 2.0, 0.5),
+            StatusEffect::new(StatusEffectType::Burn, 1.0, 8.0),
+            StatusEffect::new(StatusEffectType::Burn, 3.0, 5.0),
+        ]);
+
+        let summary: Vec<(StatusEffectType, f32, f32)> = effects.iter().map(|effect| (effect.effect_type, effect.remaining, effect.magnitude)).collect();
+        assert_eq!(summary, vec![(StatusEffectType::Slow, 4.0, 0.5), (StatusEffectType::Burn, 3.0, 8.0)]);
+    }
+
+    #[test]
+    fn immune_units_are_not_affected() {
+        let mut effects = StatusEffects::new();
+        assert!(!add_status_effect(&mut effects, &[StatusEffectType::Freeze], StatusEffect::new(StatusEffectType::Freeze, 2.0, 0.0)));
+        assert!(!add_status_effect(&mut effects, &[], StatusEffect::new(StatusEffectType::Stun, 0.0, 0.0)));
+        assert!(effects.is_empty());
+
+        let mut enemy = test_enemy(100.0, 100.0);
+        enemy.status_immunities = vec![StatusEffectType::Burn];
+        let mut units = UnitStore::new();
+        units.insert(0, enemy);
+        assert!(!units.apply_status_effect(0, StatusEffect::new(StatusEffectType::Burn, 2.0, 10.0)));
+        assert!(units.status_effects[0].is_empty());
+    }
+
+    #[test]
+    fn freeze_slow_and_stun_modify_movement_and_attacks() {
+        let freeze = with_effects(&[StatusEffect::new(StatusEffectType::Freeze, 2.0, 0.0)]);
+        assert_eq!((movement_multiplier(&freeze), attack_speed_multiplier(&freeze), can_attack(&freeze)), (0.0, FREEZE_ATTACK_SPEED, true));
+
+        let slow = with_effects(&[StatusEffect::new(StatusEffectType::Slow, 2.0, 0.4)]);
+        assert_eq!((movement_multiplier(&slow), attack_speed_multiplier(&slow), can_attack(&slow)), (0.6, 1.0 - 0.4 * SLOW_ATTACK_SPEED_FRACTION, true));
+
+        let stun = with_effects(&[StatusEffect::new(StatusEffectType::Stun, 2.0, 0.0)]);
+        assert_eq!((movement_multiplier(&stun), attack_speed_multiplier(&stun), can_attack(&stun)), (0.0, 0.0, false));
+
+        let burn = with_effects(&[StatusEffect::new(StatusEffectType::Burn, 2.0, 10.0)]);
+        assert_eq!((movement_multiplier(&burn), attack_speed_multiplier(&burn), can_attack(&burn)), (1.0, 1.0, true));
+    }
+
+    #[test]
+    fn slows_past_a_full_stop_are_clamped() {
+        let slow = with_effects(&[StatusEffect::new(StatusEffectType::Slow, 2.0, 1.5)]);
+        assert_eq!(movement_multiplier(&slow), 0.0);
+        assert_eq!(attack_speed_multiplier(&slow), 1.0 - SLOW_ATTACK_SPEED_FRACTION);
+    }
+}
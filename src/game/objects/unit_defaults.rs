@@ -1,16 +1,38 @@
+use crate::game::map::terrain::TerrainEffect;
 use crate::game::maths::pos_2::FIXED_POINT_SCALE;
 use crate::game::objects::animation::Animation;
+use crate::game::objects::behaviour::Behaviour;
 use crate::game::objects::attacks::attack_stats::AttackName;
+use crate::game::objects::damage::Resistances;
 use crate::game::objects::game_object::GameObject;
 use crate::game::objects::loot::Loot;
 use crate::game::objects::object_shape::ObjectShape;
 use crate::game::objects::object_type::ObjectType;
 use crate::game::objects::on_death::OnDeath;
 use crate::game::objects::sound::Sound;
-use crate::ui::asset::sprite::sprite_sheet::{ADULT_WHITE_DRAGON, AQUA_DRAKE, TREASURE, YOUNG_RED_DRAGON};
+use crate::game::objects::status_effect::StatusEffectType;
+use crate::ui::asset::sprite::sprite_sheet::{ADULT_GREEN_DRAGON, ADULT_WHITE_DRAGON, AQUA_DRAKE, TREASURE, YOUNG_RED_DRAGON};
 use crate::ui::sound::kira_audio::SOUND_01;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum EnemyName {
+    BabyDragon,
+    AquaDrake,
+    AdultWhiteDragon,
+    ElderDragon,
+}
+
+pub fn create_enemy(enemy_name: EnemyName) -> GameObject {
+    match enemy_name {
+        EnemyName::BabyDragon => create_01_baby_dragon(),
+        EnemyName::AquaDrake => create_02_aqua_drake(),
+        EnemyName::AdultWhiteDragon => create_03_adult_white_dragon(),
+        EnemyName::ElderDragon => create_04_elder_dragon(),
+    }
+}
+
 pub fn create_01_baby_dragon() -> GameObject {
     let animation = Animation::new(YOUNG_RED_DRAGON, Duration::from_secs(2), (25, 25));
 
@@ -24,10 +46,16 @@ pub fn create_01_baby_dragon() -> GameObject {
     );
 
     unit.attack_cooldowns.insert(AttackName::Proximity, 2.0);
+    unit.behaviour = Behaviour::Swarmer {
+        cohesion: 0.3,
+        separation_distance: 30 * FIXED_POINT_SCALE,
+    };
+    unit.resistances = Resistances { fire: 0.5, ..Resistances::default() };
 
     let loot = Loot {
         gold: 1.0,
         exp: 1.0,
+        ruby: 0.0,
     };
 
     // unit.on_death = OnDeath {
@@ -51,9 +79,18 @@ pub fn create_02_aqua_drake() -> GameObject {
         Some(animation)
     );
 
+    unit.attack_cooldowns.insert(AttackName::FireBolt, 2.0);
+    unit.behaviour = Behaviour::Kiter {
+        preferred_distance: 250 * FIXED_POINT_SCALE,
+        tolerance: 50 * FIXED_POINT_SCALE,
+    };
+    unit.terrain_immunities = vec![TerrainEffect::Slow];
+    unit.resistances = Resistances { frost: 0.5, lightning: -0.5, ..Resistances::default() };
+
     let loot = Loot {
         gold: 5.0,
         exp: 10.0,
+        ruby: 0.0,
     };
 
     // unit.on_death = OnDeath {
@@ -77,9 +114,16 @@ pub fn create_03_adult_white_dragon() -> GameObject {
         Some(animation)
     );
 
+    unit.behaviour = Behaviour::Fleer { flee_below: 0.25 };
+    unit.terrain_immunities = vec![TerrainEffect::Slippery];
+    unit.status_immunities = vec![StatusEffectType::Freeze];
+    unit.resistances = Resistances { frost: 0.75, fire: -0.25, ..Resistances::default() };
+    unit.armour = 20.0;
+
     let loot = Loot {
         gold: 25.0,
         exp: 75.0,
+        ruby: 0.0,
     };
 
     // unit.on_death = OnDeath {
@@ -91,6 +135,44 @@ pub fn create_03_adult_white_dragon() -> GameObject {
     unit
 }
 
+// Boss encounters override health, loot and attacks per phase
+pub fn create_04_elder_dragon() -> GameObject {
+    let animation = Animation::new(ADULT_GREEN_DRAGON, Duration::from_secs(2), (120, 120));
+
+    let mut unit = GameObject::new(
+        ObjectType::Enemy,
+        ObjectShape::new(110 * FIXED_POINT_SCALE, 110 * FIXED_POINT_SCALE),
+        20 * FIXED_POINT_SCALE,
+        2500.0,
+        2500.0,
+        Some(animation)
+    );
+
+    unit.attack_cooldowns.insert(AttackName::Proximity, 2.0);
+    unit.behaviour = Behaviour::Charger {
+        trigger_distance: 300 * FIXED_POINT_SCALE,
+        wind_up: 0.8,
+        charge_duration: 0.7,
+        recovery: 1.0,
+        charge_speed_multiplier: 5.0,
+    };
+    // Too big to be bothered by the ground it walks over
+    unit.terrain_immunities = vec![TerrainEffect::Slow, TerrainEffect::Burning, TerrainEffect::Thorns];
+    // Crowd control would trivialise the fight
+    unit.status_immunities = vec![StatusEffectType::Freeze, StatusEffectType::Stun];
+    unit.resistances = Resistances { fire: 0.25, frost: 0.25, lightning: 0.25, ..Resistances::default() };
+    unit.armour = 50.0;
+
+    let loot = Loot {
+        gold: 250.0,
+        exp: 500.0,
+        ruby: 1.0,
+    };
+
+    unit.loot = Some(loot);
+    unit
+}
+
 pub fn collectable_01_basic_monster(loot: Option<Loot>) -> GameObject {
     let mut animation = Animation::new(TREASURE, Duration::from_secs(1), (45, 45));
 
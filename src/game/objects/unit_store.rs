@@ -0,0 +1,280 @@
+use crate::game::map::terrain::TerrainEffect;
+use crate::game::objects::animation::Animation;
+use crate::game::objects::attacks::attack_stats::{AttackName, AttackStats};
+use crate::game::objects::behaviour::{Behaviour, BehaviourState};
+use crate::game::objects::damage::Resistances;
+use crate::game::objects::elite_affix::EliteAffix;
+use crate::game::objects::game_object::GameObject;
+use crate::game::objects::loot::Loot;
+use crate::game::objects::object_shape::ObjectShape;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::on_death::OnDeath;
+use crate::game::objects::status_effect::{add_status_effect, StatusEffect, StatusEffectType, StatusEffects};
+use crate::game::objects::upgrades::Upgrade;
+use smallvec::SmallVec;
+use std::time::Instant;
+
+pub type AttackCooldowns = SmallVec<[(AttackName, f32); 4]>;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+// Component columns for every unit in the adventure, indexed by unit id.
+// Positions live alongside in GameData.unit_positions using the same ids.
+// A slot is empty when its object_type is None.
+#[derive(Clone, Default)]
+pub struct UnitStore {
+    pub object_types: Vec<Option<ObjectType>>,
+    pub shapes: Vec<ObjectShape>,
+    pub move_speeds: Vec<i32>,
+    pub health: Vec<Health>,
+    pub animations: Vec<Option<Animation>>,
+    pub attack_cooldowns: Vec<AttackCooldowns>,
+    pub attack_stats: Vec<Option<AttackStats>>,
+    pub parent_ids: Vec<Option<u32>>,
+    pub upgrades: Vec<Vec<Upgrade>>,
+    pub pickup_radii: Vec<Option<i32>>,
+    pub loot: Vec<Option<Loot>>,
+    pub on_death: Vec<OnDeath>,
+    pub affixes: Vec<Vec<EliteAffix>>,
+    pub behaviours: Vec<Behaviour>,
+    pub behaviour_states: Vec<BehaviourState>,
+    pub terrain_immunities: Vec<Vec<TerrainEffect>>,
+    pub status_effects: Vec<StatusEffects>,
+    pub status_immunities: Vec<Vec<StatusEffectType>>,
+    pub resistances: Vec<Resistances>,
+    pub armour: Vec<f32>,
+    // Last tick's movement in world units per second, carried over on ice
+    pub velocities: Vec<(f32, f32)>,
+}
+
+impl UnitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.object_types.len()
+    }
+
+    pub fn is_alive(&self, id: u32) -> bool {
+        self.object_type(id).is_some()
+    }
+
+    pub fn object_type(&self, id: u32) -> Option<ObjectType> {
+        self.object_types.get(id as usize).copied().flatten()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.object_types
+            .iter()
+            .enumerate()
+            .filter(|(_, object_type)| object_type.is_some())
+            .map(|(id, _)| id as u32)
+    }
+
+    pub fn ids_of_type(&self, object_type: ObjectType) -> impl Iterator<Item = u32> + '_ {
+        self.object_types
+            .iter()
+            .enumerate()
+            .filter(move |(_, slot)| **slot == Some(object_type))
+            .map(|(id, _)| id as u32)
+    }
+
+    pub fn insert(&mut self, id: u32, unit: GameObject) {
+        let index = id as usize;
+        if index == self.len() {
+            self.push_empty();
+        }
+
+        self.object_types[index] = Some(unit.object_type);
+        self.shapes[index] = unit.object_shape;
+        self.move_speeds[index] = unit.move_speed;
+        self.health[index] = Health { current: unit.health_current, max: unit.health_max };
+        self.animations[index] = unit.animation;
+        self.attack_cooldowns[index] = unit.attack_cooldowns.into_iter().collect();
+        self.attack_stats[index] = unit.attack_stats;
+        self.parent_ids[index] = unit.parent_unit_id;
+        self.upgrades[index] = unit.upgrades;
+        self.pickup_radii[index] = unit.pickup_radius;
+        self.loot[index] = unit.loot;
+        self.on_death[index] = unit.on_death;
+        self.affixes[index] = unit.affixes;
+        self.behaviours[index] = unit.behaviour;
+        self.behaviour_states[index] = BehaviourState::default();
+        self.terrain_immunities[index] = unit.terrain_immunities;
+        self.status_effects[index] = unit.status_effects;
+        self.status_immunities[index] = unit.status_immunities;
+        self.resistances[index] = unit.resistances;
+        self.armour[index] = unit.armour;
+        self.velocities[index] = (0.0, 0.0);
+    }
+
+    pub fn take(&mut self, id: u32) -> Option<GameObject> {
+        let index = id as usize;
+        let object_type = self.object_types.get_mut(index)?.take()?;
+        let health = self.health[index];
+
+        Some(GameObject {
+            id,
+            object_type,
+            object_shape: std::mem::replace(&mut self.shapes[index], ObjectShape::new(0, 0)),
+            move_speed: self.move_speeds[index],
+            health_max: health.max,
+            health_current: health.current,
+            animation: self.animations[index].take(),
+            attack_cooldowns: std::mem::take(&mut self.attack_cooldowns[index]).into_iter().collect(),
+            upgrades: std::mem::take(&mut self.upgrades[index]),
+            pickup_radius: self.pickup_radii[index].take(),
+            loot: self.loot[index].take(),
+            on_death: std::mem::replace(&mut self.on_death[index], OnDeath::default()),
+            affixes: std::mem::take(&mut self.affixes[index]),
+            behaviour: self.behaviours[index],
+            terrain_immunities: std::mem::take(&mut self.terrain_immunities[index]),
+            status_effects: std::mem::take(&mut self.status_effects[index]),
+            status_immunities: std::mem::take(&mut self.status_immunities[index]),
+            resistances: self.resistances[index],
+            armour: self.armour[index],
+            parent_unit_id: self.parent_ids[index].take(),
+            attack_stats: self.attack_stats[index].take(),
+        })
+    }
+
+    pub fn apply_damage(&mut self, id: u32, damage: f64) -> bool {
+        let index = id as usize;
+        if let Some(animation) = self.animations[index].as_mut() {
+            self.health[index].current -= damage as f32;
+            animation.last_damage_time = Some(Instant::now());
+            self.health[index].current <= 0.0
+        } else {
+            false
+        }
+    }
+
+    pub fn heal(&mut self, id: u32, amount: f32) {
+        let health = &mut self.health[id as usize];
+        health.current = (health.current + amount).min(health.max);
+    }
+
+    // False when the unit is immune
+    pub fn apply_status_effect(&mut self, id: u32, effect: StatusEffect) -> bool {
+        let index = id as usize;
+        add_status_effect(&mut self.status_effects[index], &self.status_immunities[index], effect)
+    }
+
+    pub fn set_cooldown(&mut self, id: u32, attack_name: AttackName, cooldown: f32) {
+        let cooldowns = &mut self.attack_cooldowns[id as usize];
+        if let Some((_, existing)) = cooldowns.iter_mut().find(|(name, _)| *name == attack_name) {
+            *existing = cooldown;
+        } else {
+            cooldowns.push((attack_name, cooldown));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn push_empty(&mut self) {
+        self.object_types.push(None);
+        self.shapes.push(ObjectShape::new(0, 0));
+        self.move_speeds.push(0);
+        self.health.push(Health::default());
+        self.animations.push(None);
+        self.attack_cooldowns.push(AttackCooldowns::new());
+        self.attack_stats.push(None);
+        self.parent_ids.push(None);
+        self.upgrades.push(Vec::new());
+        self.pickup_radii.push(None);
+        self.loot.push(None);
+        self.on_death.push(OnDeath::default());
+        self.affixes.push(Vec::new());
+        self.behaviours.push(Behaviour::default());
+        self.behaviour_states.push(BehaviourState::default());
+        self.terrain_immunities.push(Vec::new());
+        self.status_effects.push(StatusEffects::new());
+        self.status_immunities.push(Vec::new());
+        self.resistances.push(Resistances::default());
+        self.armour.push(0.0);
+        self.velocities.push((0.0, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+    use std::time::Duration;
+
+    fn enemy(health: f32) -> GameObject {
+        GameObject::new(ObjectType::Enemy, ObjectShape::new(10, 10), 100, health, health, None)
+    }
+
+    #[test]
+    fn take_returns_the_inserted_unit_and_frees_the_slot() {
+        let mut units = UnitStore::new();
+        units.insert(0, enemy(5.0));
+        units.insert(1, enemy(8.0));
+
+        let taken = units.take(0).expect("unit 0 was inserted");
+        assert_eq!(taken.health_max, 5.0);
+        assert!(!units.is_alive(0));
+        assert_eq!(units.ids().collect::<Vec<_>>(), vec![1]);
+
+        units.insert(0, enemy(3.0));
+        assert_eq!(units.health[0].current, 3.0);
+    }
+
+    fn best_of<T>(passes: u32, mut run: impl FnMut() -> T) -> Duration {
+        (0..passes)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(run());
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or_default()
+    }
+
+    // A pass over Vec<GameObject> against the same pass over the columns.
+    // Timings print with: cargo test --release unit_iteration_timing -- --nocapture
+    #[test]
+    fn unit_iteration_timing() {
+        const UNITS: usize = 200_000;
+        const PASSES: u32 = 10;
+
+        let mut objects: Vec<GameObject> = (0..UNITS).map(|index| enemy(1.0 + (index % 50) as f32)).collect();
+        let mut units = UnitStore::new();
+        for (index, unit) in objects.iter().cloned().enumerate() {
+            units.insert(index as u32, unit);
+        }
+
+        let objects_read = best_of(PASSES, || {
+            objects.iter().filter(|unit| unit.object_type == ObjectType::Enemy).map(|unit| unit.health_current).sum::<f32>()
+        });
+        let columns_read = best_of(PASSES, || {
+            units.health.iter().zip(&units.object_types).filter(|(_, object_type)| **object_type == Some(ObjectType::Enemy)).map(|(health, _)| health.current).sum::<f32>()
+        });
+
+        let objects_write = best_of(PASSES, || {
+            for unit in objects.iter_mut() {
+                unit.health_current = (unit.health_current + 0.01).min(unit.health_max);
+            }
+        });
+        let columns_write = best_of(PASSES, || {
+            for health in units.health.iter_mut() {
+                health.current = (health.current + 0.01).min(health.max);
+            }
+        });
+
+        println!("{} units, best of {} passes", UNITS, PASSES);
+        println!("sum health    Vec<GameObject> {:?}, UnitStore {:?}", objects_read, columns_read);
+        println!("regen health  Vec<GameObject> {:?}, UnitStore {:?}", objects_write, columns_write);
+
+        assert!(columns_read < objects_read, "column read {:?} vs objects {:?}", columns_read, objects_read);
+        assert!(columns_write < objects_write, "column write {:?} vs objects {:?}", columns_write, objects_write);
+    }
+}
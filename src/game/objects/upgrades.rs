@@ -1,4 +1,8 @@
+use crate::game::maths::pos_2::FIXED_POINT_SCALE;
+use crate::game::objects::attacks::attack_stats::AttackName;
+use crate::game::objects::stat_modifier::{AttackStat, ModifierKind, StatModifier};
 use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
 pub enum UpgradeType {
@@ -7,10 +11,67 @@ pub enum UpgradeType {
     IncreaseAOE,
     IncreaseRange,
     IncreaseSpeed,
+    ExtraProjectiles,
+    ExtraBurst,
+}
+
+impl UpgradeType {
+    pub const ALL: [UpgradeType; 7] = [
+        UpgradeType::IncreaseDamage,
+        UpgradeType::DecreaseCooldown,
+        UpgradeType::IncreaseAOE,
+        UpgradeType::IncreaseRange,
+        UpgradeType::IncreaseSpeed,
+        UpgradeType::ExtraProjectiles,
+        UpgradeType::ExtraBurst,
+    ];
+
+    // Level ups stop offering an upgrade once it reaches this level
+    pub fn max_level(&self) -> u32 {
+        match self {
+            UpgradeType::IncreaseDamage => 10,
+            UpgradeType::DecreaseCooldown => 10,
+            UpgradeType::IncreaseAOE => 5,
+            UpgradeType::IncreaseRange => 5,
+            UpgradeType::IncreaseSpeed => 10,
+            UpgradeType::ExtraProjectiles => 4,
+            UpgradeType::ExtraBurst => 3,
+        }
+    }
+
+    // Only ever taken for a single attack rather than all of them
+    pub fn is_attack_specific(&self) -> bool {
+        matches!(self, UpgradeType::ExtraProjectiles | UpgradeType::ExtraBurst)
+    }
+
+    pub fn modifiers(&self, level: u32) -> SmallVec<[StatModifier; 2]> {
+        let level = level as f64;
+        match self {
+            UpgradeType::IncreaseDamage => smallvec![StatModifier::new(AttackStat::Damage, ModifierKind::Flat, 10.0 * level)],
+            UpgradeType::DecreaseCooldown => smallvec![StatModifier::new(AttackStat::Cooldown, ModifierKind::AdditivePercent, -0.05 * level)],
+            UpgradeType::IncreaseAOE => smallvec![StatModifier::new(AttackStat::Area, ModifierKind::AdditivePercent, 0.15 * level)],
+            UpgradeType::IncreaseRange => smallvec![
+                StatModifier::new(AttackStat::Lifetime, ModifierKind::AdditivePercent, 0.1 * level),
+                StatModifier::new(AttackStat::Speed, ModifierKind::AdditivePercent, 0.05 * level),
+            ],
+            UpgradeType::IncreaseSpeed => smallvec![StatModifier::new(AttackStat::Speed, ModifierKind::Flat, FIXED_POINT_SCALE as f64 * level)],
+            UpgradeType::ExtraProjectiles => smallvec![StatModifier::new(AttackStat::ProjectileCount, ModifierKind::Flat, level)],
+            UpgradeType::ExtraBurst => smallvec![StatModifier::new(AttackStat::BurstCount, ModifierKind::Flat, level)],
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct Upgrade {
     pub upgrade_type: UpgradeType,
     pub level: u32,
+    // None applies to every attack the unit has
+    #[serde(default)]
+    pub attack: Option<AttackName>,
+}
+
+impl Upgrade {
+    pub fn applies_to(&self, attack_name: AttackName) -> bool {
+        self.attack.is_none_or(|attack| attack == attack_name)
+    }
 }
@@ -8,7 +8,12 @@ pub fn collect_loot(loot: Vec<Loot>, game_data: Arc<GameData>) {
 
     let total_gold: f64 = loot.iter().map(|l| l.gold).sum();
     let total_exp: f64 = loot.iter().map(|l| l.exp).sum();
+    let total_ruby: f64 = loot.iter().map(|l| l.ruby).sum();
 
-    *resource_amounts.gold.get_or_insert(total_gold) += total_gold;
-    *resource_amounts.exp.get_or_insert(total_exp) += total_exp;
+    *resource_amounts.gold.get_or_insert(0.0) += total_gold;
+    *resource_amounts.exp.get_or_insert(0.0) += total_exp;
+
+    if total_ruby > 0.0 {
+        *resource_amounts.ruby.get_or_insert(0.0) += total_ruby;
+    }
 }
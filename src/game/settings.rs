@@ -1,3 +1,5 @@
+use crate::enums::loglevel::LogLevel;
+use crate::enums::minimapcorner::MinimapCorner;
 use crate::enums::numberformatmode::NumberFormatMode;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +10,20 @@ pub struct Settings {
     pub window_height: f32,
     pub vsync: bool,
     pub autosave_interval: u64,
+    #[serde(default = "LogLevel::default")]
+    pub log_level: LogLevel,
+    #[serde(default)]
+    pub show_flow_field: bool,
+    #[serde(default = "default_show_minimap")]
+    pub show_minimap: bool,
+    // Side length in points
+    #[serde(default = "default_minimap_size")]
+    pub minimap_size: f32,
+    #[serde(default = "MinimapCorner::default")]
+    pub minimap_corner: MinimapCorner,
+    // 1.0 fits the whole map, higher zooms in around the player
+    #[serde(default = "default_minimap_zoom")]
+    pub minimap_zoom: f32,
 }
 
 impl Default for Settings {
@@ -18,6 +34,24 @@ impl Default for Settings {
             window_height: 720.0,
             vsync: true,
             autosave_interval: 5,
+            log_level: LogLevel::default(),
+            show_flow_field: false,
+            show_minimap: default_show_minimap(),
+            minimap_size: default_minimap_size(),
+            minimap_corner: MinimapCorner::default(),
+            minimap_zoom: default_minimap_zoom(),
         }
     }
 }
+
+fn default_show_minimap() -> bool {
+    true
+}
+
+fn default_minimap_size() -> f32 {
+    200.0
+}
+
+fn default_minimap_zoom() -> f32 {
+    1.0
+}
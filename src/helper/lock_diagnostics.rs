@@ -0,0 +1,312 @@
+use crate::helper::logging::LOCK;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Reverse;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, warn};
+
+// Debug-build bookkeeping behind acquire_lock/acquire_lock_mut. Locks are identified by
+// address, so a lock passed around under different names is still tracked as one.
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(500);
+const STALL_THRESHOLD: Duration = Duration::from_secs(2);
+const TIMING_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy)]
+struct HeldLock {
+    lock_id: usize,
+    write: bool,
+    acquired_at: Instant,
+}
+
+struct WaitingLock {
+    lock_id: usize,
+    write: bool,
+    since: Instant,
+    reported: bool,
+}
+
+// Remembers who first took `from` then `to`, so an inversion can name both threads,
+// and whether either lock has been write locked along that path
+struct OrderEdge {
+    thread_name: String,
+    from_write: bool,
+    to_write: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct LockTiming {
+    pub name: String,
+    pub acquisitions: u64,
+    pub contended: u64,
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+    pub total_hold: Duration,
+    pub max_hold: Duration,
+}
+
+#[derive(Default)]
+struct LockDiagnostics {
+    timings: FxHashMap<usize, LockTiming>,
+    held: FxHashMap<ThreadId, Vec<HeldLock>>,
+    waiting: FxHashMap<ThreadId, WaitingLock>,
+    thread_names: FxHashMap<ThreadId, String>,
+    order: FxHashMap<(usize, usize), OrderEdge>,
+    reported_inversions: FxHashSet<(usize, usize)>,
+}
+
+impl LockDiagnostics {
+    fn name(&self, lock_id: usize) -> &str {
+        self.timings.get(&lock_id).map(|timing| timing.name.as_str()).unwrap_or("<unnamed>")
+    }
+
+    fn holders(&self, lock_id: usize) -> Vec<String> {
+        self.held
+            .iter()
+            .filter(|(_, held)| held.iter().any(|lock| lock.lock_id == lock_id))
+            .map(|(thread_id, held)| {
+                let lock = held.iter().find(|lock| lock.lock_id == lock_id).unwrap();
+                format!(
+                    "{} ({} for {:?})",
+                    self.thread_names.get(thread_id).map(String::as_str).unwrap_or("<unknown>"),
+                    if lock.write { "write" } else { "read" },
+                    lock.acquired_at.elapsed(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn diagnostics() -> MutexGuard<'static, LockDiagnostics> {
+    static DIAGNOSTICS: OnceLock<Mutex<LockDiagnostics>> = OnceLock::new();
+
+    DIAGNOSTICS
+        .get_or_init(|| {
+            thread::Builder::new()
+                .name("lock_watchdog".to_string())
+                .spawn(run_watchdog)
+                .expect("Failed to start lock watchdog");
+            Mutex::new(LockDiagnostics::default())
+        })
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+fn current_thread_name() -> String {
+    let thread = thread::current();
+    thread.name().map(str::to_string).unwrap_or_else(|| format!("{:?}", thread.id()))
+}
+
+pub fn lock_id<T>(lock: &T) -> usize {
+    lock as *const T as usize
+}
+
+pub fn begin_wait(lock_id: usize, name: &str, write: bool) {
+    let thread_id = thread::current().id();
+    let mut diagnostics = diagnostics();
+
+    diagnostics.thread_names.entry(thread_id).or_insert_with(current_thread_name);
+    let timing = diagnostics.timings.entry(lock_id).or_default();
+    if timing.name.is_empty() {
+        timing.name = name.to_string();
+    }
+
+    check_lock_order(&mut diagnostics, thread_id, lock_id, write);
+    diagnostics.waiting.insert(thread_id, WaitingLock { lock_id, write, since: Instant::now(), reported: false });
+}
+
+pub fn end_wait(lock_id: usize, write: bool, wait: Duration, contended: bool) {
+    let thread_id = thread::current().id();
+    let mut diagnostics = diagnostics();
+
+    if let Some(waiting) = diagnostics.waiting.remove(&thread_id) && waiting.reported {
+        warn!(
+            target: LOCK,
+            "{} finally acquired {} after {:?}",
+            current_thread_name(),
+            diagnostics.name(lock_id),
+            wait,
+        );
+    }
+
+    let timing = diagnostics.timings.entry(lock_id).or_default();
+    timing.acquisitions += 1;
+    if contended {
+        timing.contended += 1;
+        timing.total_wait += wait;
+        timing.max_wait = timing.max_wait.max(wait);
+    }
+
+    diagnostics.held.entry(thread_id).or_default().push(HeldLock { lock_id, write, acquired_at: Instant::now() });
+}
+
+pub fn release(lock_id: usize) {
+    let thread_id = thread::current().id();
+    let mut diagnostics = diagnostics();
+
+    let Some(held) = diagnostics.held.get_mut(&thread_id) else { return; };
+    let Some(index) = held.iter().rposition(|lock| lock.lock_id == lock_id) else { return; };
+    let hold = held.remove(index).acquired_at.elapsed();
+
+    if let Some(timing) = diagnostics.timings.get_mut(&lock_id) {
+        timing.total_hold += hold;
+        timing.max_hold = timing.max_hold.max(hold);
+    }
+}
+
+// Every lock already held by this thread must come before the new one. If another thread
+// has recorded the opposite order, the two can deadlock against each other, but only when
+// both locks would actually block: readers never block each other, so each lock in the
+// pair needs a write on at least one side.
+fn check_lock_order(diagnostics: &mut LockDiagnostics, thread_id: ThreadId, lock_id: usize, write: bool) {
+    let held: Vec<(usize, bool)> = diagnostics
+        .held
+        .get(&thread_id)
+        .map(|held| held.iter().filter(|lock| lock.lock_id != lock_id).map(|lock| (lock.lock_id, lock.write)).collect())
+        .unwrap_or_default();
+
+    let thread_name = diagnostics.thread_names.get(&thread_id).cloned().unwrap_or_default();
+
+    for (held_id, held_write) in held {
+        let inversion = diagnostics
+            .order
+            .get(&(lock_id, held_id))
+            .filter(|edge| (held_write || edge.to_write) && (write || edge.from_write))
+            .map(|edge| edge.thread_name.clone());
+
+        if let Some(other_thread) = inversion {
+            let pair = (held_id.min(lock_id), held_id.max(lock_id));
+            if diagnostics.reported_inversions.insert(pair) {
+                warn!(
+                    target: LOCK,
+                    "Lock order inversion: {} takes {} then {}, but {} took {} then {}. Current holders of {}: {:?}",
+                    thread_name,
+                    diagnostics.name(held_id),
+                    diagnostics.name(lock_id),
+                    other_thread,
+                    diagnostics.name(lock_id),
+                    diagnostics.name(held_id),
+                    diagnostics.name(lock_id),
+                    diagnostics.holders(lock_id),
+                );
+            }
+        }
+
+        let edge = diagnostics.order.entry((held_id, lock_id)).or_insert_with(|| OrderEdge {
+            thread_name: thread_name.clone(),
+            from_write: false,
+            to_write: false,
+        });
+        edge.from_write |= held_write;
+        edge.to_write |= write;
+    }
+}
+
+pub fn lock_timings() -> Vec<LockTiming> {
+    let mut timings: Vec<LockTiming> = diagnostics().timings.values().cloned().collect();
+    timings.sort_by_key(|timing| Reverse(timing.total_wait));
+    timings
+}
+
+fn report_stalls(diagnostics: &mut LockDiagnostics) {
+    let mut stalled = Vec::new();
+
+    for (thread_id, waiting) in diagnostics.waiting.iter_mut() {
+        if !waiting.reported && waiting.since.elapsed() >= STALL_THRESHOLD {
+            waiting.reported = true;
+            stalled.push((*thread_id, waiting.lock_id, waiting.write, waiting.since.elapsed()));
+        }
+    }
+
+    for (thread_id, lock_id, write, waited) in stalled {
+        let held_by_waiter: Vec<&str> = diagnostics
+            .held
+            .get(&thread_id)
+            .map(|held| held.iter().map(|lock| diagnostics.name(lock.lock_id)).collect())
+            .unwrap_or_default();
+
+        error!(
+            target: LOCK,
+            "{} has waited {:?} for {} lock on {} while holding {:?}. Held by: {:?}",
+            diagnostics.thread_names.get(&thread_id).map(String::as_str).unwrap_or("<unknown>"),
+            waited,
+            if write { "write" } else { "read" },
+            diagnostics.name(lock_id),
+            held_by_waiter,
+            diagnostics.holders(lock_id),
+        );
+    }
+}
+
+fn report_timings() {
+    for timing in lock_timings().iter().filter(|timing| timing.contended > 0).take(5) {
+        debug!(
+            target: LOCK,
+            "{}: {} acquisitions, {} contended, wait total {:?} max {:?}, hold total {:?} max {:?}",
+            timing.name,
+            timing.acquisitions,
+            timing.contended,
+            timing.total_wait,
+            timing.max_wait,
+            timing.total_hold,
+            timing.max_hold,
+        );
+    }
+}
+
+fn run_watchdog() {
+    let mut last_timing_report = Instant::now();
+
+    loop {
+        thread::sleep(WATCHDOG_INTERVAL);
+        report_stalls(&mut diagnostics());
+
+        if last_timing_report.elapsed() >= TIMING_REPORT_INTERVAL {
+            report_timings();
+            last_timing_report = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST: usize = 1;
+    const SECOND: usize = 2;
+
+    // Takes `lock_id` while holding `held_id`, as checked in begin_wait
+    fn take(diagnostics: &mut LockDiagnostics, held_id: usize, held_write: bool, lock_id: usize, write: bool) {
+        let thread_id = thread::current().id();
+        diagnostics.held.insert(thread_id, vec![HeldLock { lock_id: held_id, write: held_write, acquired_at: Instant::now() }]);
+        check_lock_order(diagnostics, thread_id, lock_id, write);
+    }
+
+    #[test]
+    fn read_only_inversions_are_not_reported() {
+        let mut diagnostics = LockDiagnostics::default();
+        take(&mut diagnostics, FIRST, false, SECOND, false);
+        take(&mut diagnostics, SECOND, false, FIRST, false);
+
+        assert!(diagnostics.reported_inversions.is_empty());
+    }
+
+    #[test]
+    fn inversions_that_can_block_on_both_locks_are_reported() {
+        let mut diagnostics = LockDiagnostics::default();
+        take(&mut diagnostics, FIRST, true, SECOND, false);
+        take(&mut diagnostics, SECOND, true, FIRST, false);
+
+        assert!(diagnostics.reported_inversions.contains(&(FIRST, SECOND)));
+    }
+
+    #[test]
+    fn a_write_on_only_one_lock_is_not_reported() {
+        let mut diagnostics = LockDiagnostics::default();
+        take(&mut diagnostics, FIRST, true, SECOND, false);
+        take(&mut diagnostics, SECOND, false, FIRST, false);
+
+        assert!(diagnostics.reported_inversions.is_empty());
+    }
+}
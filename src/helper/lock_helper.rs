@@ -1,33 +1,96 @@
+use std::ops::{Deref, DerefMut};
 use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use std::thread;
-use std::time::{Duration, Instant};
 
-pub fn acquire_lock<'a, T>(lock: &'a RwLock<T>, name: &str) -> RwLockReadGuard<'a, T> {
-    let timeout = Duration::from_secs(2);
+#[cfg(debug_assertions)]
+use crate::helper::lock_diagnostics;
+#[cfg(debug_assertions)]
+use std::time::Instant;
+
+// Blocks until the lock is available. Debug builds also record acquisition order, wait and
+// hold times per lock, and a watchdog reports any thread stuck waiting.
+#[cfg(debug_assertions)]
+pub fn acquire_lock<'a, T>(lock: &'a RwLock<T>, name: &str) -> LockReadGuard<'a, T> {
+    let lock_id = lock_diagnostics::lock_id(lock);
     let start = Instant::now();
+    lock_diagnostics::begin_wait(lock_id, name, false);
 
-    while start.elapsed() < timeout {
-        if let Ok(guard) = lock.try_read() {
-            return guard;
-        }
-        thread::sleep(Duration::from_millis(1));
-    }
+    let (guard, contended) = match lock.try_read() {
+        Ok(guard) => (guard, false),
+        Err(_) => (lock.read().unwrap_or_else(|_| panic!("Read lock for {} is poisoned", name)), true),
+    };
 
-    eprintln!("Failed to acquire read lock for {} within 2 seconds", name);
-    panic!("Lock acquisition timed out");
+    lock_diagnostics::end_wait(lock_id, false, start.elapsed(), contended);
+    LockReadGuard { guard, _held: HeldLock { lock_id } }
 }
 
-pub fn acquire_lock_mut<'a, T>(lock: &'a RwLock<T>, name: &str) -> RwLockWriteGuard<'a, T> {
-    let timeout = Duration::from_secs(2);
+#[cfg(debug_assertions)]
+pub fn acquire_lock_mut<'a, T>(lock: &'a RwLock<T>, name: &str) -> LockWriteGuard<'a, T> {
+    let lock_id = lock_diagnostics::lock_id(lock);
     let start = Instant::now();
+    lock_diagnostics::begin_wait(lock_id, name, true);
+
+    let (guard, contended) = match lock.try_write() {
+        Ok(guard) => (guard, false),
+        Err(_) => (lock.write().unwrap_or_else(|_| panic!("Write lock for {} is poisoned", name)), true),
+    };
+
+    lock_diagnostics::end_wait(lock_id, true, start.elapsed(), contended);
+    LockWriteGuard { guard, _held: HeldLock { lock_id } }
+}
+
+#[cfg(not(debug_assertions))]
+pub fn acquire_lock<'a, T>(lock: &'a RwLock<T>, name: &str) -> LockReadGuard<'a, T> {
+    LockReadGuard { guard: lock.read().unwrap_or_else(|_| panic!("Read lock for {} is poisoned", name)) }
+}
+
+#[cfg(not(debug_assertions))]
+pub fn acquire_lock_mut<'a, T>(lock: &'a RwLock<T>, name: &str) -> LockWriteGuard<'a, T> {
+    LockWriteGuard { guard: lock.write().unwrap_or_else(|_| panic!("Write lock for {} is poisoned", name)) }
+}
+
+pub struct LockReadGuard<'a, T> {
+    guard: RwLockReadGuard<'a, T>,
+    #[cfg(debug_assertions)]
+    _held: HeldLock,
+}
+
+pub struct LockWriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+    #[cfg(debug_assertions)]
+    _held: HeldLock,
+}
 
-    while start.elapsed() < timeout {
-        if let Ok(guard) = lock.try_write() {
-            return guard;
-        }
-        thread::sleep(Duration::from_millis(1));
+// Declared after the guard so the hold time is recorded once the lock is released
+#[cfg(debug_assertions)]
+struct HeldLock {
+    lock_id: usize,
+}
+
+#[cfg(debug_assertions)]
+impl Drop for HeldLock {
+    fn drop(&mut self) {
+        lock_diagnostics::release(self.lock_id);
+    }
+}
+
+impl<T> Deref for LockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
     }
+}
+
+impl<T> Deref for LockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
 
-    eprintln!("Failed to acquire write lock for {} within 2 seconds", name);
-    panic!("Lock acquisition timed out");
+impl<T> DerefMut for LockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
 }
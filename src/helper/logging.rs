@@ -0,0 +1,78 @@
+use crate::enums::loglevel::LogLevel;
+use crate::game::data::save_load::data_path;
+use std::env;
+use std::fs::OpenOptions;
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+// Targets used with the tracing macros, e.g. `info!(target: SAVE, ...)`.
+// RUST_LOG can raise or lower any of them on top of the level in settings, e.g. `collision=trace`.
+pub const SAVE: &str = "save";
+pub const IDLE: &str = "idle";
+pub const GAME_LOOP: &str = "game_loop";
+pub const COLLISION: &str = "collision";
+pub const RENDER: &str = "render";
+pub const AUDIO: &str = "audio";
+pub const INPUT: &str = "input";
+pub const UI: &str = "ui";
+pub const LOCK: &str = "lock";
+
+// Written next to the save file
+const LOG_FILE: &str = "game.log";
+
+// Keeps third party crates quiet unless RUST_LOG asks for them
+const DEPENDENCY_DIRECTIVES: &str = "wgpu_core=warn,wgpu_hal=warn,naga=warn,eframe=warn,egui_glow=warn";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+pub fn init_logging(level: LogLevel) {
+    let (filter, handle) = reload::Layer::new(build_filter(level));
+
+    let log_path = data_path(LOG_FILE);
+    let file_layer = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| eprintln!("Failed to open log file {}: {}", log_path.display(), e))
+        .ok()
+        .map(|file| {
+            fmt::layer()
+                .with_ansi(false)
+                .with_thread_names(true)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_writer(Mutex::new(file))
+        });
+
+    let console_layer = fmt::layer()
+        .with_thread_names(true)
+        .with_span_events(FmtSpan::CLOSE);
+
+    let initialised = tracing_subscriber::registry()
+        .with(filter)
+        .with(console_layer)
+        .with(file_layer)
+        .try_init()
+        .is_ok();
+
+    if initialised {
+        let _ = FILTER_HANDLE.set(handle);
+    }
+}
+
+pub fn set_log_level(level: LogLevel) {
+    if let Some(handle) = FILTER_HANDLE.get() && let Err(e) = handle.reload(build_filter(level)) {
+        eprintln!("Failed to change log level: {}", e);
+    }
+}
+
+fn build_filter(level: LogLevel) -> EnvFilter {
+    let mut directives = format!("{},{}", level.as_directive(), DEPENDENCY_DIRECTIVES);
+    if let Ok(overrides) = env::var("RUST_LOG") {
+        directives.push(',');
+        directives.push_str(&overrides);
+    }
+
+    EnvFilter::new(directives)
+}
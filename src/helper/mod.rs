@@ -1 +1,5 @@
-pub mod lock_helper;
\ No newline at end of file
+pub mod lock_helper;
+#[cfg(debug_assertions)]
+pub mod lock_diagnostics;
+pub mod logging;
+pub mod triple_buffer;
\ No newline at end of file
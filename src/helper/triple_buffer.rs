@@ -0,0 +1,59 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const INDEX_MASK: usize = 0b011;
+const FRESH_BIT: usize = 0b100;
+
+struct SharedBuffers<T> {
+    buffers: [UnsafeCell<T>; 3],
+    middle: AtomicUsize,
+}
+
+// The writer and reader each own one buffer outright and only ever trade the
+// middle buffer through an atomic swap, so no buffer is touched by both sides at once.
+unsafe impl<T: Send> Sync for SharedBuffers<T> {}
+
+pub struct TripleBufferWriter<T> {
+    shared: Arc<SharedBuffers<T>>,
+    write_index: usize,
+}
+
+pub struct TripleBufferReader<T> {
+    shared: Arc<SharedBuffers<T>>,
+    read_index: usize,
+}
+
+pub fn triple_buffer<T: Default>() -> (TripleBufferWriter<T>, TripleBufferReader<T>) {
+    let shared = Arc::new(SharedBuffers {
+        buffers: [UnsafeCell::new(T::default()), UnsafeCell::new(T::default()), UnsafeCell::new(T::default())],
+        middle: AtomicUsize::new(1),
+    });
+
+    (
+        TripleBufferWriter { shared: Arc::clone(&shared), write_index: 0 },
+        TripleBufferReader { shared, read_index: 2 },
+    )
+}
+
+impl<T> TripleBufferWriter<T> {
+    pub fn write(&mut self) -> &mut T {
+        unsafe { &mut *self.shared.buffers[self.write_index].get() }
+    }
+
+    pub fn publish(&mut self) {
+        let previous = self.shared.middle.swap(self.write_index | FRESH_BIT, Ordering::AcqRel);
+        self.write_index = previous & INDEX_MASK;
+    }
+}
+
+impl<T> TripleBufferReader<T> {
+    pub fn read(&mut self) -> &T {
+        if self.shared.middle.load(Ordering::Relaxed) & FRESH_BIT != 0 {
+            let previous = self.shared.middle.swap(self.read_index, Ordering::AcqRel);
+            self.read_index = previous & INDEX_MASK;
+        }
+
+        unsafe { &*self.shared.buffers[self.read_index].get() }
+    }
+}
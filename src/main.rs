@@ -7,7 +7,13 @@ use rayon::prelude::ParallelSliceMut;
 use rayon::ThreadPoolBuilder;
 use std::sync::Arc;
 use std::thread;
+use tracing::info;
 use crate::game::loops::idle_loop::IdleLoop;
+use crate::enums::loglevel::LogLevel;
+use crate::game::data::stored_data::SETTINGS;
+use crate::helper::lock_helper::acquire_lock_mut;
+use crate::helper::logging::{init_logging, set_log_level, GAME_LOOP};
+use crate::helper::triple_buffer::triple_buffer;
 
 mod game;
 mod ui;
@@ -20,11 +26,15 @@ fn main() {
         .build_global()
         .unwrap();
 
-    println!("0");
+    init_logging(LogLevel::default());
+
     let game_data = load_game_or_new();
-    println!("1");
+    if let Some(settings) = game_data.get_field(SETTINGS) {
+        set_log_level(settings.log_level);
+    }
+
     let mut testing = KiraAudio::new();
-    println!("Initialised all Game Data");
+    info!(target: GAME_LOOP, "Initialised all Game Data");
 
     let game_data_arc = Arc::new(game_data);
     let game_data_one = Arc::clone(&game_data_arc);
@@ -33,15 +43,26 @@ fn main() {
     let game_data_four = Arc::clone(&game_data_arc);
     let game_data_five = Arc::clone(&game_data_arc);
 
-    let game_loop = GameLoop::new(game_data_one);
+    let (render_snapshot_writer, render_snapshot_reader) = triple_buffer();
+    let audio_events = acquire_lock_mut(&game_data_arc.events, "events").subscribe();
+
+    let game_loop = GameLoop::new(game_data_one, render_snapshot_writer);
     let input_listener = InputListener::new(game_data_two);
     let idle_loop = IdleLoop::new(game_data_three);
 
-    thread::spawn(move || game_loop.start_game());
-    thread::spawn(move || idle_loop.start_idle_loop());
-    thread::spawn(move || input_listener.listen());
-    thread::spawn(move || auto_save(game_data_four));
-    thread::spawn(move || { testing.play_soundtrack_looping(); });
+    spawn_named("game_loop", move || game_loop.start_game());
+    spawn_named("idle_loop", move || idle_loop.start_idle_loop());
+    spawn_named("input_listener", move || input_listener.listen());
+    spawn_named("auto_save", move || auto_save(game_data_four));
+    spawn_named("audio", move || { testing.listen(audio_events); });
+
+    create_window(game_data_five, render_snapshot_reader).expect("Failed to start UI");
+}
 
-    create_window(game_data_five).expect("Failed to start UI");
+// Named so lock diagnostics and panics can say which loop they came from
+fn spawn_named<F: FnOnce() + Send + 'static>(name: &str, f: F) {
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(f)
+        .unwrap_or_else(|e| panic!("Failed to start {} thread: {}", name, e));
 }
\ No newline at end of file
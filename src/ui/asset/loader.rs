@@ -1,11 +1,22 @@
 use crate::game::data::game_data::GameData;
+use crate::helper::logging::RENDER;
 use crate::ui::asset::sprite::sprite_sheet::{SpriteSheet, SPRITE_DATA, SPRITE_FOLDERS};
 use eframe::egui;
 use eframe::egui::ColorImage;
 use egui::{Context, FontData, FontDefinitions, FontFamily};
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
+use tracing::{debug, info};
 
+// Maps shipped with the game, found by the map file an adventure names
+pub const EMBER_ARENA_MAP: &str = include_str!("map/ember_arena.tmj");
+
+pub fn embedded_map(map_file: &str) -> Option<&'static str> {
+    match map_file {
+        "map/ember_arena.tmj" => Some(EMBER_ARENA_MAP),
+        _ => None,
+    }
+}
 
 // Icons
 pub const ADVENTURE_IMAGE: &str = "adventure";
@@ -134,7 +145,7 @@ pub fn load_sprites_native(gl: &glow::Context) -> FxHashMap<String, SpriteSheet>
 
     load_sprite_folders_native(gl, &mut native_sprite_sheets);
 
-    println!("✅ All native sprites loaded!");
+    info!(target: RENDER, "All native sprites loaded!");
     native_sprite_sheets
 }
 
@@ -142,7 +153,8 @@ fn load_sprite_sheets_native(gl: &glow::Context, native_sprite_sheets: &mut FxHa
     let total_sheets = SPRITE_DATA.len();
 
     for (index, (name, bytes, width, height)) in SPRITE_DATA.iter().enumerate() {
-        println!(
+        debug!(
+            target: RENDER,
             "[{}/{}] Loading native sprite sheet: '{}' ({:.1}% done)",
             index + 1, total_sheets, name, (index as f32 / total_sheets as f32) * 100.0
         );
@@ -156,7 +168,8 @@ fn load_sprite_folders_native(gl: &glow::Context, native_sprite_sheets: &mut FxH
     let total_folders = SPRITE_FOLDERS.len();
 
     for (index, (name, folder_path)) in SPRITE_FOLDERS.iter().enumerate() {
-        println!(
+        debug!(
+            target: RENDER,
             "[{}/{}] Loading sprite folder: '{}' ({:.1}% done)",
             index + 1, total_folders, name, (index as f32 / total_folders as f32) * 100.0
         );
@@ -1,8 +1,10 @@
+use crate::helper::logging::RENDER;
 use egui::ColorImage;
 use glow::{HasContext, NativeTexture, PixelUnpackData};
 use image::{DynamicImage, RgbaImage};
 use std::fs::{create_dir_all, read, read_dir};
 use std::path::PathBuf;
+use tracing::{debug, trace};
 
 // Sprites
 pub const ADULT_GREEN_DRAGON: &str = "adult_green_dragon";
@@ -63,7 +65,7 @@ impl SpriteSheet {
 
         let path = PathBuf::from(folder_path);
 
-        println!("Path: {:?}", path);
+        debug!(target: RENDER, "Loading sprite folder {:?}", path);
 
         let mut files: Vec<PathBuf> = read_dir(&path)
             .expect("Failed to read sprite folder")
@@ -72,10 +74,9 @@ impl SpriteSheet {
             .collect();
 
         files.sort();
-        println!("✅ Files sorted.");
 
         for (index, file) in files.iter().enumerate() {
-            println!("📂 Loading image {}", index);
+            trace!(target: RENDER, "Loading image {}", index);
             let image_data = read(file).expect("Failed to read image file");
             let sprite = image::load_from_memory(&image_data).expect("Failed to load image");
 
@@ -147,7 +148,7 @@ fn create_opengl_texture(gl: &glow::Context, image: &RgbaImage) -> NativeTexture
 
 pub fn convert_transparent_white_to_black(gl: &glow::Context, folder_path: &str) {
     let path = PathBuf::from(folder_path);
-    println!("Path: {:?}", path);
+    debug!(target: RENDER, "Loading sprite folder {:?}", path);
 
     let mut files: Vec<PathBuf> = read_dir(&path)
         .expect("Failed to read sprite folder")
@@ -156,14 +157,13 @@ pub fn convert_transparent_white_to_black(gl: &glow::Context, folder_path: &str)
         .collect();
 
     files.sort();
-    println!("✅ Files sorted.");
 
     let output_folder = path.join("modified");
     create_dir_all(&output_folder).expect("Failed to create modified image folder");
 
     // Load and modify each image
     for (index, file) in files.iter().enumerate() {
-        println!("📂 Loading image {}", index);
+        trace!(target: RENDER, "Loading image {}", index);
         let image_data = read(file).expect("Failed to read image file");
         let sprite = image::load_from_memory(&image_data).expect("Failed to load image");
 
@@ -186,6 +186,6 @@ pub fn convert_transparent_white_to_black(gl: &glow::Context, folder_path: &str)
             .save(&modified_file_path)
             .expect("Failed to save modified image");
 
-        println!("✅ Saved modified image: {}", modified_file_path.display());
+        debug!(target: RENDER, "Saved modified image: {}", modified_file_path.display());
     }
 }
\ No newline at end of file
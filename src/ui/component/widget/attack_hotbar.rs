@@ -0,0 +1,70 @@
+use crate::game::data::attack_loadout::{Hotbar, SlotCooldown, ABILITY_KEYS};
+use crate::ui::asset::loader::DP_COMIC_FONT;
+use eframe::egui::{Color32, Sense, Ui, Widget};
+use egui::{Align2, FontFamily, FontId, Painter, Pos2, Rect, Response, Stroke, StrokeKind, Vec2};
+
+const SLOT_SPACING: f32 = 6.0;
+// Auto cast slots are drawn smaller than the ability slots they sit beside
+const AUTO_CAST_SCALE: f32 = 0.65;
+
+pub struct AttackHotbar<'a> {
+    hotbar: &'a Hotbar,
+}
+
+impl<'a> AttackHotbar<'a> {
+    pub fn new(hotbar: &'a Hotbar) -> Self {
+        Self { hotbar }
+    }
+}
+
+impl<'a> Widget for AttackHotbar<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::hover());
+        let painter = ui.painter_at(rect);
+
+        let slot_size = rect.height();
+        let auto_cast_size = slot_size * AUTO_CAST_SCALE;
+        let auto_cast_width = self.hotbar.auto_cast.len() as f32 * (auto_cast_size + SLOT_SPACING);
+        let abilities_width = self.hotbar.abilities.len() as f32 * (slot_size + SLOT_SPACING) - SLOT_SPACING;
+        let mut left = rect.center().x - (auto_cast_width + SLOT_SPACING + abilities_width) / 2.0;
+
+        for slot in &self.hotbar.auto_cast {
+            let slot_rect = Rect::from_min_size(Pos2::new(left, rect.max.y - auto_cast_size), Vec2::splat(auto_cast_size));
+            draw_slot(&painter, slot_rect, Some(slot), None);
+            left += auto_cast_size + SLOT_SPACING;
+        }
+        left += SLOT_SPACING;
+
+        for (slot, key) in self.hotbar.abilities.iter().zip(ABILITY_KEYS) {
+            let slot_rect = Rect::from_min_size(Pos2::new(left, rect.min.y), Vec2::splat(slot_size));
+            draw_slot(&painter, slot_rect, slot.as_ref(), Some(&key.to_string()));
+            left += slot_size + SLOT_SPACING;
+        }
+
+        response
+    }
+}
+
+// The unfilled part of a cooling down slot is shaded from the top
+fn draw_slot(painter: &Painter, rect: Rect, slot: Option<&SlotCooldown>, key: Option<&str>) {
+    let font = |size: f32| FontId::new(size, FontFamily::Name(DP_COMIC_FONT.into()));
+    painter.rect_filled(rect, 4.0, Color32::from_rgba_unmultiplied(20, 20, 20, 220));
+
+    let ready = slot.is_some_and(|slot| slot.remaining <= 0.0);
+    if let Some(slot) = slot {
+        painter.text(rect.center(), Align2::CENTER_CENTER, format!("{:?}", slot.attack_name), font(rect.height() * 0.2), Color32::WHITE);
+
+        if !ready {
+            let shaded = Rect::from_min_max(rect.min, Pos2::new(rect.max.x, rect.min.y + rect.height() * (1.0 - slot.progress())));
+            painter.rect_filled(shaded, 4.0, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+            painter.text(rect.center_bottom() - Vec2::new(0.0, 2.0), Align2::CENTER_BOTTOM, format!("{:.1}", slot.remaining), font(rect.height() * 0.28), Color32::LIGHT_GRAY);
+        }
+    }
+
+    if let Some(key) = key {
+        painter.text(rect.min + Vec2::new(3.0, 1.0), Align2::LEFT_TOP, key, font(rect.height() * 0.22), Color32::GOLD);
+    }
+
+    let border = if ready { Color32::GOLD } else { Color32::from_rgb(128, 0, 128) };
+    painter.rect_stroke(rect, 4.0, Stroke::new(2.0, border), StrokeKind::Inside);
+}
@@ -50,6 +50,11 @@ impl<'a> CustomProgressBar<'a> {
         self
     }
 
+    pub fn hide_completed(mut self) -> Self {
+        self.draw_completed = false;
+        self
+    }
+
     pub fn with_completed_text(mut self, text_str: String, font_id: FontId) -> Self {
         self.draw_completed = true;
         self.completed_text = Some(text_str.clone());
@@ -112,7 +117,7 @@ impl<'a> Widget for CustomProgressBar<'a> {
         let size = Vec2::new(ui.available_width(), ui.available_height());
 
         let (rect, response) = ui.allocate_exact_size(size, Sense::click());
-        let bar_colour = if progress_completed { Color32::from_rgb(0, 200, 0) } else { Color32::from_rgb(200, 0, 0) };
+        let bar_colour = if progress_completed && self.draw_completed { Color32::from_rgb(0, 200, 0) } else { Color32::from_rgb(200, 0, 0) };
 
         let button_clicked = self.button_clicked(ui, &response);
         let click_released_inside = self.released_inside(ui, &response);
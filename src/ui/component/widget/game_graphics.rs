@@ -1,24 +1,33 @@
+use crate::game::data::chain_arcs::{ChainArc, CHAIN_ARC_DURATION};
+use crate::game::data::damage_numbers::{DamageNumber, DAMAGE_NUMBER_DURATION};
 use crate::game::data::game_data::GameData;
 use crate::game::map::camera_state::CameraState;
+use crate::game::map::flow_field::{FlowField, UNREACHABLE};
 use crate::game::maths::pos_2::{Pos2FixedPoint, FIXED_POINT_SCALE};
-use crate::ui::graphics::gl::{draw_map, draw_units};
+use crate::helper::lock_helper::acquire_lock_mut;
+use crate::helper::logging::RENDER;
+use crate::ui::graphics::gl::{draw_fog, draw_map, draw_units};
+use crate::ui::asset::loader::DP_COMIC_FONT;
 use crate::ui::graphics::rendering_data::RenderData;
 use eframe::egui::{Color32, Sense, Ui, Widget};
 use eframe::Frame;
-use egui::{Pos2, Rect, Response, Stroke, StrokeKind};
+use egui::{Align2, FontFamily, FontId, Painter, Pos2, Rect, Response, Shape, Stroke, StrokeKind, Vec2};
 use glow::*;
+use rand::Rng;
 use std::hash::Hash;
 use std::sync::Arc;
 use std::time::Instant;
+use tracing::debug;
 
 pub struct GameGraphics<'a> {
     game_data: Arc<GameData>,
     frame: &'a mut Frame,
+    render_data: &'a RenderData,
 }
 
 impl<'a> GameGraphics<'a> {
-    pub fn new(game_data: Arc<GameData>, frame: &'a mut Frame) -> Self {
-        Self { game_data, frame }
+    pub fn new(game_data: Arc<GameData>, frame: &'a mut Frame, render_data: &'a RenderData) -> Self {
+        Self { game_data, frame, render_data }
     }
 }
 
@@ -26,7 +35,10 @@ impl<'a> Widget for GameGraphics<'a> {
     fn ui(self, ui: &mut Ui) -> Response {
         let available_size = ui.available_size_before_wrap();
         let (rect, response) = ui.allocate_exact_size(available_size, Sense::click());
-        let render_data = RenderData::from(Arc::clone(&self.game_data));
+        let render_data = self.render_data;
+
+        let cursor_position = response.hover_pos().map(|pointer| screen_to_world(pointer - rect.min.to_vec2(), &render_data.camera_state, &rect));
+        *acquire_lock_mut(&self.game_data.cursor_position, "cursor_position") = cursor_position;
 
         let mut renderer_lock = self.game_data.offscreen_renderer.write().unwrap();
         if let Some(renderer) = renderer_lock.as_mut() {
@@ -43,14 +55,20 @@ impl<'a> Widget for GameGraphics<'a> {
                 gl.clear_color(0.0, 0.0, 0.0, 1.0);
                 gl.clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
             }
-            draw_map(&gl, &render_data, &rect, renderer);
-            draw_units(&gl, &render_data, &rect, renderer);
+            draw_map(&gl, render_data, &rect, renderer);
+            draw_units(&gl, render_data, &rect, renderer);
+            draw_fog(&gl, render_data, &rect, renderer);
             renderer.unbind();
 
             let texture_id = self.frame.register_native_glow_texture(renderer.get_texture());
             let uv = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(1.0, 1.0));
             let tint = Color32::WHITE;
             ui.painter().image(texture_id, rect, uv, tint);
+            if let Some(flow_field) = &render_data.flow_field {
+                draw_flow_field(ui.painter(), flow_field, &render_data.camera_state, &rect);
+            }
+            draw_chain_arcs(ui.painter(), &render_data.chain_arcs, &render_data.camera_state, &rect);
+            draw_damage_numbers(ui.painter(), &render_data.damage_numbers, &render_data.camera_state, &rect);
             ui.painter().rect_stroke(rect, 5.0, Stroke::new(3.0, Color32::from_rgb(100, 0, 100)), StrokeKind::Inside);
 
             return response
@@ -67,16 +85,99 @@ pub(crate) fn world_to_screen(world_pos: Pos2FixedPoint, camera: &CameraState, r
     )
 }
 
+pub(crate) fn screen_to_world(screen_pos: Pos2, camera: &CameraState, rect: &Rect) -> Pos2FixedPoint {
+    let scale = FIXED_POINT_SCALE as f32 / camera.get_zoom_scaled();
+    Pos2FixedPoint::new(
+        camera.camera_pos.x + ((screen_pos.x - rect.size().x / 2.0) * scale) as i32,
+        camera.camera_pos.y + ((screen_pos.y - rect.size().y / 2.0) * scale) as i32,
+    )
+}
+
+// Debug overlay, one arrow per visible tile pointing at the next tile, red further from the player
+fn draw_flow_field(painter: &Painter, flow_field: &FlowField, camera: &CameraState, rect: &Rect) {
+    let max_cost = flow_field.costs.iter().copied().filter(|&cost| cost != UNREACHABLE).max().unwrap_or(0).max(1);
+    let half_tile = flow_field.tile_size / 2;
+    let tile_centre = |x: usize, y: usize| {
+        let world_pos = Pos2FixedPoint::new(x as i32 * flow_field.tile_size + half_tile, y as i32 * flow_field.tile_size + half_tile);
+        world_to_screen(world_pos, camera, rect) + rect.min.to_vec2()
+    };
+
+    // Only the part of the field's window that is on screen, plus a tile of margin
+    let view_min = screen_to_world(Pos2::ZERO, camera, rect);
+    let view_max = screen_to_world(rect.size().to_pos2(), camera, rect);
+    let to_tile = |value: i32| (value.max(0) / flow_field.tile_size) as usize;
+    let (min_x, min_y) = (to_tile(view_min.x).saturating_sub(1).max(flow_field.origin.0), to_tile(view_min.y).saturating_sub(1).max(flow_field.origin.1));
+    let (max_x, max_y) = ((to_tile(view_max.x) + 1).min(flow_field.origin.0 + flow_field.width), (to_tile(view_max.y) + 1).min(flow_field.origin.1 + flow_field.height));
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let Some((next_x, next_y)) = flow_field.next_tile(x, y) else { continue; };
+
+            let start = tile_centre(x, y);
+            if !rect.contains(start) {
+                continue;
+            }
+            let end = start + (tile_centre(next_x, next_y) - start) * 0.4;
+
+            let heat = (flow_field.cost(x, y) as f32 / max_cost as f32).min(1.0);
+            let colour = Color32::from_rgb((255.0 * heat) as u8, (255.0 * (1.0 - heat)) as u8, 60);
+            painter.line_segment([start, end], Stroke::new(1.5, colour));
+            painter.circle_filled(start, 1.5, colour);
+        }
+    }
+}
+
+// Jagged lines that fade out over the arc's lifetime
+fn draw_chain_arcs(painter: &Painter, chain_arcs: &[ChainArc], camera: &CameraState, rect: &Rect) {
+    const SEGMENTS: usize = 6;
+
+    for chain_arc in chain_arcs {
+        let fade = 1.0 - (chain_arc.spawn_time.elapsed().as_secs_f32() / CHAIN_ARC_DURATION.as_secs_f32()).min(1.0);
+        let colour = Color32::from_rgba_unmultiplied(170, 200, 255, (255.0 * fade) as u8);
+
+        let start = world_to_screen(chain_arc.from, camera, rect) + rect.min.to_vec2();
+        let end = world_to_screen(chain_arc.to, camera, rect) + rect.min.to_vec2();
+        let normal = (end - start).normalized().rot90();
+
+        let points: Vec<Pos2> = (0..=SEGMENTS).map(|segment| {
+            let along = start + (end - start) * (segment as f32 / SEGMENTS as f32);
+            let jitter = if segment == 0 || segment == SEGMENTS { 0.0 } else { rand::rng().random_range(-6.0..6.0) };
+            along + normal * jitter
+        }).collect();
+
+        painter.add(Shape::line(points, Stroke::new(2.0, colour)));
+    }
+}
+
 fn check_window_size(game_data: &GameData, rect: Rect) {
     let mut window_size_lock = game_data.graphic_window_size.write().unwrap();
 
     if let Some(previous_size) = *window_size_lock {
         if rect.size() != previous_size {
-            println!("Size Changed: {:?} -> {:?}", previous_size, rect.size());
+            debug!(target: RENDER, "Size Changed: {:?} -> {:?}", previous_size, rect.size());
             *window_size_lock = Some(rect.size());
         }
     } else {
-        println!("Set Initial Size: {:?}", rect.size());
+        debug!(target: RENDER, "Set Initial Size: {:?}", rect.size());
         *window_size_lock = Some(rect.size());
     }
+}
+
+// Numbers float up and fade out, crits are drawn larger with an exclamation mark
+fn draw_damage_numbers(painter: &Painter, damage_numbers: &[DamageNumber], camera: &CameraState, rect: &Rect) {
+    const RISE: f32 = 30.0;
+
+    for damage_number in damage_numbers {
+        let age = (damage_number.spawn_time.elapsed().as_secs_f32() / DAMAGE_NUMBER_DURATION.as_secs_f32()).min(1.0);
+        let position = world_to_screen(damage_number.position, camera, rect) + rect.min.to_vec2() - Vec2::new(0.0, RISE * age);
+        if !rect.contains(position) {
+            continue;
+        }
+
+        let value = if damage_number.value < 10.0 { format!("{:.1}", damage_number.value) } else { format!("{:.0}", damage_number.value) };
+        let (text, size) = if damage_number.critical { (format!("{}!", value), 22.0) } else { (value, 15.0) };
+        let colour = damage_number.colour.gamma_multiply(1.0 - age);
+
+        painter.text(position, Align2::CENTER_BOTTOM, text, FontId::new(size, FontFamily::Name(DP_COMIC_FONT.into())), colour);
+    }
 }
\ No newline at end of file
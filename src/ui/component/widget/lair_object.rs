@@ -1,4 +1,5 @@
 use crate::game::data::resource_cost::ResourceAmount;
+use crate::helper::logging::UI;
 use crate::ui::asset::loader::{DP_COMIC_FONT, DRAGON_HEART_GEMSTONE_IMAGE, IMP_CHEF_IMAGE};
 use crate::ui::component::widget::custom_progress_bar::CustomProgressBar;
 use crate::ui::component::widget::label_no_interact::LabelNoInteract;
@@ -10,6 +11,7 @@ use eframe::epaint::FontFamily;
 use egui::{Align, Color32, FontId, Frame, Image, Layout, Stroke, TextureHandle, UiBuilder, Vec2};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use tracing::debug;
 use derivative::Derivative;
 
 #[derive(Clone, Serialize, Deserialize, Derivative)]
@@ -107,7 +109,7 @@ impl Widget for LairObject {
                                     CustomProgressBar::new(progress, self.production_duration as f64)
                                         .show_percentage()
                                         .with_completed_text("Production Ready".to_string(), font_id)
-                                        .set_on_click(Box::new(|| println!("Progress Bar Clicked")))
+                                        .set_on_click(Box::new(|| debug!(target: UI, "Progress Bar Clicked")))
                                 );
                             });
                         });
@@ -0,0 +1,97 @@
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::settings::Settings;
+use crate::ui::graphics::gl::tile_colour;
+use crate::ui::graphics::rendering_data::RenderData;
+use eframe::egui::{Color32, Sense, Ui, Widget};
+use egui::{Pos2, Rect, Response, Stroke, StrokeKind, Vec2};
+
+// Tiles drawn per side before the minimap starts skipping tiles
+const MAX_TILES_ACROSS: usize = 100;
+
+pub struct Minimap<'a> {
+    render_data: &'a RenderData,
+    zoom: f32,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(render_data: &'a RenderData, settings: &Settings) -> Self {
+        Self { render_data, zoom: settings.minimap_zoom.max(1.0) }
+    }
+}
+
+impl<'a> Widget for Minimap<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 5.0, Color32::from_rgba_unmultiplied(0, 0, 0, 196));
+
+        let render_data = self.render_data;
+        let Some(game_map) = &render_data.game_map else { return response; };
+
+        let player_position = render_data.units
+            .iter()
+            .find(|unit| unit.object_type == ObjectType::Player)
+            .map_or(render_data.camera_state.camera_pos, |unit| unit.position);
+
+        // Zoomed in views follow the player but stop at the map edge, an axis shorter than the view is centred
+        let map_extent = (game_map.width.max(game_map.height) as i64 * game_map.tile_size as i64) as f32;
+        let view_extent = map_extent / self.zoom;
+        let clamp_axis = |centre: i32, map_size: usize| {
+            let map_size = (map_size as i64 * game_map.tile_size as i64) as f32;
+            if map_size <= view_extent {
+                (map_size - view_extent) / 2.0
+            } else {
+                (centre as f32 - view_extent / 2.0).clamp(0.0, map_size - view_extent)
+            }
+        };
+        let view_min = (clamp_axis(player_position.x, game_map.width), clamp_axis(player_position.y, game_map.height));
+        let scale = rect.width() / view_extent;
+
+        let to_minimap = |position: Pos2FixedPoint| {
+            Pos2::new(rect.min.x + (position.x as f32 - view_min.0) * scale, rect.min.y + (position.y as f32 - view_min.1) * scale)
+        };
+
+        let world_min = Pos2FixedPoint::new(view_min.0 as i32, view_min.1 as i32);
+        let world_max = Pos2FixedPoint::new((view_min.0 + view_extent) as i32, (view_min.1 + view_extent) as i32);
+        if let Some((min_tile, max_tile)) = game_map.tile_range(world_min, world_max) {
+            let step = (max_tile.0 - min_tile.0 + 1).max(max_tile.1 - min_tile.1 + 1).div_ceil(MAX_TILES_ACROSS).max(1);
+            let tile_extent = Vec2::splat(game_map.tile_size as f32 * step as f32 * scale);
+
+            game_map.for_each_tile_in(min_tile, max_tile, |x, y, tile| {
+                if x % step != 0 || y % step != 0 {
+                    return;
+                }
+
+                let colour = match &render_data.visibility {
+                    Some(visibility) if !visibility.is_explored(x, y) => return,
+                    Some(visibility) if !visibility.is_visible(x, y) => tile_colour(tile.tile_type).gamma_multiply(0.5),
+                    _ => tile_colour(tile.tile_type),
+                };
+
+                let tile_min = to_minimap(Pos2FixedPoint::new(x as i32 * game_map.tile_size, y as i32 * game_map.tile_size));
+                painter.rect_filled(Rect::from_min_size(tile_min, tile_extent), 0.0, colour);
+            });
+        }
+
+        // Denser cells get bigger and more opaque blobs
+        for &(cell_center, count) in &render_data.enemy_density {
+            let strength = (count as f32 / 20.0).min(1.0);
+            painter.circle_filled(to_minimap(cell_center), 1.5 + 3.5 * strength, Color32::from_rgba_unmultiplied(230, 40, 40, (90.0 + 165.0 * strength) as u8));
+        }
+
+        for unit in render_data.units.iter().filter(|unit| unit.object_type == ObjectType::Collectable) {
+            painter.circle_filled(to_minimap(unit.position), 1.5, Color32::GOLD);
+        }
+
+        if let Some(boss) = &render_data.boss {
+            painter.circle_filled(to_minimap(boss.position), 5.0, Color32::from_rgb(200, 0, 200));
+            painter.circle_stroke(to_minimap(boss.position), 5.0, Stroke::new(1.5, Color32::WHITE));
+        }
+
+        painter.circle_filled(to_minimap(player_position), 3.0, Color32::WHITE);
+        painter.rect_stroke(rect, 5.0, Stroke::new(2.0, Color32::from_rgb(128, 0, 128)), StrokeKind::Inside);
+
+        response
+    }
+}
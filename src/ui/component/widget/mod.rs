@@ -4,4 +4,6 @@ pub mod label_no_interact;
 pub mod game_graphics;
 pub mod custom_progress_bar;
 pub mod interactive_widget;
-pub mod lair_object;
\ No newline at end of file
+pub mod lair_object;
+pub mod minimap;
+pub mod attack_hotbar;
\ No newline at end of file
@@ -23,72 +23,117 @@ pub fn draw_map(gl: &Context, render_data: &RenderData, paintbox_rect: &Rect, re
         let mut colours = Vec::new();
         let mut sprite_tiles = Vec::new();
 
-        for x in 0..game_map.width {
-            for y in 0..game_map.height {
-                let tile = game_map.get_tile(x, y);
-                let world_pos = Pos2FixedPoint::new(x as i32 * game_map.get_tile_size(), y as i32 * game_map.get_tile_size());
-                let screen_pos = world_to_screen(world_pos, camera_state, paintbox_rect);
-                let tile_rect = Rect::from_min_size(screen_pos, Vec2::new(tile_size, tile_size));
-
-                if !tile_rect.intersects(Rect::from_min_size(Pos2::new(0.0, 0.0), paintbox_rect.size())) {
-                    continue;
+        // Only the tiles under the view are visited, a tile of margin covers partly visible edges
+        let half_extent = paintbox_rect.size() / 2.0 * FIXED_POINT_SCALE as f32 / camera_state.get_zoom_scaled();
+        let view_min = Pos2FixedPoint::new(camera_state.camera_pos.x - half_extent.x as i32 - game_map.get_tile_size(), camera_state.camera_pos.y - half_extent.y as i32 - game_map.get_tile_size());
+        let view_max = Pos2FixedPoint::new(camera_state.camera_pos.x + half_extent.x as i32 + game_map.get_tile_size(), camera_state.camera_pos.y + half_extent.y as i32 + game_map.get_tile_size());
+        let Some((min_tile, max_tile)) = game_map.tile_range(view_min, view_max) else { return; };
+
+        game_map.for_each_tile_in(min_tile, max_tile, |x, y, tile| {
+            let world_pos = Pos2FixedPoint::new(x as i32 * game_map.get_tile_size(), y as i32 * game_map.get_tile_size());
+            let screen_pos = world_to_screen(world_pos, camera_state, paintbox_rect);
+            let tile_rect = Rect::from_min_size(screen_pos, Vec2::new(tile_size, tile_size));
+
+            if !tile_rect.intersects(Rect::from_min_size(Pos2::new(0.0, 0.0), paintbox_rect.size())) {
+                return;
+            }
+
+            if tile.tile_type == TileType::Grass {
+                fn pseudo_random(x: i32, y: i32, seed: u32) -> u32 {
+                    // Simple deterministic noise-like hash
+                    let mut n = x.wrapping_mul(374761393).wrapping_add(y.wrapping_mul(668265263));
+                    n = (n ^ (n >> 13)).wrapping_mul(1274126177);
+                    (n ^ (n >> 16)).wrapping_add(seed as i32) as u32
                 }
 
-                match tile.tile_type {
-                    TileType::Wall => {
-                        rects.push(tile_rect);
-                        colours.push(Color32::from_rgb(100, 100, 100));
-                    }
-                    TileType::SpawnPoint => {
-                        rects.push(tile_rect);
-                        colours.push(Color32::from_rgb(0, 0, 90));
-                    }
-                    TileType::Empty => {
-                        rects.push(tile_rect);
-                        colours.push(Color32::from_rgb(0, 0, 0));
-                    }
-                    TileType::Grass => {
-                        fn pseudo_random(x: i32, y: i32, seed: u32) -> u32 {
-                            // Simple deterministic noise-like hash
-                            let mut n = x.wrapping_mul(374761393).wrapping_add(y.wrapping_mul(668265263));
-                            n = (n ^ (n >> 13)).wrapping_mul(1274126177);
-                            (n ^ (n >> 16)).wrapping_add(seed as i32) as u32
-                        }
-
-                        let patch_size = 1;
-                        let patch_x = x / patch_size;
-                        let patch_y = y / patch_size;
-
-                        let seed = 1337;
-
-                        let grass_index = (pseudo_random(patch_x as i32, patch_y as i32, seed) % 17) as usize;
-
-                        if let Some(sprite_sheet) = renderer.sprite_sheets.get(GRASS) {
-                            let frame = sprite_sheet.get_frame_native(grass_index);
-                            sprite_tiles.push(SpriteToDraw {
-                                texture: frame,
-                                rect: tile_rect,
-                                tint: Color32::WHITE,
-                                blend_target: Color32::WHITE,
-                                colour_blend_amount: 0.0,
-                                alpha_blend_amount: 0.0,
-                                rotation: 0.0,
-                            });
-                        } else {
-                            // fallback: draw as coloured rect if sprite missing
-                            rects.push(tile_rect);
-                            colours.push(Color32::from_rgb(10, 80, 10));
-                        }
-                    }
+                let patch_size = 1;
+                let patch_x = x / patch_size;
+                let patch_y = y / patch_size;
+
+                let seed = 1337;
+
+                let grass_index = (pseudo_random(patch_x as i32, patch_y as i32, seed) % 17) as usize;
+
+                if let Some(sprite_sheet) = renderer.sprite_sheets.get(GRASS) {
+                    let frame = sprite_sheet.get_frame_native(grass_index);
+                    sprite_tiles.push(SpriteToDraw {
+                        texture: frame,
+                        rect: tile_rect,
+                        tint: Color32::WHITE,
+                        blend_target: Color32::WHITE,
+                        colour_blend_amount: 0.0,
+                        alpha_blend_amount: 0.0,
+                        rotation: 0.0,
+                    });
+                    return;
                 }
             }
-        }
+
+            // Grass falls back to a coloured rect if the sprite is missing
+            rects.push(tile_rect);
+            colours.push(tile_colour(tile.tile_type));
+        });
 
         draw_colour_rectangles(gl, &paintbox_rect, &rects, &colours, &Some(renderer.rect_shader));
         draw_colour_sprites(gl, &paintbox_rect, &sprite_tiles, &Some(renderer.sprite_shader));
     }
 }
 
+// Flat colour per tile type, shared by the map and the minimap
+pub fn tile_colour(tile_type: TileType) -> Color32 {
+    match tile_type {
+        TileType::Wall => Color32::from_rgb(100, 100, 100),
+        TileType::SpawnPoint => Color32::from_rgb(0, 0, 90),
+        TileType::Empty => Color32::from_rgb(0, 0, 0),
+        TileType::Water => Color32::from_rgb(30, 90, 190),
+        TileType::Lava => Color32::from_rgb(210, 70, 20),
+        TileType::Ice => Color32::from_rgb(180, 220, 240),
+        TileType::Thorns => Color32::from_rgb(60, 70, 25),
+        TileType::HealingSpring => Color32::from_rgb(110, 230, 170),
+        TileType::BreakableWall => Color32::from_rgb(130, 105, 85),
+        TileType::Crate => Color32::from_rgb(150, 100, 40),
+        TileType::PressurePlate => Color32::from_rgb(160, 160, 120),
+        TileType::Door => Color32::from_rgb(90, 55, 25),
+        TileType::OpenDoor => Color32::from_rgb(45, 30, 15),
+        TileType::Portal => Color32::from_rgb(160, 60, 220),
+        TileType::Grass => Color32::from_rgb(10, 80, 10),
+    }
+}
+
+// Unexplored tiles are drawn over in black, explored tiles out of sight are darkened
+pub fn draw_fog(gl: &Context, render_data: &RenderData, paintbox_rect: &Rect, renderer: &OffscreenRenderer) {
+    let (Some(game_map), Some(visibility)) = (&render_data.game_map, &render_data.visibility) else { return; };
+    let camera_state = &render_data.camera_state;
+    let tile_size = camera_state.get_zoom_scaled() * game_map.get_tile_size() as f32 / FIXED_POINT_SCALE as f32;
+
+    let half_extent = paintbox_rect.size() / 2.0 * FIXED_POINT_SCALE as f32 / camera_state.get_zoom_scaled();
+    let view_min = Pos2FixedPoint::new(camera_state.camera_pos.x - half_extent.x as i32 - game_map.get_tile_size(), camera_state.camera_pos.y - half_extent.y as i32 - game_map.get_tile_size());
+    let view_max = Pos2FixedPoint::new(camera_state.camera_pos.x + half_extent.x as i32 + game_map.get_tile_size(), camera_state.camera_pos.y + half_extent.y as i32 + game_map.get_tile_size());
+    let Some(((min_x, min_y), (max_x, max_y))) = game_map.tile_range(view_min, view_max) else { return; };
+
+    let mut rects = Vec::new();
+    let mut colours = Vec::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if visibility.is_visible(x, y) {
+                continue;
+            }
+
+            let colour = if visibility.is_explored(x, y) {
+                Color32::from_black_alpha(150)
+            } else {
+                Color32::BLACK
+            };
+
+            let world_pos = Pos2FixedPoint::new(x as i32 * game_map.get_tile_size(), y as i32 * game_map.get_tile_size());
+            rects.push(Rect::from_min_size(world_to_screen(world_pos, camera_state, paintbox_rect), Vec2::new(tile_size, tile_size)));
+            colours.push(colour);
+        }
+    }
+
+    draw_colour_rectangles(gl, &paintbox_rect, &rects, &colours, &Some(renderer.rect_shader));
+}
+
 fn get_colour_blend_amount(last_damage_time: Option<Instant>) -> f32 {
     let damage_visual_duration_millis = 100f32;
     let inverse = 1f32 / damage_visual_duration_millis;
@@ -111,8 +156,6 @@ fn get_colour_blend_amount(last_damage_time: Option<Instant>) -> f32 {
 
 pub fn draw_units(gl: &Context, render_data: &RenderData, paintbox_rect: &Rect, renderer: &OffscreenRenderer) {
     let sprite_sheets = &renderer.sprite_sheets;
-    let game_units = &render_data.game_units;
-    let unit_positions = &render_data.unit_positions;
     let camera_state = &render_data.camera_state;
 
     let mut images_to_draw = Vec::new();
@@ -123,144 +166,132 @@ pub fn draw_units(gl: &Context, render_data: &RenderData, paintbox_rect: &Rect,
     let mut health_bar_colours = Vec::new();
     let mut shadow_sprites_to_draw = Vec::new();
 
-    for unit_option in game_units.iter() {
-        if let Some(unit) = unit_option {
-            if let Some(animation) = &unit.animation {
-                let unit_screen_position = world_to_screen(unit_positions[unit.id as usize], &camera_state, paintbox_rect);
+    for unit in render_data.units.iter() {
+        let animation = &unit.animation;
+        let unit_screen_position = world_to_screen(unit.position, &camera_state, paintbox_rect);
 
-                let unit_size = Vec2::new(animation.size.0 as f32, animation.size.1 as f32) * camera_state.get_zoom_scaled();
-                let unit_rect = Rect::from_center_size(unit_screen_position, unit_size);
+        let unit_size = Vec2::new(animation.size.0 as f32, animation.size.1 as f32) * camera_state.get_zoom_scaled();
+        let unit_rect = Rect::from_center_size(unit_screen_position, unit_size);
 
-                if !unit_rect.intersects(Rect::from_min_size(Pos2::new(0.0, 0.0), paintbox_rect.size())) {
-                    continue;
-                }
+        if !unit_rect.intersects(Rect::from_min_size(Pos2::new(0.0, 0.0), paintbox_rect.size())) {
+            continue;
+        }
 
-                if (unit_size.x < 5.0 || unit_size.y < 5.0) && unit.object_type != ObjectType::Player {
-                    rects_to_draw.push(unit_rect);
-                    colours_to_draw.push(Color32::RED);
-                    continue;
-                }
+        if (unit_size.x < 5.0 || unit_size.y < 5.0) && unit.object_type != ObjectType::Player {
+            rects_to_draw.push(unit_rect);
+            colours_to_draw.push(Color32::RED);
+            continue;
+        }
+
+        if let Some(sprite_sheet) = sprite_sheets.get(animation.sprite_key) {
+            let frame_index = animation.fixed_frame_index.unwrap_or_else(|| {
+                (animation.animation_frame * sprite_sheet.get_frame_count_native() as f32).trunc() as usize
+            });
+
+            let last_damage_taken = animation.last_damage_time.clone();
+            let frame = sprite_sheet.get_frame_native(frame_index);
+
+            let shadow_scale = 1.2;
+            let shadow_size = unit_size * Vec2::new(shadow_scale, shadow_scale * 0.4);
+            let shadow_offset = Vec2::new(unit_size.x * 0.07, unit_size.y * 0.35);
+            let shadow_rect = Rect::from_center_size(unit_screen_position + shadow_offset, shadow_size);
 
-                if let Some(sprite_sheet) = sprite_sheets.get(&animation.sprite_key) {
-                    let frame_index = animation.fixed_frame_index.unwrap_or_else(|| {
-                        (animation.animation_frame * sprite_sheet.get_frame_count_native() as f32).trunc() as usize
+            let mut offset = 0.0;
+            if let Some(animation_offset) = animation.rotation_offset {
+                offset = animation_offset;
+            }
+
+            match unit.object_type {
+                ObjectType::Player => {
+                    player_to_draw.push(SpriteToDraw {
+                        texture: frame,
+                        rect: unit_rect,
+                        tint: unit.tint,
+                        blend_target: Color32::WHITE,
+                        colour_blend_amount: get_colour_blend_amount(last_damage_taken),
+                        alpha_blend_amount: 1.0,
+                        rotation: offset,
                     });
 
-                    let last_damage_taken = animation.last_damage_time.clone();
-                    let frame = sprite_sheet.get_frame_native(frame_index);
+                    let health_bar_height = 4.0 * camera_state.get_zoom_scaled();
+                    let health_bar_width = unit_size.x * 0.9;
+                    let current_health_width = health_bar_width * (unit.health_current / unit.health_max);
+
+                    let health_bar_bg_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
+                    let health_bar_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
 
-                    let shadow_scale = 1.2;
-                    let shadow_size = unit_size * Vec2::new(shadow_scale, shadow_scale * 0.4);
-                    let shadow_offset = Vec2::new(unit_size.x * 0.07, unit_size.y * 0.35);
-                    let shadow_rect = Rect::from_center_size(unit_screen_position + shadow_offset, shadow_size);
+                    let health_bar_bg_rect = Rect::from_min_size(health_bar_bg_min, Vec2::new(health_bar_width, health_bar_height));
+                    let health_bar_rect = Rect::from_min_size(health_bar_min, Vec2::new(current_health_width, health_bar_height));
+
+                    health_bar_rects.push(health_bar_bg_rect);
+                    health_bar_colours.push(Color32::BLACK);
+                    health_bar_rects.push(health_bar_rect);
+                    health_bar_colours.push(Color32::GREEN);
+                },
+                ObjectType::Enemy => {
+                    images_to_draw.push(SpriteToDraw {
+                        texture: frame,
+                        rect: unit_rect,
+                        tint: unit.tint,
+                        blend_target: Color32::WHITE,
+                        colour_blend_amount: get_colour_blend_amount(last_damage_taken),
+                        alpha_blend_amount: 0.0,
+                        rotation: offset,
+                    });
 
-                    let mut offset = 0.0;
-                    if let Some(animation_offset) = animation.rotation_offset {
-                        offset = animation_offset;
+                    if unit.health_current != unit.health_max {
+                        let health_bar_height = 3.0 * camera_state.get_zoom_scaled();
+                        let health_bar_width = unit_size.x * 0.7;
+                        let current_health_width = health_bar_width * (unit.health_current / unit.health_max).max(0.0);
+
+                        let health_bar_bg_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
+                        let health_bar_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
+
+                        let health_bar_bg_rect = Rect::from_min_size(health_bar_bg_min, Vec2::new(health_bar_width, health_bar_height));
+                        let health_bar_rect = Rect::from_min_size(health_bar_min, Vec2::new(current_health_width, health_bar_height));
+
+                        health_bar_rects.push(health_bar_bg_rect);
+                        health_bar_colours.push(Color32::BLACK);
+                        health_bar_rects.push(health_bar_rect);
+                        health_bar_colours.push(Color32::RED);
                     }
+                }
+                ObjectType::Collectable => {
+                    images_to_draw.push(SpriteToDraw {
+                        texture: frame,
+                        rect: unit_rect,
+                        tint: Color32::WHITE,
+                        blend_target: Color32::WHITE,
+                        colour_blend_amount: 0.0,
+                        alpha_blend_amount: 0.0,
+                        rotation: offset,
+                    });
+
+                    if unit.health_current != unit.health_max {
+                        let health_bar_height = 3.0 * camera_state.get_zoom_scaled();
+                        let health_bar_width = unit_size.x * 0.7;
+                        let current_health_width = health_bar_width * (unit.health_current / unit.health_max).max(0.0);
+
+                        let health_bar_bg_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
+                        let health_bar_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
 
-                    match unit.object_type {
-                        ObjectType::Player => {
-                            player_to_draw.push(SpriteToDraw {
-                                texture: frame,
-                                rect: unit_rect,
-                                tint: Color32::WHITE,
-                                blend_target: Color32::WHITE,
-                                colour_blend_amount: get_colour_blend_amount(last_damage_taken),
-                                alpha_blend_amount: 1.0,
-                                rotation: offset,
-                            });
-
-                            let health_bar_height = 4.0 * camera_state.get_zoom_scaled();
-                            let health_bar_width = unit_size.x * 0.9;
-                            let current_health_width = health_bar_width * (unit.health_current / unit.health_max);
-
-                            let health_bar_bg_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
-                            let health_bar_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
-
-                            let health_bar_bg_rect = Rect::from_min_size(health_bar_bg_min, Vec2::new(health_bar_width, health_bar_height));
-                            let health_bar_rect = Rect::from_min_size(health_bar_min, Vec2::new(current_health_width, health_bar_height));
-
-                            health_bar_rects.push(health_bar_bg_rect);
-                            health_bar_colours.push(Color32::BLACK);
-                            health_bar_rects.push(health_bar_rect);
-                            health_bar_colours.push(Color32::GREEN);
-                        },
-                        ObjectType::Enemy => {
-                            images_to_draw.push(SpriteToDraw {
-                                texture: frame,
-                                rect: unit_rect,
-                                tint: Color32::WHITE,
-                                blend_target: Color32::WHITE,
-                                colour_blend_amount: get_colour_blend_amount(last_damage_taken),
-                                alpha_blend_amount: 0.0,
-                                rotation: offset,
-                            });
-
-                            if unit.health_current != unit.health_max {
-                                let health_bar_height = 3.0 * camera_state.get_zoom_scaled();
-                                let health_bar_width = unit_size.x * 0.7;
-                                let current_health_width = health_bar_width * (unit.health_current / unit.health_max).max(0.0);
-
-                                let health_bar_bg_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
-                                let health_bar_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
-
-                                let health_bar_bg_rect = Rect::from_min_size(health_bar_bg_min, Vec2::new(health_bar_width, health_bar_height));
-                                let health_bar_rect = Rect::from_min_size(health_bar_min, Vec2::new(current_health_width, health_bar_height));
-
-                                health_bar_rects.push(health_bar_bg_rect);
-                                health_bar_colours.push(Color32::BLACK);
-                                health_bar_rects.push(health_bar_rect);
-                                health_bar_colours.push(Color32::RED);
-                            }
-                        }
-                        ObjectType::Collectable => {
-                            images_to_draw.push(SpriteToDraw {
-                                texture: frame,
-                                rect: unit_rect,
-                                tint: Color32::WHITE,
-                                blend_target: Color32::WHITE,
-                                colour_blend_amount: 0.0,
-                                alpha_blend_amount: 0.0,
-                                rotation: offset,
-                            });
-
-                            if unit.health_current != unit.health_max {
-                                let health_bar_height = 3.0 * camera_state.get_zoom_scaled();
-                                let health_bar_width = unit_size.x * 0.7;
-                                let current_health_width = health_bar_width * (unit.health_current / unit.health_max).max(0.0);
-
-                                let health_bar_bg_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
-                                let health_bar_min = unit_screen_position + Vec2::new(-health_bar_width / 2.0, -unit_size.y * 0.5);
-
-                                let health_bar_bg_rect = Rect::from_min_size(health_bar_bg_min, Vec2::new(health_bar_width, health_bar_height));
-                                let health_bar_rect = Rect::from_min_size(health_bar_min, Vec2::new(current_health_width, health_bar_height));
-
-                                health_bar_rects.push(health_bar_bg_rect);
-                                health_bar_colours.push(Color32::BLACK);
-                                health_bar_rects.push(health_bar_rect);
-                                health_bar_colours.push(Color32::RED);
-                            }
-                        }
-                        ObjectType::Attack => {
-                            if let Some(stats) = &unit.attack_stats {
-                                offset += stats.direction.1.atan2(stats.direction.0).to_degrees();
-                            }
-                            images_to_draw.push(SpriteToDraw {
-                                texture: frame,
-                                rect: unit_rect,
-                                tint: Color32::WHITE,
-                                blend_target: Color32::WHITE,
-                                colour_blend_amount: 0.0,
-                                alpha_blend_amount: 0.0,
-                                rotation: offset,
-                            });
-                        }
+                        let health_bar_bg_rect = Rect::from_min_size(health_bar_bg_min, Vec2::new(health_bar_width, health_bar_height));
+                        let health_bar_rect = Rect::from_min_size(health_bar_min, Vec2::new(current_health_width, health_bar_height));
+
+                        health_bar_rects.push(health_bar_bg_rect);
+                        health_bar_colours.push(Color32::BLACK);
+                        health_bar_rects.push(health_bar_rect);
+                        health_bar_colours.push(Color32::RED);
+                    }
+                }
+                ObjectType::Attack => {
+                    if let Some(direction) = unit.direction {
+                        offset += direction.1.atan2(direction.0).to_degrees();
                     }
-                    shadow_sprites_to_draw.push(SpriteToDraw {
+                    images_to_draw.push(SpriteToDraw {
                         texture: frame,
-                        rect: shadow_rect,
-                        tint: Color32::from_rgba_premultiplied(0, 0, 0, 192),
+                        rect: unit_rect,
+                        tint: unit.tint,
                         blend_target: Color32::WHITE,
                         colour_blend_amount: 0.0,
                         alpha_blend_amount: 0.0,
@@ -268,6 +299,15 @@ pub fn draw_units(gl: &Context, render_data: &RenderData, paintbox_rect: &Rect,
                     });
                 }
             }
+            shadow_sprites_to_draw.push(SpriteToDraw {
+                texture: frame,
+                rect: shadow_rect,
+                tint: Color32::from_rgba_premultiplied(0, 0, 0, 192),
+                blend_target: Color32::WHITE,
+                colour_blend_amount: 0.0,
+                alpha_blend_amount: 0.0,
+                rotation: offset,
+            });
         }
     }
 
@@ -1,4 +1,5 @@
 pub mod gl;
 pub mod offscreen_renderer;
 pub mod sprite_to_draw;
-pub mod rendering_data;
\ No newline at end of file
+pub mod rendering_data;
+pub mod unit_to_render;
\ No newline at end of file
@@ -1,9 +1,11 @@
+use crate::helper::logging::RENDER;
 use crate::ui::asset::loader::load_sprites_native;
 use crate::ui::asset::sprite::sprite_sheet::SpriteSheet;
 use crate::ui::graphics::gl::{create_rect_shader_program, create_sprite_shader_program};
 use glow::*;
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
+use tracing::debug;
 
 pub struct OffscreenRenderer {
     gl: Arc<Context>,
@@ -70,7 +72,7 @@ impl OffscreenRenderer {
             return;
         }
 
-        println!("Resizing framebuffer to: {}x{}", new_width, new_height);
+        debug!(target: RENDER, "Resizing framebuffer to: {}x{}", new_width, new_height);
 
         let gl = &self.gl;
         unsafe {
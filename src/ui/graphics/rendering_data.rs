@@ -1,40 +1,110 @@
-use crate::game::data::damage_numbers::DamageNumber;
+use crate::game::collision::spatial_hash_grid::{cell_center, SpatialHashGrid};
+use crate::game::data::chain_arcs::{ChainArc, CHAIN_ARC_DURATION};
+use crate::game::data::damage_numbers::{DamageNumber, DAMAGE_NUMBER_DURATION};
 use crate::game::data::game_data::GameData;
+use crate::game::data::stored_data::SETTINGS;
+use crate::game::loops::unit_spawner::BossStatus;
 use crate::game::map::camera_state::CameraState;
+use crate::game::map::flow_field::FlowField;
 use crate::game::map::game_map::GameMap;
+use crate::game::map::visibility::VisibilityMap;
 use crate::game::maths::pos_2::Pos2FixedPoint;
-use crate::game::objects::game_object::GameObject;
+use crate::game::objects::elite_affix::elite_tint;
+use crate::game::objects::object_type::ObjectType;
+use crate::game::objects::status_effect::status_tint;
+use crate::game::objects::unit_store::UnitStore;
 use crate::helper::lock_helper::acquire_lock;
-use egui::Vec2;
+use crate::ui::graphics::unit_to_render::UnitToRender;
+use egui::Color32;
 use std::sync::Arc;
 
+#[derive(Default)]
 pub struct RenderData {
-    pub game_units: Vec<Option<GameObject>>,
-    pub unit_positions: Vec<Pos2FixedPoint>,
+    pub units: Vec<UnitToRender>,
     pub camera_state: CameraState,
     pub damage_numbers: Vec<DamageNumber>,
-    pub game_map: Option<GameMap>,
-    pub window_size: Option<Vec2>,
+    pub chain_arcs: Vec<ChainArc>,
+    pub game_map: Option<Arc<GameMap>>,
+    pub boss: Option<BossStatus>,
+    pub flow_field: Option<Arc<FlowField>>,
+    pub visibility: Option<Arc<VisibilityMap>>,
+    // Enemies per spatial grid cell, keyed by the cell centre. Only filled while the minimap is shown.
+    pub enemy_density: Vec<(Pos2FixedPoint, u32)>,
 }
 
 impl RenderData {
-    pub fn from(game_data: Arc<GameData>) -> Self {
-        let game_units = acquire_lock(&game_data.units, "game_units").clone();
-        let unit_positions = acquire_lock(&game_data.unit_positions, "unit_positions").clone();
-        let camera_state = acquire_lock(&game_data.camera_state, "camera_state").clone();
-        let damage_numbers = acquire_lock(&game_data.damage_numbers, "damage_numbers").clone();
-        let game_map = acquire_lock(&game_data.game_map, "game_map").clone();
-        let window_size = acquire_lock(&game_data.graphic_window_size, "window_size").clone();
-
-        Self {
-            game_units,
-            unit_positions,
-            camera_state,
-            damage_numbers,
-            game_map,
-            window_size,
+    pub fn update_from(&mut self, game_data: &GameData) {
+        let settings = game_data.get_field(SETTINGS).unwrap_or_default();
+        self.visibility = acquire_lock(&game_data.visibility, "visibility").clone();
+        let hidden_by_fog = self.visibility.as_deref().filter(|visibility| visibility.fog_of_war.hide_enemies);
+
+        {
+            let spawn_director = acquire_lock(&game_data.spawn_director, "spawn_director");
+            let game_units = acquire_lock(&game_data.units, "game_units");
+            let unit_positions = acquire_lock(&game_data.unit_positions, "unit_positions");
+            collect_units_to_render(&game_units, &unit_positions, &mut self.units);
+            self.boss = spawn_director.as_ref().and_then(|spawn_director| spawn_director.boss_status(&game_units, &unit_positions));
+
+            self.enemy_density.clear();
+            if settings.show_minimap {
+                let spatial_grid = acquire_lock(&game_data.spatial_hash_grid, "spatial_hash_grid");
+                collect_enemy_density(&game_units, &unit_positions, &spatial_grid, hidden_by_fog, &mut self.enemy_density);
+            }
+        }
+
+        if let Some(visibility) = hidden_by_fog {
+            self.units.retain(|unit| unit.object_type != ObjectType::Enemy || visibility.is_position_visible(unit.position));
         }
 
+        self.camera_state = acquire_lock(&game_data.camera_state, "camera_state").clone();
+
+        self.damage_numbers.clear();
+        self.damage_numbers.extend(acquire_lock(&game_data.damage_numbers, "damage_numbers").iter().filter(|damage_number| damage_number.spawn_time.elapsed() < DAMAGE_NUMBER_DURATION).cloned());
+
+        self.chain_arcs.clear();
+        self.chain_arcs.extend(acquire_lock(&game_data.chain_arcs, "chain_arcs").iter().filter(|chain_arc| chain_arc.spawn_time.elapsed() < CHAIN_ARC_DURATION).cloned());
+
+        self.game_map = acquire_lock(&game_data.game_map, "game_map").clone();
 
+        self.flow_field = if settings.show_flow_field {
+            acquire_lock(&game_data.flow_field, "flow_field").clone()
+        } else {
+            None
+        };
     }
-}
\ No newline at end of file
+}
+
+pub fn collect_units_to_render(units: &UnitStore, unit_positions: &[Pos2FixedPoint], out: &mut Vec<UnitToRender>) {
+    out.clear();
+    out.extend(units.ids().filter_map(|id| {
+        let index = id as usize;
+        let animation = units.animations[index]?;
+        let health = units.health[index];
+
+        Some(UnitToRender {
+            object_type: units.object_types[index]?,
+            position: *unit_positions.get(index)?,
+            animation,
+            health_current: health.current,
+            health_max: health.max,
+            direction: units.attack_stats[index].as_ref().map(|stats| stats.direction),
+            tint: status_tint(&units.status_effects[index])
+                .or_else(|| elite_tint(&units.affixes[index]))
+                .or_else(|| units.attack_stats[index].as_ref().and_then(|stats| stats.behaviour_tint()))
+                .unwrap_or(Color32::WHITE),
+        })
+    }));
+}
+
+// Enemies the fog hides aren't counted
+fn collect_enemy_density(units: &UnitStore, unit_positions: &[Pos2FixedPoint], spatial_grid: &SpatialHashGrid, visibility: Option<&VisibilityMap>, out: &mut Vec<(Pos2FixedPoint, u32)>) {
+    out.extend(spatial_grid.grid.iter().filter_map(|(&cell, unit_ids)| {
+        let count = unit_ids
+            .iter()
+            .filter(|&&id| units.object_type(id) == Some(ObjectType::Enemy))
+            .filter(|&&id| visibility.is_none_or(|visibility| unit_positions.get(id as usize).is_some_and(|&position| visibility.is_position_visible(position))))
+            .count() as u32;
+
+        (count > 0).then(|| (cell_center(cell), count))
+    }));
+}
@@ -0,0 +1,15 @@
+use crate::game::maths::pos_2::Pos2FixedPoint;
+use crate::game::objects::animation::Animation;
+use crate::game::objects::object_type::ObjectType;
+use egui::Color32;
+
+#[derive(Clone, Copy)]
+pub struct UnitToRender {
+    pub object_type: ObjectType,
+    pub position: Pos2FixedPoint,
+    pub animation: Animation,
+    pub health_current: f32,
+    pub health_max: f32,
+    pub direction: Option<(f32, f32)>,
+    pub tint: Color32,
+}
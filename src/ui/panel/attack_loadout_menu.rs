@@ -0,0 +1,67 @@
+use crate::game::data::attack_loadout::{AttackLoadout, LoadoutSlot, ABILITY_KEYS, ABILITY_SLOTS, AUTO_CAST_SLOTS};
+use crate::game::data::game_data::GameData;
+use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::ui::asset::loader::DP_COMIC_FONT;
+use eframe::egui::{Color32, ComboBox, Rect, Ui, Vec2};
+use eframe::emath::Align;
+use egui::{FontFamily, FontId, Layout, Pos2, RichText, StrokeKind, UiBuilder};
+
+// Chosen in the lair and used by the next adventure
+pub fn show_attack_loadout(ui: &mut Ui, game_data: &GameData, game_rect: Rect) {
+    let panel_size = Vec2::new(280.0, 90.0 + 32.0 * (AUTO_CAST_SLOTS + ABILITY_SLOTS) as f32);
+    let panel_rect = Rect::from_min_size(Pos2::new(game_rect.max.x - panel_size.x - 20.0, game_rect.min.y + 20.0), panel_size);
+
+    let painter = ui.painter();
+    painter.rect_filled(panel_rect, 10.0, Color32::from_rgb(65, 35, 10));
+    painter.rect_stroke(panel_rect, 10.0, (2.0, Color32::from_rgb(128, 0, 128)), StrokeKind::Inside);
+
+    let loadout = acquire_lock(&game_data.player_data, "player_data").attack_loadout.clone();
+    let heading = |text: &str| RichText::new(text).font(FontId::new(26.0, FontFamily::Name(DP_COMIC_FONT.into()))).color(Color32::GOLD);
+
+    ui.allocate_new_ui(
+        UiBuilder::new()
+            .max_rect(panel_rect.shrink(12.0))
+            .layout(Layout::top_down(Align::Min)),
+        |ui| {
+            ui.label(heading("Auto Cast"));
+            for index in 0..AUTO_CAST_SLOTS {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Slot {}:", index + 1));
+                    slot_combo_box(ui, game_data, &loadout, LoadoutSlot::AutoCast(index));
+                });
+            }
+
+            ui.add_space(8.0);
+            ui.label(heading("Abilities"));
+            for (index, key) in ABILITY_KEYS.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", key));
+                    slot_combo_box(ui, game_data, &loadout, LoadoutSlot::Ability(index));
+                });
+            }
+        },
+    );
+}
+
+fn slot_combo_box(ui: &mut Ui, game_data: &GameData, loadout: &AttackLoadout, slot: LoadoutSlot) {
+    let selected = loadout.slot(slot);
+    let mut chosen = None;
+
+    ComboBox::from_id_salt(("attack_loadout", format!("{:?}", slot)))
+        .width(160.0)
+        .selected_text(selected.map_or("Empty".to_string(), |attack_name| format!("{:?}", attack_name)))
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(selected.is_none(), "Empty").clicked() {
+                chosen = Some(None);
+            }
+            for attack_name in AttackLoadout::selectable_attacks() {
+                if ui.selectable_label(selected == Some(attack_name), format!("{:?}", attack_name)).clicked() {
+                    chosen = Some(Some(attack_name));
+                }
+            }
+        });
+
+    if let Some(attack) = chosen {
+        acquire_lock_mut(&game_data.player_data, "player_data").attack_loadout.assign(slot, attack);
+    }
+}
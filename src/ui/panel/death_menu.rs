@@ -1,13 +1,16 @@
 use crate::enums::gamestate::GameState;
 use crate::game::data::game_data::GameData;
+use crate::helper::lock_helper::acquire_lock;
+use crate::ui::asset::loader::DP_COMIC_FONT;
 use crate::ui::component::widget::custom_button::CustomButton;
 use crate::ui::component::widget::custom_heading::CustomHeading;
+use crate::ui::component::widget::label_no_interact::LabelNoInteract;
 use eframe::egui::{Color32, Rect, Ui, Vec2};
 use eframe::emath::Align;
 use egui::{Layout, Stroke, StrokeKind, UiBuilder};
 
 pub fn show_death_menu(ui: &mut Ui, game_data: &GameData, game_rect: Rect) {
-    let menu_rect = Rect::from_center_size(game_rect.center(), Vec2::new(320.0, 270.0));
+    let menu_rect = Rect::from_center_size(game_rect.center(), Vec2::new(320.0, 395.0));
     let painter = ui.painter();
     painter.rect_filled(menu_rect, 10.0, Color32::from_rgb(20, 20, 20));
     painter.rect_stroke(menu_rect, 10.0, Stroke::new(1.5, Color32::WHITE), StrokeKind::Inside); // optional border
@@ -17,6 +20,15 @@ pub fn show_death_menu(ui: &mut Ui, game_data: &GameData, game_rect: Rect) {
         ("Quit", GameState::Quitting),
     ];
 
+    let run_stats = acquire_lock(&game_data.run_stats, "run_stats").clone();
+    let stat_lines = [
+        format!("Enemies killed: {}", run_stats.enemies_killed),
+        format!("Bosses defeated: {}", run_stats.bosses_defeated),
+        format!("Damage dealt: {:.0}", run_stats.damage_dealt),
+        format!("Damage taken: {:.0}", run_stats.damage_taken),
+        format!("Gold collected: {:.0}", run_stats.gold_collected),
+    ];
+
     let mut heading = CustomHeading::new("DEFEAT!!");
     heading.font_size = 60.0;
     heading.font_colour = Color32::RED;
@@ -32,6 +44,11 @@ pub fn show_death_menu(ui: &mut Ui, game_data: &GameData, game_rect: Rect) {
                 ui.separator();
                 ui.add_space(10.0);
 
+                for line in &stat_lines {
+                    ui.add(LabelNoInteract::new(line, DP_COMIC_FONT.to_string(), 18.0, Color32::WHITE));
+                }
+                ui.add_space(10.0);
+
                 for (text, state) in buttons {
                     ui.add(CustomButton::new(
                         None,
@@ -1,4 +1,5 @@
 use crate::enums::gamestate::GameState;
+use crate::game::data::adventure_definition::AdventureName;
 use crate::game::data::game_data::GameData;
 use crate::game::data::initialise_adventure::initialise_adventure;
 use crate::ui::component::widget::custom_button::CustomButton;
@@ -12,15 +13,16 @@ pub fn show_begin_adventure(ui: &mut Ui, game_data: &GameData, game_rect: Rect)
         game_rect.center().x - button_size.x / 2.0,
         game_rect.max.y - button_size.y - 70.0,
     );
-    let button_rect = Rect::from_min_size(bottom_center, button_size);
+    // The arena button sits above the main one
+    let button_rect = Rect::from_min_max(bottom_center - Vec2::new(0.0, button_size.y + 10.0), bottom_center + button_size);
 
-    let begin_adventure_button = CustomButton::new(
+    let adventure_button = |label: &'static str, adventure_name: AdventureName| CustomButton::new(
         None,
-        Some("Begin Adventure"),
+        Some(label),
         Box::new({
             let game_data = game_data.clone();
             move || {
-                initialise_adventure(&game_data);
+                initialise_adventure(&game_data, adventure_name);
                 game_data.set_game_state(GameState::Playing);
             }
         }),
@@ -32,8 +34,9 @@ pub fn show_begin_adventure(ui: &mut Ui, game_data: &GameData, game_rect: Rect)
             .layout(Layout::top_down_justified(Align::Min)),
         |ui| {
             ui.vertical_centered(|ui| {
+                ui.add(adventure_button("Ember Arena", AdventureName::EmberArena));
                 ui.add_space(10.0);
-                ui.add(begin_adventure_button);
+                ui.add(adventure_button("Begin Adventure", AdventureName::DragonValley));
             });
         },
     );
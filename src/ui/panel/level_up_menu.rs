@@ -0,0 +1,54 @@
+use crate::game::data::game_data::GameData;
+use crate::game::data::player_level::choose_level_up;
+use crate::helper::lock_helper::acquire_lock;
+use crate::ui::asset::loader::DP_COMIC_FONT;
+use crate::ui::component::widget::custom_button::CustomButton;
+use crate::ui::component::widget::custom_heading::CustomHeading;
+use eframe::egui::{Color32, Rect, Ui, Vec2};
+use eframe::emath::Align;
+use egui::{FontFamily, FontId, Layout, RichText, StrokeKind, UiBuilder};
+
+pub fn show_level_up_menu(ui: &mut Ui, game_data: &GameData, game_rect: Rect) {
+    let (level, choices) = {
+        let player_level = acquire_lock(&game_data.player_level, "player_level");
+        (player_level.level, player_level.choices.clone())
+    };
+
+    let menu_rect = Rect::from_center_size(game_rect.center(), Vec2::new(480.0, 140.0 + 110.0 * choices.len() as f32));
+    let painter = ui.painter();
+    painter.rect_filled(menu_rect, 10.0, Color32::from_rgba_unmultiplied(20, 20, 20, 235));
+    painter.rect_stroke(menu_rect, 10.0, (2.0, Color32::from_rgb(128, 0, 128)), StrokeKind::Inside);
+
+    let titles: Vec<String> = choices.iter().map(|choice| choice.title()).collect();
+
+    ui.allocate_new_ui(
+        UiBuilder::new()
+            .max_rect(menu_rect)
+            .layout(Layout::top_down_justified(Align::Min)),
+        |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(20.0);
+                ui.add(CustomHeading::new(&format!("Level {}", level)));
+                ui.separator();
+                ui.add_space(10.0);
+
+                for (index, (choice, title)) in choices.iter().zip(&titles).enumerate() {
+                    ui.add(
+                        CustomButton::new(
+                            None,
+                            Some(title),
+                            Box::new(move || choose_level_up(game_data, index)),
+                        )
+                        .with_size(Vec2::new(420.0, 50.0)),
+                    );
+                    ui.label(
+                        RichText::new(choice.description())
+                            .font(FontId::new(24.0, FontFamily::Name(DP_COMIC_FONT.into())))
+                            .color(Color32::LIGHT_GRAY),
+                    );
+                    ui.add_space(20.0);
+                }
+            });
+        },
+    );
+}
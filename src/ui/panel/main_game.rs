@@ -1,31 +1,43 @@
 use crate::enums::gamestate::GameState;
 use crate::game::data::game_data::GameData;
+use crate::enums::minimapcorner::MinimapCorner;
 use crate::game::data::stored_data::SETTINGS;
 use crate::helper::lock_helper::{acquire_lock, acquire_lock_mut};
+use crate::helper::logging::UI;
+use crate::helper::triple_buffer::TripleBufferReader;
 use crate::ui::asset::loader::{COIN_IMAGE, DP_COMIC_FONT, DRAGONS_LAIR_IMAGE, DRAGON_IMAGE, FOOD_IMAGE, RUBY_IMAGE};
+use crate::ui::component::widget::attack_hotbar::AttackHotbar;
 use crate::ui::component::widget::custom_heading::CustomHeading;
 use crate::ui::component::widget::custom_progress_bar::CustomProgressBar;
 use crate::ui::component::widget::game_graphics::GameGraphics;
+use crate::ui::component::widget::label_no_interact::LabelNoInteract;
 use crate::ui::component::widget::lair_object::get_lair_object;
+use crate::ui::component::widget::minimap::Minimap;
+use crate::game::loops::unit_spawner::BossStatus;
+use crate::ui::graphics::rendering_data::RenderData;
+use crate::ui::panel::attack_loadout_menu::show_attack_loadout;
 use crate::ui::panel::death_menu::show_death_menu;
 use crate::ui::panel::game_menu_lair::show_begin_adventure;
 use crate::ui::panel::game_menu_paused::show_game_menu_paused;
+use crate::ui::panel::level_up_menu::show_level_up_menu;
 use eframe::{egui, Frame};
 use egui::{Align, Color32, FontFamily, FontId, Image, Layout, Pos2, Rect, RichText, ScrollArea, StrokeKind, Ui, UiBuilder, Vec2};
 use std::process::exit;
 use std::sync::{Arc, OnceLock};
 use uuid::Uuid;
+use tracing::debug;
 
 static GAME_GRAPHICS_ID: OnceLock<Uuid> = OnceLock::new();
 static RESOURCE_HUD_ID: OnceLock<Uuid> = OnceLock::new();
 
-pub fn show_main_game(ui: &mut Ui, game_data: Arc<GameData>, frame: &mut Frame) {
+pub fn show_main_game(ui: &mut Ui, game_data: Arc<GameData>, frame: &mut Frame, render_snapshot: &mut TripleBufferReader<RenderData>) {
     let game_state = acquire_lock(&game_data.game_state, "game_state").clone();
 
     match game_state {
         GameState::Lair => handle_game_state_lair(ui, &game_data),
-        GameState::Playing => handle_game_state_playing(ui, &game_data, frame),
+        GameState::Playing => handle_game_state_playing(ui, &game_data, frame, render_snapshot.read()),
         GameState::Paused => handle_game_state_paused(ui, &game_data),
+        GameState::LevelUp => handle_game_state_level_up(ui, &game_data, frame, render_snapshot.read()),
         GameState::Dead => handle_game_state_dead(ui, &game_data),
         GameState::Quitting => handle_game_state_quitting(),
     }
@@ -43,15 +55,16 @@ fn handle_game_state_lair(ui: &mut Ui, game_data: &GameData) {
     let hud_pos = Pos2::new(game_rect.min.x + 20.0, game_rect.min.y + 20.0);
     let hud_rect = Rect::from_min_size(hud_pos, hud_size);
     show_begin_adventure(ui, game_data, game_rect);
+    show_attack_loadout(ui, game_data, game_rect);
     draw_resource_hud_lair(ui, game_data, hud_rect);
 }
 
-fn handle_game_state_playing(ui: &mut Ui, game_data: &Arc<GameData>, frame: &mut Frame) {
+fn handle_game_state_playing(ui: &mut Ui, game_data: &Arc<GameData>, frame: &mut Frame, render_data: &RenderData) {
     ui.add(CustomHeading::new("Adventure Mode"));
     ui.separator();
     let game_rect = ui.available_rect_before_wrap();
 
-    ui.put(game_rect, GameGraphics::new(Arc::clone(game_data), frame));
+    ui.put(game_rect, GameGraphics::new(Arc::clone(game_data), frame, render_data));
 
     let (hud_rect, progress_rect) = get_hud_rects(&game_rect);
 
@@ -64,9 +77,94 @@ fn handle_game_state_playing(ui: &mut Ui, game_data: &Arc<GameData>, frame: &mut
             progress_rect,
             CustomProgressBar::new(food_value, 100.0)
                 .show_percentage()
-                .set_on_click(Box::new(|| println!("Progress Bar Clicked"))),
+                .set_on_click(Box::new(|| debug!(target: UI, "Progress Bar Clicked"))),
         );
     }
+
+    draw_exp_bar(ui, game_data, &progress_rect);
+    draw_hotbar(ui, game_data, &game_rect, &progress_rect);
+
+    if let Some(boss) = &render_data.boss {
+        draw_boss_health_bar(ui, boss, &game_rect);
+    }
+
+    let settings = game_data.get_field(SETTINGS).unwrap_or_default();
+    if settings.show_minimap {
+        ui.put(get_minimap_rect(&game_rect, &hud_rect, settings.minimap_corner, settings.minimap_size), Minimap::new(render_data, &settings));
+    }
+}
+
+// The top left corner sits below the resource HUD
+fn get_minimap_rect(game_rect: &Rect, hud_rect: &Rect, corner: MinimapCorner, size: f32) -> Rect {
+    let margin = 20.0;
+    let size = size.min(game_rect.width() - margin * 2.0).min(game_rect.height() - margin * 2.0).max(0.0);
+
+    let minimap_pos = match corner {
+        MinimapCorner::TopLeft => Pos2::new(game_rect.min.x + margin, hud_rect.max.y + margin),
+        MinimapCorner::TopRight => Pos2::new(game_rect.max.x - margin - size, game_rect.min.y + margin),
+        MinimapCorner::BottomLeft => Pos2::new(game_rect.min.x + margin, game_rect.max.y - margin - size),
+        MinimapCorner::BottomRight => Pos2::new(game_rect.max.x - margin - size, game_rect.max.y - margin - size),
+    };
+
+    Rect::from_min_size(minimap_pos, Vec2::splat(size))
+}
+
+// The run stays frozen underneath while a choice is made
+fn handle_game_state_level_up(ui: &mut Ui, game_data: &Arc<GameData>, frame: &mut Frame, render_data: &RenderData) {
+    ui.add(CustomHeading::new("Adventure Mode"));
+    ui.separator();
+    let game_rect = ui.available_rect_before_wrap();
+
+    ui.put(game_rect, GameGraphics::new(Arc::clone(game_data), frame, render_data));
+
+    let (hud_rect, progress_rect) = get_hud_rects(&game_rect);
+    draw_resource_hud_active(ui, game_data, hud_rect);
+    draw_exp_bar(ui, game_data, &progress_rect);
+
+    show_level_up_menu(ui, game_data, game_rect);
+}
+
+// Sits just above the food bar
+fn draw_exp_bar(ui: &mut Ui, game_data: &GameData, progress_rect: &Rect) {
+    let total_exp = acquire_lock(&game_data.resource_amounts, "resource_amounts").exp.unwrap_or(0.0);
+    let (level, progress) = {
+        let player_level = acquire_lock(&game_data.player_level, "player_level");
+        (player_level.level, player_level.progress(total_exp))
+    };
+
+    let bar_rect = Rect::from_min_size(Pos2::new(progress_rect.min.x, progress_rect.min.y - 24.0), Vec2::new(progress_rect.width(), 16.0));
+    let label_rect = Rect::from_min_size(Pos2::new(bar_rect.min.x - 90.0, bar_rect.min.y - 6.0), Vec2::new(85.0, 28.0));
+
+    ui.put(label_rect, LabelNoInteract::new(&format!("Lv {}", level), DP_COMIC_FONT.to_string(), 24.0, Color32::LIGHT_BLUE));
+    ui.put(bar_rect, CustomProgressBar::new(progress * 100.0, 100.0));
+}
+
+// Sits above the exp bar
+fn draw_hotbar(ui: &mut Ui, game_data: &GameData, game_rect: &Rect, progress_rect: &Rect) {
+    let hotbar = acquire_lock(&game_data.hotbar, "hotbar").clone();
+    let slot_size = 56.0;
+    let hotbar_rect = Rect::from_min_size(
+        Pos2::new(game_rect.min.x, progress_rect.min.y - 34.0 - slot_size),
+        Vec2::new(game_rect.width(), slot_size),
+    );
+
+    ui.put(hotbar_rect, AttackHotbar::new(&hotbar));
+}
+
+fn draw_boss_health_bar(ui: &mut Ui, boss: &BossStatus, game_rect: &Rect) {
+    let bar_size = Vec2::new(500.0, 26.0);
+    let bar_pos = Pos2::new(game_rect.center().x - bar_size.x / 2.0, game_rect.min.y + 55.0);
+    let bar_rect = Rect::from_min_size(bar_pos, bar_size);
+    let title_rect = Rect::from_min_size(Pos2::new(bar_pos.x, bar_pos.y - 32.0), Vec2::new(bar_size.x, 30.0));
+
+    let title = format!("{} - Phase {}/{}", boss.title, boss.phase, boss.phase_count);
+    ui.put(title_rect, LabelNoInteract::new(&title, DP_COMIC_FONT.to_string(), 24.0, Color32::WHITE));
+    ui.put(
+        bar_rect,
+        CustomProgressBar::new(boss.health.current.max(0.0) as f64, boss.health.max as f64)
+            .show_percentage()
+            .hide_completed(),
+    );
 }
 
 fn handle_game_state_paused(ui: &mut Ui, game_data: &GameData) {
@@ -4,4 +4,6 @@ pub mod shop;
 pub mod upgrades;
 pub mod game_menu_paused;
 pub mod game_menu_lair;
-pub mod death_menu;
\ No newline at end of file
+pub mod death_menu;
+pub mod level_up_menu;
+pub mod attack_loadout_menu;
\ No newline at end of file
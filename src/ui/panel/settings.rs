@@ -1,6 +1,9 @@
+use crate::enums::loglevel::LogLevel;
+use crate::enums::minimapcorner::MinimapCorner;
 use crate::enums::numberformatmode::NumberFormatMode;
 use crate::game::data::game_data::GameData;
 use crate::game::data::stored_data::SETTINGS;
+use crate::helper::logging::set_log_level;
 use crate::ui::component::widget::custom_heading::CustomHeading;
 use eframe::egui;
 use eframe::egui::{Align, ComboBox, Layout, Slider};
@@ -9,6 +12,8 @@ use uuid::Uuid;
 
 static COMBOBOX_ID: OnceLock<Uuid> = OnceLock::new();
 static RESOLUTION_ID: OnceLock<Uuid> = OnceLock::new();
+static LOG_LEVEL_ID: OnceLock<Uuid> = OnceLock::new();
+static MINIMAP_CORNER_ID: OnceLock<Uuid> = OnceLock::new();
 
 pub fn show_settings_panel(ui: &mut egui::Ui, game_data: &GameData) {
     ui.add(CustomHeading::new("Settings Panel"));
@@ -16,6 +21,8 @@ pub fn show_settings_panel(ui: &mut egui::Ui, game_data: &GameData) {
 
     let combobox_id = *COMBOBOX_ID.get_or_init(Uuid::new_v4);
     let resolution_id = *RESOLUTION_ID.get_or_init(Uuid::new_v4);
+    let log_level_id = *LOG_LEVEL_ID.get_or_init(Uuid::new_v4);
+    let minimap_corner_id = *MINIMAP_CORNER_ID.get_or_init(Uuid::new_v4);
 
     ui.with_layout(Layout::top_down(Align::Min), |ui| {
         let settings = game_data.get_field(SETTINGS).unwrap_or_default();
@@ -88,5 +95,76 @@ pub fn show_settings_panel(ui: &mut egui::Ui, game_data: &GameData) {
                 game_data.set_field(SETTINGS, updated_settings);
             }
         });
+
+        ui.horizontal(|ui| {
+            ui.label("Flow Field Overlay:");
+            let mut show_flow_field = settings.show_flow_field;
+            if ui.checkbox(&mut show_flow_field, "Enabled").changed() {
+                let mut updated_settings = settings;
+                updated_settings.show_flow_field = show_flow_field;
+                game_data.set_field(SETTINGS, updated_settings);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Minimap:");
+            let mut show_minimap = settings.show_minimap;
+            if ui.checkbox(&mut show_minimap, "Enabled").changed() {
+                let mut updated_settings = settings;
+                updated_settings.show_minimap = show_minimap;
+                game_data.set_field(SETTINGS, updated_settings);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Minimap Size:");
+            let mut minimap_size = settings.minimap_size;
+            if ui.add(Slider::new(&mut minimap_size, 120.0..=360.0).text("Points")).changed() {
+                let mut updated_settings = settings;
+                updated_settings.minimap_size = minimap_size;
+                game_data.set_field(SETTINGS, updated_settings);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Minimap Zoom:");
+            let mut minimap_zoom = settings.minimap_zoom;
+            if ui.add(Slider::new(&mut minimap_zoom, 1.0..=8.0).text("x")).changed() {
+                let mut updated_settings = settings;
+                updated_settings.minimap_zoom = minimap_zoom;
+                game_data.set_field(SETTINGS, updated_settings);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Minimap Corner:");
+            ComboBox::from_id_salt(minimap_corner_id)
+                .selected_text(format!("{:?}", settings.minimap_corner))
+                .show_ui(ui, |ui| {
+                    for minimap_corner in MinimapCorner::ALL {
+                        if ui.selectable_label(settings.minimap_corner == minimap_corner, format!("{:?}", minimap_corner)).clicked() {
+                            let mut updated_settings = settings;
+                            updated_settings.minimap_corner = minimap_corner;
+                            game_data.set_field(SETTINGS, updated_settings);
+                        }
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Log Level:");
+            ComboBox::from_id_salt(log_level_id)
+                .selected_text(format!("{:?}", settings.log_level))
+                .show_ui(ui, |ui| {
+                    for log_level in LogLevel::ALL {
+                        if ui.selectable_label(settings.log_level == log_level, format!("{:?}", log_level)).clicked() {
+                            let mut updated_settings = settings;
+                            updated_settings.log_level = log_level;
+                            game_data.set_field(SETTINGS, updated_settings);
+                            set_log_level(log_level);
+                        }
+                    }
+                });
+        });
     });
 }
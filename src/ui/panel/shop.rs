@@ -1,9 +1,11 @@
 use crate::game::data::game_data::GameData;
 use crate::helper::lock_helper::acquire_lock;
+use crate::helper::logging::UI;
 use crate::ui::component::widget::custom_button::CustomButton;
 use crate::ui::component::widget::custom_heading::CustomHeading;
 use eframe::egui;
 use egui::Vec2;
+use tracing::debug;
 use crate::ui::asset::loader::EXIT_IMAGE;
 
 pub fn show_shop(ui: &mut egui::Ui, game_data: &GameData) {
@@ -21,7 +23,7 @@ pub fn show_shop(ui: &mut egui::Ui, game_data: &GameData) {
                 Some(icon.clone()),
                 Some("Buy 1,000 Gold ($4.99)"),
                 Box::new(|| {
-                println!("Button clicked by: {}", user_name)
+                debug!(target: UI, "Button clicked by: {}", user_name)
                 // initiate_purchase(steam_client.clone(), "gold_pack_1000");
             }))
                 .with_size(Vec2::new(300.0, 50.0))
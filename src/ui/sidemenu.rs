@@ -1,3 +1,5 @@
+use crate::helper::logging::UI;
+use tracing::warn;
 use crate::enums::gametab::GameTab;
 use crate::game::data::game_data::GameData;
 use crate::game::data::stored_data::CURRENT_TAB;
@@ -33,7 +35,7 @@ pub fn show_side_menu(ctx: &Context, game_data: &GameData) {
                     ));
                     ui.separator();
                 } else {
-                    eprintln!("Warning: Icon '{}' not found!", icon_name);
+                    warn!(target: UI, "Icon '{}' not found!", icon_name);
                 }
             }
 
@@ -1,12 +1,15 @@
 use kira::sound::static_sound::StaticSoundData;
 use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle, StreamingSoundSettings};
-use kira::sound::{FromFileError, PlaybackState};
+use crate::game::events::event_bus::EventBatch;
+use crate::game::events::game_event::GameEvent;
+use crate::helper::logging::AUDIO;
+use kira::sound::FromFileError;
 use kira::{AudioManager, AudioManagerSettings};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::io::Cursor;
 use std::process::exit;
-use std::thread::sleep;
-use std::time::Duration;
+use std::sync::mpsc::Receiver;
+use tracing::error;
 
 pub const TRACK_01: &str = "01_midnight_wander";
 pub const TRACK_02: &str = "02_chilled_guitar";
@@ -41,7 +44,7 @@ pub struct KiraAudio {
 impl KiraAudio {
     pub fn new() -> Self {
         let mut manager = AudioManager::new(AudioManagerSettings::default()).unwrap_or_else(|e| {
-            eprintln!("Failed to initialize audio manager: {}", e);
+            error!(target: AUDIO, "Failed to initialize audio manager: {}", e);
             exit(1);
         });
 
@@ -49,7 +52,7 @@ impl KiraAudio {
         let mut sound_effects = FxHashMap::default();
         for (name, bytes) in SOUND_EFFECTS {
             let sound_data = StaticSoundData::from_cursor(Cursor::new(bytes)).unwrap_or_else(|e| {
-                eprintln!("Failed to load static sound effect {}: {}", name, e);
+                error!(target: AUDIO, "Failed to load static sound effect {}: {}", name, e);
                 exit(1);
             });
             sound_effects.insert(name.to_string(), sound_data);
@@ -60,7 +63,7 @@ impl KiraAudio {
         for (name, bytes) in SOUNDTRACKS {
             let stream_data = StreamingSoundData::from_cursor(Cursor::new(bytes))
                 .unwrap_or_else(|e| {
-                    eprintln!("Failed to load streaming soundtrack {}: {}", name, e);
+                    error!(target: AUDIO, "Failed to load streaming soundtrack {}: {}", name, e);
                     exit(1);
                 })
                 .with_settings(StreamingSoundSettings::new().loop_region(..));
@@ -79,25 +82,46 @@ impl KiraAudio {
     pub fn play_sound(&mut self, name: &str) {
         if let Some(sound_data) = self.sound_effects.get(name) {
             if let Err(e) = self.audio_manager.play(sound_data.clone()) {
-                eprintln!("Failed to play sound '{}': {}", name, e);
+                error!(target: AUDIO, "Failed to play sound '{}': {}", name, e);
             }
         } else {
-            eprintln!("Sound effect '{}' not found!", name);
+            error!(target: AUDIO, "Sound effect '{}' not found!", name);
         }
     }
 
     pub fn play_soundtrack_looping(&mut self) {
         if let Some(track) = self.soundtracks.remove(TRACK_02) {
-            println!("Trying to play");
-            let handle = self.audio_manager.play(track).expect("TODO: panic message");
-            println!("Played");
+            match self.audio_manager.play(track) {
+                Ok(handle) => self.current_soundtrack = Some(handle),
+                Err(e) => error!(target: AUDIO, "Failed to play soundtrack '{}': {}", TRACK_02, e),
+            }
+        } else {
+            error!(target: AUDIO, "Soundtrack '{}' not found!", TRACK_02);
+        }
+    }
 
-            while handle.state() == PlaybackState::Playing {
-                sleep(Duration::from_millis(100));
+    // Runs until the event bus drops this subscriber
+    pub fn listen(&mut self, events: Receiver<EventBatch>) {
+        self.play_soundtrack_looping();
+
+        while let Ok(batch) = events.recv() {
+            let mut sounds_to_play = FxHashSet::default();
+
+            for event in batch.iter() {
+                match event {
+                    GameEvent::UnitDied { sound: Some(sound), .. } if !sound.name.is_empty() => {
+                        sounds_to_play.insert(sound.name.as_str());
+                    }
+                    GameEvent::LootCollected { .. } => {
+                        sounds_to_play.insert(SOUND_01);
+                    }
+                    _ => {}
+                }
             }
 
-        } else {
-            eprintln!("Soundtrack '{}' not found!", TRACK_02);
+            for sound in sounds_to_play {
+                self.play_sound(sound);
+            }
         }
     }
 }
\ No newline at end of file
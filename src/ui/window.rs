@@ -3,8 +3,11 @@ use crate::game::constants::{FRAME_RATE, GAME_NAME};
 use crate::game::data::game_data::GameData;
 use crate::game::data::stored_data::{CURRENT_TAB, GAME_IN_FOCUS, SETTINGS};
 use crate::game::settings::Settings;
+use crate::helper::logging::RENDER;
+use crate::helper::triple_buffer::TripleBufferReader;
 use crate::ui::asset::loader::{load_icons, load_icons_inverted, register_custom_font};
 use crate::ui::graphics::offscreen_renderer::OffscreenRenderer;
+use crate::ui::graphics::rendering_data::RenderData;
 use crate::ui::panel::main_game::show_main_game;
 use crate::ui::panel::settings::show_settings_panel;
 use crate::ui::panel::shop::show_shop;
@@ -17,6 +20,7 @@ use glow::HasContext;
 use std::sync::Arc;
 use std::thread::spawn;
 use std::time::{Duration, Instant};
+use tracing::debug;
 
 pub const BACKGROUND_COLOUR: Color32 = Color32::from_rgb(5, 5, 5);
 
@@ -24,10 +28,11 @@ pub struct MyAppWindow {
     game_data: Arc<GameData>,
     gl_ctx: Arc<glow::Context>,
     last_update: Instant,
+    render_snapshot: TripleBufferReader<RenderData>,
 }
 
 impl MyAppWindow {
-    pub fn new(game_data: Arc<GameData>, ctx: Context, gl: Arc<glow::Context>) -> Self {
+    pub fn new(game_data: Arc<GameData>, ctx: Context, gl: Arc<glow::Context>, render_snapshot: TripleBufferReader<RenderData>) -> Self {
         {
             let mut offscreen_renderer = game_data.offscreen_renderer.write().unwrap();
             let width = game_data.get_field(SETTINGS).unwrap().window_width;
@@ -57,6 +62,7 @@ impl MyAppWindow {
             game_data,
             gl_ctx: gl,
             last_update: Instant::now(),
+            render_snapshot,
         }
     }
 }
@@ -84,7 +90,7 @@ impl App for MyAppWindow {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.with_layout(Layout::top_down(Align::Center), |ui| {
                 match current_tab {
-                    GameTab::Adventure => show_main_game(ui, Arc::clone(&self.game_data), _frame),
+                    GameTab::Adventure => show_main_game(ui, Arc::clone(&self.game_data), _frame, &mut self.render_snapshot),
                     GameTab::Settings => show_settings_panel(ui, &self.game_data),
                     GameTab::Shop => show_shop(ui, &self.game_data),
                     GameTab::Upgrades => show_upgrades(ui, &self.game_data),
@@ -100,13 +106,13 @@ impl Drop for MyAppWindow {
         if let Some(shader) = self.game_data.rect_shader.write().unwrap().take() {
             unsafe {
                 self.gl_ctx.delete_program(shader);
-                println!("✅ Deleted rectangle shader program when MyAppWindow was dropped.");
+                debug!(target: RENDER, "Deleted rectangle shader program when MyAppWindow was dropped.");
             }
         }
         if let Some(shader) = self.game_data.sprite_shader.write().unwrap().take() {
             unsafe {
                 self.gl_ctx.delete_program(shader);
-                println!("✅ Deleted sprite shader program when MyAppWindow was dropped.");
+                debug!(target: RENDER, "Deleted sprite shader program when MyAppWindow was dropped.");
             }
         }
     }
@@ -127,7 +133,7 @@ fn set_window_size(ctx: &Context, settings: &Settings) {
     }
 }
 
-pub fn create_window(game_data: Arc<GameData>) -> eframe::Result {
+pub fn create_window(game_data: Arc<GameData>, render_snapshot: TripleBufferReader<RenderData>) -> eframe::Result {
     let settings = game_data.get_field(SETTINGS).unwrap_or_default();
     let options = NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -143,9 +149,9 @@ pub fn create_window(game_data: Arc<GameData>) -> eframe::Result {
     eframe::run_native(
         GAME_NAME,
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             if let Some(gl_ctx) = cc.gl.clone() {
-                Ok(Box::new(MyAppWindow::new(Arc::clone(&game_data), cc.egui_ctx.clone(), gl_ctx)) as Box<dyn App>)
+                Ok(Box::new(MyAppWindow::new(Arc::clone(&game_data), cc.egui_ctx.clone(), gl_ctx, render_snapshot)) as Box<dyn App>)
             } else {
                 Err("Failed to get OpenGL context".into())
             }